@@ -0,0 +1,81 @@
+CREATE TABLE IF NOT EXISTS webhooks
+(
+    id VARCHAR(36) PRIMARY KEY NOT NULL,
+    url TEXT NOT NULL,
+    request_body TEXT NOT NULL,
+    description TEXT NOT NULL,
+    created_at DATETIME(6) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS job_done_watchers
+(
+    id VARCHAR(36) PRIMARY KEY NOT NULL,
+    job_name VARCHAR(253) NOT NULL,
+    timeout_seconds BIGINT NOT NULL DEFAULT 0,
+    status VARCHAR(32) NOT NULL,
+    created_at DATETIME(6) NOT NULL,
+    INDEX watchers_job_name_and_status_idx (job_name, status),
+    INDEX watchers_job_status_idx (status)
+);
+
+CREATE TABLE IF NOT EXISTS job_done_trigger_webhooks
+(
+    id VARCHAR(36) PRIMARY KEY NOT NULL,
+    webhook_id VARCHAR(36) NOT NULL,
+    job_done_watcher_id VARCHAR(36) NOT NULL,
+    timeout_seconds BIGINT NOT NULL DEFAULT 0,
+    status VARCHAR(32) NOT NULL,
+    called_at DATETIME(6) DEFAULT NULL,
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id),
+    FOREIGN KEY (job_done_watcher_id) REFERENCES job_done_watchers(id)
+);
+
+CREATE TRIGGER job_done_trigger_webhooks_timeout_trigger
+    AFTER UPDATE
+    ON job_done_watchers
+    FOR EACH ROW
+    UPDATE job_done_trigger_webhooks
+    SET status = 'Timeout'
+    WHERE job_done_trigger_webhooks.job_done_watcher_id = NEW.id
+      AND NEW.status = 'Timeout';
+
+CREATE TABLE IF NOT EXISTS job_watcher_family
+(
+    id VARCHAR(36) PRIMARY KEY NOT NULL,
+    job_family VARCHAR(253) NOT NULL,
+    url TEXT NOT NULL,
+    request_body TEXT NOT NULL,
+    description TEXT NOT NULL,
+    on_outcomes VARCHAR(64) NOT NULL DEFAULT 'succeeded',
+    min_duration_seconds BIGINT DEFAULT NULL,
+    consecutive_failures BIGINT DEFAULT NULL,
+    on_state_change BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at DATETIME(6) NOT NULL,
+    INDEX job_watcher_family_idx (job_family)
+);
+
+CREATE TABLE IF NOT EXISTS job_family_states
+(
+    job_family VARCHAR(253) PRIMARY KEY NOT NULL,
+    last_outcome VARCHAR(32) NOT NULL,
+    consecutive_failures BIGINT NOT NULL DEFAULT 0,
+    updated_at DATETIME(6) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS job_family_deliveries
+(
+    id VARCHAR(36) PRIMARY KEY NOT NULL,
+    job_family_watcher_id VARCHAR(36) NOT NULL,
+    job_family VARCHAR(253) NOT NULL,
+    job_name VARCHAR(253) NOT NULL,
+    job_outcome VARCHAR(32) NOT NULL,
+    status VARCHAR(32) NOT NULL,
+    attempts BIGINT NOT NULL DEFAULT 0,
+    response_status_code BIGINT DEFAULT NULL,
+    error TEXT DEFAULT NULL,
+    created_at DATETIME(6) NOT NULL,
+    last_attempt_at DATETIME(6) DEFAULT NULL,
+    FOREIGN KEY (job_family_watcher_id) REFERENCES job_watcher_family(id),
+    INDEX job_family_deliveries_job_family_and_created_at_idx (job_family, created_at),
+    INDEX job_family_deliveries_created_at_idx (created_at)
+);
@@ -0,0 +1,7 @@
+ALTER TABLE job_done_watchers ADD COLUMN namespace VARCHAR(63) DEFAULT NULL;
+
+CREATE INDEX watchers_created_at_idx
+ON job_done_watchers (created_at, id);
+
+CREATE INDEX webhooks_created_at_idx
+ON webhooks (created_at, id);
\ No newline at end of file
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS job_done_trigger_webhook_attempts
+(
+    id VARCHAR(36) PRIMARY KEY NOT NULL,
+    job_done_watcher_id VARCHAR(36) NOT NULL,
+    job_done_trigger_webhook_id VARCHAR(36) NOT NULL,
+    status VARCHAR(32) NOT NULL,
+    response_status_code BIGINT DEFAULT NULL,
+    error TEXT DEFAULT NULL,
+    attempted_at DATETIME(6) NOT NULL,
+    FOREIGN KEY (job_done_watcher_id) REFERENCES job_done_watchers(id),
+    FOREIGN KEY (job_done_trigger_webhook_id) REFERENCES job_done_trigger_webhooks(id),
+    INDEX job_done_trigger_webhook_attempts_job_done_watcher_id_idx (job_done_watcher_id, attempted_at)
+);
\ No newline at end of file
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS dead_letters
+(
+    id VARCHAR(36) PRIMARY KEY NOT NULL,
+    job_done_watcher_id VARCHAR(36) NOT NULL,
+    job_done_trigger_webhook_id VARCHAR(36) NOT NULL UNIQUE,
+    webhook_id VARCHAR(36) NOT NULL,
+    job_name VARCHAR(253) NOT NULL,
+    namespace VARCHAR(63) DEFAULT NULL,
+    request_method VARCHAR(16) DEFAULT NULL,
+    request_url TEXT DEFAULT NULL,
+    request_headers TEXT DEFAULT NULL,
+    request_body TEXT DEFAULT NULL,
+    response_status_code BIGINT DEFAULT NULL,
+    error TEXT DEFAULT NULL,
+    attempts BIGINT NOT NULL,
+    created_at DATETIME(6) NOT NULL,
+    FOREIGN KEY (job_done_watcher_id) REFERENCES job_done_watchers(id),
+    FOREIGN KEY (job_done_trigger_webhook_id) REFERENCES job_done_trigger_webhooks(id),
+    INDEX dead_letters_created_at_idx (created_at, id)
+);
\ No newline at end of file
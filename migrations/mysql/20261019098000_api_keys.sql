@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS api_keys
+(
+    id VARCHAR(36) PRIMARY KEY NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    scopes VARCHAR(64) NOT NULL,
+    created_at DATETIME(6) NOT NULL
+);
\ No newline at end of file
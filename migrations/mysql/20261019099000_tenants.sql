@@ -0,0 +1,11 @@
+ALTER TABLE webhooks ADD COLUMN tenant VARCHAR(63) DEFAULT NULL;
+ALTER TABLE job_done_watchers ADD COLUMN tenant VARCHAR(63) DEFAULT NULL;
+ALTER TABLE job_watcher_family ADD COLUMN tenant VARCHAR(63) DEFAULT NULL;
+ALTER TABLE job_family_deliveries ADD COLUMN tenant VARCHAR(63) DEFAULT NULL;
+ALTER TABLE api_keys ADD COLUMN tenant VARCHAR(63) DEFAULT NULL;
+
+CREATE INDEX webhooks_tenant_idx
+ON webhooks (tenant);
+
+CREATE INDEX watchers_tenant_idx
+ON job_done_watchers (tenant);
\ No newline at end of file
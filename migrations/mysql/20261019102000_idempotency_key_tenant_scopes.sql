@@ -0,0 +1 @@
+ALTER TABLE idempotency_keys MODIFY scope VARCHAR(128) NOT NULL;
\ No newline at end of file
@@ -0,0 +1,23 @@
+ALTER TABLE job_watcher_family ADD COLUMN name VARCHAR(253) NOT NULL DEFAULT '';
+
+-- The config file used to be inserted again on every start, only the oldest copy of each job family is kept.
+UPDATE job_watcher_family
+JOIN (
+    SELECT watcher.id
+    FROM job_watcher_family AS watcher
+    LEFT JOIN job_watcher_family AS older
+        ON older.job_family = watcher.job_family
+       AND (older.created_at < watcher.created_at
+            OR (older.created_at = watcher.created_at AND older.id < watcher.id))
+    WHERE older.id IS NULL
+) AS oldest ON oldest.id = job_watcher_family.id
+SET job_watcher_family.name = job_watcher_family.job_family;
+
+UPDATE job_family_deliveries
+JOIN job_watcher_family AS duplicate ON duplicate.id = job_family_deliveries.job_family_watcher_id AND duplicate.name = ''
+JOIN job_watcher_family AS kept ON kept.name = duplicate.job_family
+SET job_family_deliveries.job_family_watcher_id = kept.id;
+
+DELETE FROM job_watcher_family WHERE name = '';
+
+CREATE UNIQUE INDEX job_watcher_family_name_idx ON job_watcher_family (name);
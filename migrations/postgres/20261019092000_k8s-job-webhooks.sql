@@ -0,0 +1,98 @@
+CREATE TABLE IF NOT EXISTS webhooks
+(
+    id VARCHAR PRIMARY KEY NOT NULL,
+    url VARCHAR NOT NULL,
+    request_body VARCHAR NOT NULL,
+    description TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS job_done_watchers
+(
+    id VARCHAR PRIMARY KEY NOT NULL,
+    job_name VARCHAR NOT NULL,
+    timeout_seconds BIGINT NOT NULL DEFAULT 0,
+    status VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS job_done_trigger_webhooks
+(
+    id VARCHAR PRIMARY KEY NOT NULL,
+    webhook_id VARCHAR NOT NULL REFERENCES webhooks(id),
+    job_done_watcher_id VARCHAR NOT NULL REFERENCES job_done_watchers(id),
+    timeout_seconds BIGINT NOT NULL DEFAULT 0,
+    status VARCHAR NOT NULL,
+    called_at TIMESTAMPTZ DEFAULT NULL
+);
+
+CREATE OR REPLACE FUNCTION job_done_trigger_webhooks_timeout() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE job_done_trigger_webhooks
+    SET status = 'Timeout'
+    WHERE job_done_trigger_webhooks.job_done_watcher_id = NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER job_done_trigger_webhooks_timeout_trigger
+    AFTER UPDATE OF status
+    ON job_done_watchers
+    FOR EACH ROW
+    WHEN (NEW.status = 'Timeout')
+    EXECUTE FUNCTION job_done_trigger_webhooks_timeout();
+
+CREATE TABLE IF NOT EXISTS job_watcher_family
+(
+    id VARCHAR PRIMARY KEY NOT NULL,
+    job_family VARCHAR NOT NULL,
+    url VARCHAR NOT NULL,
+    request_body VARCHAR NOT NULL,
+    description TEXT NOT NULL,
+    on_outcomes VARCHAR NOT NULL DEFAULT 'succeeded',
+    min_duration_seconds BIGINT DEFAULT NULL,
+    consecutive_failures BIGINT DEFAULT NULL,
+    on_state_change BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS job_family_states
+(
+    job_family VARCHAR PRIMARY KEY NOT NULL,
+    last_outcome VARCHAR NOT NULL,
+    consecutive_failures BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS job_family_deliveries
+(
+    id VARCHAR PRIMARY KEY NOT NULL,
+    job_family_watcher_id VARCHAR NOT NULL REFERENCES job_watcher_family(id),
+    job_family VARCHAR NOT NULL,
+    job_name VARCHAR NOT NULL,
+    job_outcome VARCHAR NOT NULL,
+    status VARCHAR NOT NULL,
+    attempts BIGINT NOT NULL DEFAULT 0,
+    response_status_code BIGINT DEFAULT NULL,
+    error TEXT DEFAULT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    last_attempt_at TIMESTAMPTZ DEFAULT NULL
+);
+
+CREATE INDEX IF NOT EXISTS job_done_trigger_webhooks_job_done_watcher_id_idx
+ON job_done_trigger_webhooks (job_done_watcher_id);
+
+CREATE INDEX IF NOT EXISTS watchers_job_name_and_status_idx
+ON job_done_watchers (job_name, status);
+
+CREATE INDEX IF NOT EXISTS watchers_job_status_idx
+ON job_done_watchers (status);
+
+CREATE INDEX IF NOT EXISTS job_watcher_family_idx
+ON job_watcher_family (job_family);
+
+CREATE INDEX IF NOT EXISTS job_family_deliveries_job_family_and_created_at_idx
+ON job_family_deliveries (job_family, created_at);
+
+CREATE INDEX IF NOT EXISTS job_family_deliveries_created_at_idx
+ON job_family_deliveries (created_at);
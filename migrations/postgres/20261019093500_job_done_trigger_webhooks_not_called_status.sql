@@ -0,0 +1,2 @@
+-- Statuses were stored as "Not Called" before the status names lost their spaces.
+UPDATE job_done_trigger_webhooks SET status = 'NotCalled' WHERE status = 'Not Called';
\ No newline at end of file
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS job_done_trigger_webhook_attempts
+(
+    id VARCHAR PRIMARY KEY NOT NULL,
+    job_done_watcher_id VARCHAR NOT NULL REFERENCES job_done_watchers(id),
+    job_done_trigger_webhook_id VARCHAR NOT NULL REFERENCES job_done_trigger_webhooks(id),
+    status VARCHAR NOT NULL,
+    response_status_code BIGINT DEFAULT NULL,
+    error TEXT DEFAULT NULL,
+    attempted_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS job_done_trigger_webhook_attempts_job_done_watcher_id_idx
+ON job_done_trigger_webhook_attempts (job_done_watcher_id, attempted_at);
\ No newline at end of file
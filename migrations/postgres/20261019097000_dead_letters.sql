@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS dead_letters
+(
+    id VARCHAR PRIMARY KEY NOT NULL,
+    job_done_watcher_id VARCHAR NOT NULL REFERENCES job_done_watchers(id),
+    job_done_trigger_webhook_id VARCHAR NOT NULL UNIQUE REFERENCES job_done_trigger_webhooks(id),
+    webhook_id VARCHAR NOT NULL,
+    job_name VARCHAR NOT NULL,
+    namespace VARCHAR DEFAULT NULL,
+    request_method VARCHAR DEFAULT NULL,
+    request_url VARCHAR DEFAULT NULL,
+    request_headers TEXT DEFAULT NULL,
+    request_body TEXT DEFAULT NULL,
+    response_status_code BIGINT DEFAULT NULL,
+    error TEXT DEFAULT NULL,
+    attempts BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS dead_letters_created_at_idx
+ON dead_letters (created_at, id);
\ No newline at end of file
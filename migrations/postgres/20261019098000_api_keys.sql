@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS api_keys
+(
+    id VARCHAR PRIMARY KEY NOT NULL,
+    name VARCHAR NOT NULL,
+    key_hash VARCHAR NOT NULL UNIQUE,
+    scopes VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
\ No newline at end of file
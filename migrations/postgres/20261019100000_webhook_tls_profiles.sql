@@ -0,0 +1 @@
+ALTER TABLE webhooks ADD COLUMN tls_profile VARCHAR DEFAULT NULL;
\ No newline at end of file
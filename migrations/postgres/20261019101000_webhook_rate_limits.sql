@@ -0,0 +1 @@
+ALTER TABLE webhooks ADD COLUMN rate_limit_per_second DOUBLE PRECISION DEFAULT NULL;
\ No newline at end of file
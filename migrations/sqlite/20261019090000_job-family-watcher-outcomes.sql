@@ -0,0 +1,12 @@
+ALTER TABLE job_watcher_family ADD COLUMN on_outcomes VARCHAR NOT NULL DEFAULT 'succeeded';
+ALTER TABLE job_watcher_family ADD COLUMN min_duration_seconds INTEGER DEFAULT NULL;
+ALTER TABLE job_watcher_family ADD COLUMN consecutive_failures INTEGER DEFAULT NULL;
+ALTER TABLE job_watcher_family ADD COLUMN on_state_change BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS job_family_states
+(
+    job_family VARCHAR PRIMARY KEY NOT NULL,
+    last_outcome VARCHAR NOT NULL,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    updated_at DATETIME NOT NULL
+);
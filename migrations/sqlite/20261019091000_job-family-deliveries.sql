@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS job_family_deliveries
+(
+    id VARCHAR PRIMARY KEY NOT NULL,
+    job_family_watcher_id VARCHAR NOT NULL,
+    job_family VARCHAR NOT NULL,
+    job_name VARCHAR NOT NULL,
+    job_outcome VARCHAR NOT NULL,
+    status VARCHAR NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    response_status_code INTEGER DEFAULT NULL,
+    error TEXT DEFAULT NULL,
+    created_at DATETIME NOT NULL,
+    last_attempt_at DATETIME DEFAULT NULL,
+    FOREIGN KEY(job_family_watcher_id) REFERENCES job_watcher_family(id)
+);
+
+CREATE INDEX IF NOT EXISTS job_family_deliveries_job_family_and_created_at_idx
+ON job_family_deliveries (job_family, created_at);
+
+CREATE INDEX IF NOT EXISTS job_family_deliveries_created_at_idx
+ON job_family_deliveries (created_at);
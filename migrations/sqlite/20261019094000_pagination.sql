@@ -0,0 +1,7 @@
+ALTER TABLE job_done_watchers ADD COLUMN namespace VARCHAR DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS watchers_created_at_idx
+ON job_done_watchers (created_at, id);
+
+CREATE INDEX IF NOT EXISTS webhooks_created_at_idx
+ON webhooks (created_at, id);
\ No newline at end of file
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS idempotency_keys
+(
+    scope VARCHAR NOT NULL,
+    idempotency_key VARCHAR NOT NULL,
+    fingerprint VARCHAR NOT NULL,
+    resource_id VARCHAR DEFAULT NULL,
+    created_at DATETIME NOT NULL,
+    expires_at DATETIME NOT NULL,
+    PRIMARY KEY (scope, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idempotency_keys_expires_at_idx
+ON idempotency_keys (expires_at);
\ No newline at end of file
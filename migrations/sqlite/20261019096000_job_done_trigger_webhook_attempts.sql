@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS job_done_trigger_webhook_attempts
+(
+    id VARCHAR PRIMARY KEY NOT NULL,
+    job_done_watcher_id VARCHAR NOT NULL,
+    job_done_trigger_webhook_id VARCHAR NOT NULL,
+    status VARCHAR NOT NULL,
+    response_status_code INTEGER DEFAULT NULL,
+    error TEXT DEFAULT NULL,
+    attempted_at DATETIME NOT NULL,
+    FOREIGN KEY(job_done_watcher_id) REFERENCES job_done_watchers(id),
+    FOREIGN KEY(job_done_trigger_webhook_id) REFERENCES job_done_trigger_webhooks(id)
+);
+
+CREATE INDEX IF NOT EXISTS job_done_trigger_webhook_attempts_job_done_watcher_id_idx
+ON job_done_trigger_webhook_attempts (job_done_watcher_id, attempted_at);
\ No newline at end of file
@@ -0,0 +1,8 @@
+ALTER TABLE job_done_trigger_webhook_attempts ADD COLUMN request_method VARCHAR DEFAULT NULL;
+ALTER TABLE job_done_trigger_webhook_attempts ADD COLUMN request_url VARCHAR DEFAULT NULL;
+ALTER TABLE job_done_trigger_webhook_attempts ADD COLUMN request_headers TEXT DEFAULT NULL;
+ALTER TABLE job_done_trigger_webhook_attempts ADD COLUMN request_body TEXT DEFAULT NULL;
+ALTER TABLE job_done_trigger_webhook_attempts ADD COLUMN response_headers TEXT DEFAULT NULL;
+ALTER TABLE job_done_trigger_webhook_attempts ADD COLUMN response_body TEXT DEFAULT NULL;
+ALTER TABLE dead_letters ADD COLUMN response_headers TEXT DEFAULT NULL;
+ALTER TABLE dead_letters ADD COLUMN response_body TEXT DEFAULT NULL;
\ No newline at end of file
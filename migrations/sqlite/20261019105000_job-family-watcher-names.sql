@@ -0,0 +1,21 @@
+ALTER TABLE job_watcher_family ADD COLUMN name VARCHAR NOT NULL DEFAULT '';
+
+-- The config file used to be inserted again on every start, only the oldest copy of each job family is kept.
+UPDATE job_watcher_family
+SET name = job_family
+WHERE NOT EXISTS (
+    SELECT 1
+    FROM job_watcher_family AS older
+    WHERE older.job_family = job_watcher_family.job_family
+      AND (older.created_at < job_watcher_family.created_at
+           OR (older.created_at = job_watcher_family.created_at AND older.id < job_watcher_family.id))
+);
+
+UPDATE job_family_deliveries
+SET job_family_watcher_id = (SELECT kept.id FROM job_watcher_family AS kept WHERE kept.name = job_family_deliveries.job_family)
+WHERE job_family_watcher_id IN (SELECT id FROM job_watcher_family WHERE name = '');
+
+DELETE FROM job_watcher_family WHERE name = '';
+
+CREATE UNIQUE INDEX IF NOT EXISTS job_watcher_family_name_idx
+ON job_watcher_family (name);
@@ -0,0 +1,3 @@
+SELECT COUNT(*)
+FROM job_done_watchers
+WHERE status = ? AND created_at < ?
\ No newline at end of file
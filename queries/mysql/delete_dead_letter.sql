@@ -0,0 +1,2 @@
+DELETE FROM dead_letters
+WHERE id = ?
\ No newline at end of file
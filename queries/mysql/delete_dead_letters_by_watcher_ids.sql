@@ -0,0 +1,2 @@
+DELETE FROM dead_letters
+WHERE job_done_watcher_id IN
\ No newline at end of file
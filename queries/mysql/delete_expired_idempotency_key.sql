@@ -0,0 +1,2 @@
+DELETE FROM idempotency_keys
+WHERE scope = ? AND idempotency_key = ? AND (expires_at <= ? OR (resource_id IS NULL AND created_at < ?))
\ No newline at end of file
@@ -0,0 +1,4 @@
+DELETE FROM idempotency_keys
+WHERE expires_at <= ?
+ORDER BY expires_at
+LIMIT ?
\ No newline at end of file
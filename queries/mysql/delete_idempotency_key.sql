@@ -0,0 +1,2 @@
+DELETE FROM idempotency_keys
+WHERE scope = ? AND idempotency_key = ? AND created_at = ? AND resource_id IS NULL
\ No newline at end of file
@@ -0,0 +1,2 @@
+DELETE FROM job_done_trigger_webhook_attempts
+WHERE job_done_watcher_id IN
\ No newline at end of file
@@ -0,0 +1,4 @@
+DELETE FROM job_family_deliveries
+WHERE created_at < ?
+ORDER BY created_at, id
+LIMIT ?
\ No newline at end of file
@@ -0,0 +1,2 @@
+DELETE FROM job_done_watchers
+WHERE id IN
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT id, name, key_hash, scopes, tenant, created_at
+FROM api_keys
+ORDER BY created_at, id
\ No newline at end of file
@@ -0,0 +1,38 @@
+SELECT
+    id,
+    job_done_watcher_id,
+    job_done_trigger_webhook_id,
+    webhook_id,
+    job_name,
+    namespace,
+    request_method,
+    request_url,
+    request_headers,
+    request_body,
+    response_status_code,
+    error,
+    response_headers,
+    response_body,
+    attempts,
+    created_at
+FROM dead_letters
+WHERE
+    (? IS NULL OR webhook_id = ?)
+AND
+    (? IS NULL OR job_name = ?)
+AND
+    (? IS NULL OR created_at >= ?)
+AND
+    (? IS NULL OR created_at <= ?)
+AND
+    (? IS NULL OR job_done_watcher_id IN (SELECT job_done_watchers.id FROM job_done_watchers WHERE job_done_watchers.tenant = ?))
+AND
+    (? IS NULL
+    OR (? AND (created_at, id) < (?, ?))
+    OR (NOT ? AND (created_at, id) > (?, ?)))
+ORDER BY
+    CASE WHEN ? THEN created_at END DESC,
+    CASE WHEN ? THEN id END DESC,
+    created_at,
+    id
+LIMIT ?
\ No newline at end of file
@@ -0,0 +1,23 @@
+SELECT
+    id,
+    job_family_watcher_id,
+    job_family,
+    job_name,
+    job_outcome,
+    status,
+    attempts,
+    response_status_code,
+    error,
+    tenant,
+    created_at,
+    last_attempt_at
+FROM job_family_deliveries
+WHERE
+    (? IS NULL OR job_family = ?)
+AND
+    (? IS NULL OR created_at >= ?)
+AND
+    (? IS NULL OR created_at <= ?)
+AND
+    (? IS NULL OR tenant = ?)
+ORDER BY created_at DESC
\ No newline at end of file
@@ -0,0 +1,48 @@
+SELECT
+    job_done_watchers.id,
+    job_done_watchers.job_name,
+    job_done_watchers.timeout_seconds,
+    job_done_watchers.status,
+    job_done_watchers.namespace,
+    job_done_watchers.tenant,
+    job_done_watchers.created_at,
+    CAST(IF(COUNT(job_done_trigger_webhooks.id) = 0, JSON_ARRAY(), JSON_ARRAYAGG(JSON_OBJECT(
+        'id', job_done_trigger_webhooks.id,
+        'webhook_id', job_done_trigger_webhooks.webhook_id,
+        'timeout_seconds', job_done_trigger_webhooks.timeout_seconds,
+        'status', job_done_trigger_webhooks.status,
+        'called_at', DATE_FORMAT(job_done_trigger_webhooks.called_at, '%Y-%m-%dT%H:%i:%s.%fZ')))) AS CHAR) AS job_done_trigger_webhooks
+FROM
+    job_done_watchers
+LEFT JOIN
+    job_done_trigger_webhooks ON job_done_watchers.id = job_done_trigger_webhooks.job_done_watcher_id
+WHERE
+    (? IS NULL OR job_done_watchers.job_name = ?)
+AND
+    (? IS NULL OR job_done_watchers.status = ?)
+AND
+    (? IS NULL OR job_done_watchers.namespace = ?)
+AND
+    (? IS NULL OR EXISTS (
+        SELECT 1
+        FROM job_done_trigger_webhooks AS filtered_job_done_trigger_webhooks
+        WHERE filtered_job_done_trigger_webhooks.job_done_watcher_id = job_done_watchers.id
+        AND filtered_job_done_trigger_webhooks.webhook_id = ?))
+AND
+    (? IS NULL OR job_done_watchers.created_at >= ?)
+AND
+    (? IS NULL OR job_done_watchers.created_at <= ?)
+AND
+    (? IS NULL OR job_done_watchers.tenant = ?)
+AND
+    (? IS NULL
+    OR (? AND (job_done_watchers.created_at, job_done_watchers.id) < (?, ?))
+    OR (NOT ? AND (job_done_watchers.created_at, job_done_watchers.id) > (?, ?)))
+GROUP BY
+    job_done_watchers.id, job_done_watchers.job_name, job_done_watchers.timeout_seconds, job_done_watchers.status, job_done_watchers.namespace, job_done_watchers.tenant, job_done_watchers.created_at
+ORDER BY
+    CASE WHEN ? THEN job_done_watchers.created_at END DESC,
+    CASE WHEN ? THEN job_done_watchers.id END DESC,
+    job_done_watchers.created_at,
+    job_done_watchers.id
+LIMIT ?
\ No newline at end of file
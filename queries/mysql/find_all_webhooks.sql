@@ -0,0 +1,18 @@
+SELECT id, url, request_body, description, tenant, tls_profile, rate_limit_per_second, created_at
+FROM webhooks
+WHERE
+    (? IS NULL OR created_at >= ?)
+AND
+    (? IS NULL OR created_at <= ?)
+AND
+    (? IS NULL OR tenant = ?)
+AND
+    (? IS NULL
+    OR (? AND (created_at, id) < (?, ?))
+    OR (NOT ? AND (created_at, id) > (?, ?)))
+ORDER BY
+    CASE WHEN ? THEN created_at END DESC,
+    CASE WHEN ? THEN id END DESC,
+    created_at,
+    id
+LIMIT ?
\ No newline at end of file
@@ -0,0 +1,19 @@
+SELECT
+    id,
+    job_done_watcher_id,
+    job_done_trigger_webhook_id,
+    webhook_id,
+    job_name,
+    namespace,
+    request_method,
+    request_url,
+    request_headers,
+    request_body,
+    response_status_code,
+    error,
+    response_headers,
+    response_body,
+    attempts,
+    created_at
+FROM dead_letters
+WHERE job_done_trigger_webhook_id = ?
\ No newline at end of file
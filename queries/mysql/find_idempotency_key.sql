@@ -0,0 +1,3 @@
+SELECT scope, idempotency_key, fingerprint, resource_id, created_at, expires_at
+FROM idempotency_keys
+WHERE scope = ? AND idempotency_key = ?
\ No newline at end of file
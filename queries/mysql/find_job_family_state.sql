@@ -0,0 +1,3 @@
+SELECT job_family, last_outcome, consecutive_failures, updated_at
+FROM job_family_states
+WHERE job_family = ?
\ No newline at end of file
@@ -0,0 +1,5 @@
+SELECT id
+FROM job_done_watchers
+WHERE job_done_watchers.job_name = ? AND job_done_watchers.status = ?
+AND (job_done_watchers.namespace IS NULL OR job_done_watchers.namespace = ?)
+FOR UPDATE
\ No newline at end of file
@@ -0,0 +1,6 @@
+SELECT id
+FROM job_done_watchers
+WHERE status = ? AND created_at < ?
+ORDER BY created_at, id
+LIMIT ?
+FOR UPDATE
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT id, url, request_body, description, tenant, tls_profile, rate_limit_per_second, created_at
+FROM webhooks
+WHERE id = ?
\ No newline at end of file
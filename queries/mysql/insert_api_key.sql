@@ -0,0 +1,2 @@
+INSERT INTO api_keys ( id, name, key_hash, scopes, created_at, tenant )
+VALUES ( ?, ?, ?, ?, ?, ? )
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO idempotency_keys ( scope, idempotency_key, fingerprint, resource_id, created_at, expires_at )
+VALUES ( ?, ?, ?, ?, ?, ? )
\ No newline at end of file
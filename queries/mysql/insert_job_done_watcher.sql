@@ -0,0 +1,2 @@
+INSERT INTO job_done_watchers ( id, job_name, timeout_seconds, status, namespace, created_at, tenant )
+VALUES ( ?, ?, ?, ?, ?, ?, ? )
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT IGNORE INTO job_family_states ( job_family, last_outcome, consecutive_failures, updated_at )
+VALUES ( ?, ?, ?, ? )
\ No newline at end of file
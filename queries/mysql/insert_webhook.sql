@@ -0,0 +1,2 @@
+INSERT INTO webhooks ( id, url, request_body, description, created_at, tenant, tls_profile, rate_limit_per_second )
+VALUES ( ?, ?, ?, ?, ?, ?, ?, ? )
\ No newline at end of file
@@ -0,0 +1,3 @@
+UPDATE idempotency_keys
+SET resource_id = ?
+WHERE scope = ? AND idempotency_key = ? AND created_at = ? AND resource_id IS NULL
\ No newline at end of file
@@ -0,0 +1,6 @@
+UPDATE job_done_trigger_webhooks
+SET status = ?, called_at = ?
+WHERE
+    job_done_trigger_webhooks.id = ?
+AND
+    job_done_trigger_webhooks.job_done_watcher_id = ?
\ No newline at end of file
@@ -0,0 +1,3 @@
+UPDATE job_family_deliveries
+SET status = ?, attempts = ?, response_status_code = ?, error = ?, last_attempt_at = ?
+WHERE job_family_deliveries.id = ?
\ No newline at end of file
@@ -0,0 +1,7 @@
+UPDATE job_family_states
+SET last_outcome = ?,
+    consecutive_failures = ?,
+    updated_at = ?
+WHERE job_family = ?
+  AND last_outcome = ?
+  AND consecutive_failures = ?
\ No newline at end of file
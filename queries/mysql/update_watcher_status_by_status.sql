@@ -0,0 +1,3 @@
+UPDATE job_done_watchers
+SET status = ?
+WHERE job_done_watchers.id = ? AND job_done_watchers.status = ?
\ No newline at end of file
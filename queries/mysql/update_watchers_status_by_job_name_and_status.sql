@@ -0,0 +1,4 @@
+UPDATE job_done_watchers
+SET status = ?
+WHERE job_done_watchers.job_name = ? AND job_done_watchers.status = ?
+AND (job_done_watchers.namespace IS NULL OR job_done_watchers.namespace = ?)
\ No newline at end of file
@@ -0,0 +1,12 @@
+INSERT INTO dead_letters ( id, job_done_watcher_id, job_done_trigger_webhook_id, webhook_id, job_name, namespace, request_method, request_url, request_headers, request_body, response_status_code, error, response_headers, response_body, attempts, created_at )
+VALUES ( ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ? )
+ON DUPLICATE KEY UPDATE
+    request_method = VALUES(request_method),
+    request_url = VALUES(request_url),
+    request_headers = VALUES(request_headers),
+    request_body = VALUES(request_body),
+    response_status_code = VALUES(response_status_code),
+    error = VALUES(error),
+    response_headers = VALUES(response_headers),
+    response_body = VALUES(response_body),
+    attempts = VALUES(attempts)
\ No newline at end of file
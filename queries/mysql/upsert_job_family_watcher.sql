@@ -0,0 +1,12 @@
+INSERT INTO job_watcher_family ( id, name, job_family, url, request_body, description, on_outcomes, min_duration_seconds, consecutive_failures, on_state_change, created_at, tenant )
+VALUES ( ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ? )
+ON DUPLICATE KEY UPDATE
+    job_family = VALUES(job_family),
+    url = VALUES(url),
+    request_body = VALUES(request_body),
+    description = VALUES(description),
+    on_outcomes = VALUES(on_outcomes),
+    min_duration_seconds = VALUES(min_duration_seconds),
+    consecutive_failures = VALUES(consecutive_failures),
+    on_state_change = VALUES(on_state_change),
+    tenant = VALUES(tenant)
\ No newline at end of file
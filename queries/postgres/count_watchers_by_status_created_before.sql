@@ -0,0 +1,3 @@
+SELECT COUNT(*)
+FROM job_done_watchers
+WHERE status = $1 AND created_at < $2
\ No newline at end of file
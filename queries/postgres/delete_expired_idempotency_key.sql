@@ -0,0 +1,2 @@
+DELETE FROM idempotency_keys
+WHERE scope = $1 AND idempotency_key = $2 AND (expires_at <= $3 OR (resource_id IS NULL AND created_at < $4))
\ No newline at end of file
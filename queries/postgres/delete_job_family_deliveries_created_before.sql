@@ -0,0 +1,8 @@
+DELETE FROM job_family_deliveries
+WHERE id IN (
+    SELECT id
+    FROM job_family_deliveries
+    WHERE created_at < $1
+    ORDER BY created_at, id
+    LIMIT $2
+)
\ No newline at end of file
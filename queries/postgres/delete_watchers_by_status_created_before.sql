@@ -0,0 +1,19 @@
+WITH expired_job_done_watchers AS (
+    SELECT id
+    FROM job_done_watchers
+    WHERE status = $1 AND created_at < $2
+    ORDER BY created_at, id
+    LIMIT $3
+    FOR UPDATE
+), deleted_dead_letters AS (
+    DELETE FROM dead_letters
+    WHERE job_done_watcher_id IN (SELECT id FROM expired_job_done_watchers)
+), deleted_job_done_trigger_webhook_attempts AS (
+    DELETE FROM job_done_trigger_webhook_attempts
+    WHERE job_done_watcher_id IN (SELECT id FROM expired_job_done_watchers)
+), deleted_job_done_trigger_webhooks AS (
+    DELETE FROM job_done_trigger_webhooks
+    WHERE job_done_watcher_id IN (SELECT id FROM expired_job_done_watchers)
+)
+DELETE FROM job_done_watchers
+WHERE id IN (SELECT id FROM expired_job_done_watchers)
\ No newline at end of file
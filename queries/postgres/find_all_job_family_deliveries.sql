@@ -0,0 +1,23 @@
+SELECT
+    id,
+    job_family_watcher_id,
+    job_family,
+    job_name,
+    job_outcome,
+    status,
+    attempts,
+    response_status_code,
+    error,
+    tenant,
+    created_at,
+    last_attempt_at
+FROM job_family_deliveries
+WHERE
+    ($1::VARCHAR IS NULL OR job_family = $1)
+AND
+    ($2::TIMESTAMPTZ IS NULL OR created_at >= $2)
+AND
+    ($3::TIMESTAMPTZ IS NULL OR created_at <= $3)
+AND
+    ($4::VARCHAR IS NULL OR tenant = $4)
+ORDER BY created_at DESC
\ No newline at end of file
@@ -0,0 +1,15 @@
+SELECT
+    id,
+    name,
+    job_family,
+    url,
+    request_body,
+    description,
+    on_outcomes,
+    min_duration_seconds,
+    consecutive_failures,
+    on_state_change,
+    tenant,
+    created_at
+FROM job_watcher_family
+WHERE job_family = $1
\ No newline at end of file
@@ -0,0 +1,23 @@
+SELECT
+    job_done_watchers.id,
+    job_done_watchers.job_name,
+    job_done_watchers.timeout_seconds,
+    job_done_watchers.status,
+    job_done_watchers.namespace,
+    job_done_watchers.tenant,
+    job_done_watchers.created_at,
+    coalesce(json_agg(json_build_object(
+        'id', job_done_trigger_webhooks.id,
+        'webhook_id', job_done_trigger_webhooks.webhook_id,
+        'timeout_seconds', job_done_trigger_webhooks.timeout_seconds,
+        'status', job_done_trigger_webhooks.status,
+        'called_at', job_done_trigger_webhooks.called_at))
+        FILTER (WHERE job_done_trigger_webhooks.id IS NOT NULL), '[]')::text AS job_done_trigger_webhooks
+FROM
+    job_done_watchers
+LEFT JOIN
+    job_done_trigger_webhooks ON job_done_watchers.id = job_done_trigger_webhooks.job_done_watcher_id
+WHERE
+    job_done_watchers.job_name = $1 AND job_done_watchers.status = $2
+GROUP BY
+    job_done_watchers.id
\ No newline at end of file
@@ -0,0 +1,18 @@
+SELECT id, url, request_body, description, tenant, tls_profile, rate_limit_per_second, created_at
+FROM webhooks
+WHERE
+    ($1::TIMESTAMPTZ IS NULL OR created_at >= $1)
+AND
+    ($2::TIMESTAMPTZ IS NULL OR created_at <= $2)
+AND
+    ($7::VARCHAR IS NULL OR tenant = $7)
+AND
+    ($3::TIMESTAMPTZ IS NULL
+    OR ($5::BOOLEAN AND (created_at, id) < ($3, $4))
+    OR (NOT $5::BOOLEAN AND (created_at, id) > ($3, $4)))
+ORDER BY
+    CASE WHEN $5::BOOLEAN THEN created_at END DESC,
+    CASE WHEN $5::BOOLEAN THEN id END DESC,
+    created_at,
+    id
+LIMIT $6
\ No newline at end of file
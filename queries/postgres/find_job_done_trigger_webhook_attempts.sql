@@ -0,0 +1,17 @@
+SELECT
+    id,
+    job_done_watcher_id,
+    job_done_trigger_webhook_id,
+    status,
+    response_status_code,
+    error,
+    request_method,
+    request_url,
+    request_headers,
+    request_body,
+    response_headers,
+    response_body,
+    attempted_at
+FROM job_done_trigger_webhook_attempts
+WHERE job_done_watcher_id = $1
+ORDER BY attempted_at, id
\ No newline at end of file
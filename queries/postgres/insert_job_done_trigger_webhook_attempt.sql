@@ -0,0 +1,2 @@
+INSERT INTO job_done_trigger_webhook_attempts ( id, job_done_watcher_id, job_done_trigger_webhook_id, status, response_status_code, error, request_method, request_url, request_headers, request_body, response_headers, response_body, attempted_at )
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13 )
\ No newline at end of file
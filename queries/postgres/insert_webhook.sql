@@ -0,0 +1,2 @@
+INSERT INTO webhooks ( id, url, request_body, description, created_at, tenant, tls_profile, rate_limit_per_second )
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8 )
\ No newline at end of file
@@ -0,0 +1,6 @@
+UPDATE job_done_trigger_webhooks
+SET (status, called_at) = ($3, $4)
+WHERE
+    job_done_trigger_webhooks.id = $2
+AND
+    job_done_trigger_webhooks.job_done_watcher_id = $1
\ No newline at end of file
@@ -0,0 +1,3 @@
+UPDATE job_family_deliveries
+SET (status, attempts, response_status_code, error, last_attempt_at) = ($2, $3, $4, $5, $6)
+WHERE job_family_deliveries.id = $1
\ No newline at end of file
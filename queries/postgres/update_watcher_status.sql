@@ -0,0 +1,3 @@
+UPDATE job_done_watchers
+SET status = $2
+WHERE job_done_watchers.id = $1
\ No newline at end of file
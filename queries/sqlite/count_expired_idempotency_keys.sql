@@ -0,0 +1,3 @@
+SELECT COUNT(*) AS "count!: i64"
+FROM idempotency_keys
+WHERE expires_at <= ?1
\ No newline at end of file
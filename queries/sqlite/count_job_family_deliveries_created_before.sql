@@ -0,0 +1,3 @@
+SELECT COUNT(*) AS "count!: i64"
+FROM job_family_deliveries
+WHERE created_at < ?1
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT COUNT(*) AS "count!: i64"
+FROM sqlite_master
+WHERE type = 'table' AND name = '_sqlx_migrations'
\ No newline at end of file
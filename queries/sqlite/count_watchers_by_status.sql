@@ -0,0 +1,3 @@
+SELECT status, COUNT(*) AS "count!: i64"
+FROM job_done_watchers
+GROUP BY status
\ No newline at end of file
@@ -0,0 +1,2 @@
+DELETE FROM dead_letters
+WHERE job_done_trigger_webhook_id = ?1
\ No newline at end of file
@@ -0,0 +1,8 @@
+DELETE FROM idempotency_keys
+WHERE (scope, idempotency_key) IN (
+    SELECT scope, idempotency_key
+    FROM idempotency_keys
+    WHERE expires_at <= ?1
+    ORDER BY expires_at
+    LIMIT ?2
+)
\ No newline at end of file
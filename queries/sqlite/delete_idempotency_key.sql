@@ -0,0 +1,2 @@
+DELETE FROM idempotency_keys
+WHERE scope = ?1 AND idempotency_key = ?2 AND created_at = ?3 AND resource_id IS NULL
\ No newline at end of file
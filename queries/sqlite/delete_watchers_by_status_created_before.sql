@@ -0,0 +1,8 @@
+DELETE FROM job_done_watchers
+WHERE id IN (
+    SELECT id
+    FROM job_done_watchers
+    WHERE status = ?1 AND created_at < ?2
+    ORDER BY created_at, id
+    LIMIT ?3
+)
\ No newline at end of file
@@ -0,0 +1,38 @@
+SELECT
+    id,
+    job_done_watcher_id,
+    job_done_trigger_webhook_id,
+    webhook_id,
+    job_name,
+    namespace,
+    request_method,
+    request_url,
+    request_headers,
+    request_body,
+    response_status_code,
+    error,
+    response_headers,
+    response_body,
+    attempts,
+    created_at AS "created_at: _"
+FROM dead_letters
+WHERE
+    (?1 IS NULL OR webhook_id = ?1)
+AND
+    (?2 IS NULL OR job_name = ?2)
+AND
+    (?3 IS NULL OR created_at >= ?3)
+AND
+    (?4 IS NULL OR created_at <= ?4)
+AND
+    (?9 IS NULL OR job_done_watcher_id IN (SELECT job_done_watchers.id FROM job_done_watchers WHERE job_done_watchers.tenant = ?9))
+AND
+    (?5 IS NULL
+    OR (?7 AND (created_at, id) < (?5, ?6))
+    OR (NOT ?7 AND (created_at, id) > (?5, ?6)))
+ORDER BY
+    CASE WHEN ?7 THEN created_at END DESC,
+    CASE WHEN ?7 THEN id END DESC,
+    created_at,
+    id
+LIMIT ?8
\ No newline at end of file
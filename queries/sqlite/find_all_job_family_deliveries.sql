@@ -0,0 +1,23 @@
+SELECT
+    id,
+    job_family_watcher_id,
+    job_family,
+    job_name,
+    job_outcome,
+    status,
+    attempts,
+    response_status_code,
+    error,
+    tenant,
+    created_at AS "created_at: _",
+    last_attempt_at AS "last_attempt_at: _"
+FROM job_family_deliveries
+WHERE
+    (?1 IS NULL OR job_family = ?1)
+AND
+    (?2 IS NULL OR created_at >= ?2)
+AND
+    (?3 IS NULL OR created_at <= ?3)
+AND
+    (?4 IS NULL OR tenant = ?4)
+ORDER BY created_at DESC
\ No newline at end of file
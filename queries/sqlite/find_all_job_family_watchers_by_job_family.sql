@@ -1,3 +1,15 @@
-SELECT id, job_family, url, request_body, description, created_at AS "created_at: _"
+SELECT
+    id,
+    name,
+    job_family,
+    url,
+    request_body,
+    description,
+    on_outcomes,
+    min_duration_seconds,
+    consecutive_failures,
+    on_state_change AS "on_state_change: bool",
+    tenant,
+    created_at AS "created_at: _"
 FROM job_watcher_family
-WHERE job_family = ?1
+WHERE job_family = ?1
\ No newline at end of file
@@ -3,6 +3,8 @@ SELECT
     job_done_watchers.job_name,
     job_done_watchers.timeout_seconds,
     job_done_watchers.status,
+    job_done_watchers.namespace,
+    job_done_watchers.tenant,
     job_done_watchers.created_at AS "created_at: _",
     coalesce(json_group_array(json_object(
         'id', job_done_trigger_webhooks.id,
@@ -14,5 +16,33 @@ FROM
     job_done_watchers
 LEFT JOIN
     job_done_trigger_webhooks ON job_done_watchers.id = job_done_trigger_webhooks.job_done_watcher_id
+WHERE
+    (?1 IS NULL OR job_done_watchers.job_name = ?1)
+AND
+    (?2 IS NULL OR job_done_watchers.status = ?2)
+AND
+    (?3 IS NULL OR job_done_watchers.namespace = ?3)
+AND
+    (?4 IS NULL OR EXISTS (
+        SELECT 1
+        FROM job_done_trigger_webhooks AS filtered_job_done_trigger_webhooks
+        WHERE filtered_job_done_trigger_webhooks.job_done_watcher_id = job_done_watchers.id
+        AND filtered_job_done_trigger_webhooks.webhook_id = ?4))
+AND
+    (?5 IS NULL OR job_done_watchers.created_at >= ?5)
+AND
+    (?6 IS NULL OR job_done_watchers.created_at <= ?6)
+AND
+    (?11 IS NULL OR job_done_watchers.tenant = ?11)
+AND
+    (?7 IS NULL
+    OR (?9 AND (job_done_watchers.created_at, job_done_watchers.id) < (?7, ?8))
+    OR (NOT ?9 AND (job_done_watchers.created_at, job_done_watchers.id) > (?7, ?8)))
 GROUP BY
-    job_done_watchers.id
\ No newline at end of file
+    job_done_watchers.id
+ORDER BY
+    CASE WHEN ?9 THEN job_done_watchers.created_at END DESC,
+    CASE WHEN ?9 THEN job_done_watchers.id END DESC,
+    job_done_watchers.created_at,
+    job_done_watchers.id
+LIMIT ?10
\ No newline at end of file
@@ -1,2 +1,18 @@
-SELECT id, url, request_body, description, created_at AS "created_at: _"
-FROM webhooks
\ No newline at end of file
+SELECT id, url, request_body, description, tenant, tls_profile, rate_limit_per_second, created_at AS "created_at: _"
+FROM webhooks
+WHERE
+    (?1 IS NULL OR created_at >= ?1)
+AND
+    (?2 IS NULL OR created_at <= ?2)
+AND
+    (?7 IS NULL OR tenant = ?7)
+AND
+    (?3 IS NULL
+    OR (?5 AND (created_at, id) < (?3, ?4))
+    OR (NOT ?5 AND (created_at, id) > (?3, ?4)))
+ORDER BY
+    CASE WHEN ?5 THEN created_at END DESC,
+    CASE WHEN ?5 THEN id END DESC,
+    created_at,
+    id
+LIMIT ?6
\ No newline at end of file
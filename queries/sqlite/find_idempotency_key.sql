@@ -0,0 +1,3 @@
+SELECT scope, idempotency_key, fingerprint, resource_id, created_at AS "created_at: _", expires_at AS "expires_at: _"
+FROM idempotency_keys
+WHERE scope = ?1 AND idempotency_key = ?2
\ No newline at end of file
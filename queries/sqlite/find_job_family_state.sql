@@ -0,0 +1,3 @@
+SELECT job_family, last_outcome, consecutive_failures, updated_at AS "updated_at: _"
+FROM job_family_states
+WHERE job_family = ?1
\ No newline at end of file
@@ -3,6 +3,8 @@ SELECT
     job_done_watchers.job_name,
     job_done_watchers.timeout_seconds,
     job_done_watchers.status,
+    job_done_watchers.namespace,
+    job_done_watchers.tenant,
     job_done_watchers.created_at AS "created_at: _",
     coalesce(json_group_array(json_object(
         'id', job_done_trigger_webhooks.id,
@@ -0,0 +1,2 @@
+INSERT INTO idempotency_keys ( scope, idempotency_key, fingerprint, resource_id, created_at, expires_at )
+VALUES ( ?1, ?2, ?3, ?4, ?5, ?6 )
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO job_family_deliveries ( id, job_family_watcher_id, job_family, job_name, job_outcome, status, attempts, response_status_code, error, created_at, last_attempt_at, tenant )
+VALUES ( ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12 )
\ No newline at end of file
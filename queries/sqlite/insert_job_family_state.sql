@@ -0,0 +1,3 @@
+INSERT INTO job_family_states ( job_family, last_outcome, consecutive_failures, updated_at )
+VALUES ( ?1, ?2, ?3, ?4 )
+ON CONFLICT (job_family) DO NOTHING
\ No newline at end of file
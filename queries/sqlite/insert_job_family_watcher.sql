@@ -1,2 +0,0 @@
-INSERT INTO job_watcher_family ( id, job_family, url, request_body, description, created_at )
-VALUES ( ?1, ?2, ?3, ?4, ?5, ?6 )
\ No newline at end of file
@@ -0,0 +1,3 @@
+UPDATE idempotency_keys
+SET resource_id = ?4
+WHERE scope = ?1 AND idempotency_key = ?2 AND created_at = ?3 AND resource_id IS NULL
\ No newline at end of file
@@ -0,0 +1,7 @@
+UPDATE job_family_states
+SET last_outcome = ?1,
+    consecutive_failures = ?2,
+    updated_at = ?3
+WHERE job_family = ?4
+  AND last_outcome = ?5
+  AND consecutive_failures = ?6
\ No newline at end of file
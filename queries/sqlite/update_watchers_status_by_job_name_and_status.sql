@@ -1,4 +1,5 @@
 UPDATE job_done_watchers
 SET status = ?3
 WHERE job_done_watchers.job_name = ?1 AND job_done_watchers.status = ?2
+AND (job_done_watchers.namespace IS NULL OR job_done_watchers.namespace = ?4)
 RETURNING job_done_watchers.id
\ No newline at end of file
@@ -0,0 +1,12 @@
+INSERT INTO dead_letters ( id, job_done_watcher_id, job_done_trigger_webhook_id, webhook_id, job_name, namespace, request_method, request_url, request_headers, request_body, response_status_code, error, response_headers, response_body, attempts, created_at )
+VALUES ( ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16 )
+ON CONFLICT (job_done_trigger_webhook_id) DO UPDATE
+SET request_method = excluded.request_method,
+    request_url = excluded.request_url,
+    request_headers = excluded.request_headers,
+    request_body = excluded.request_body,
+    response_status_code = excluded.response_status_code,
+    error = excluded.error,
+    response_headers = excluded.response_headers,
+    response_body = excluded.response_body,
+    attempts = excluded.attempts
\ No newline at end of file
@@ -0,0 +1,12 @@
+INSERT INTO job_watcher_family ( id, name, job_family, url, request_body, description, on_outcomes, min_duration_seconds, consecutive_failures, on_state_change, created_at, tenant )
+VALUES ( ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12 )
+ON CONFLICT (name) DO UPDATE
+SET job_family = excluded.job_family,
+    url = excluded.url,
+    request_body = excluded.request_body,
+    description = excluded.description,
+    on_outcomes = excluded.on_outcomes,
+    min_duration_seconds = excluded.min_duration_seconds,
+    consecutive_failures = excluded.consecutive_failures,
+    on_state_change = excluded.on_state_change,
+    tenant = excluded.tenant
\ No newline at end of file
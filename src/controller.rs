@@ -1,29 +1,99 @@
-use moka::sync::Cache;
-use uuid::Uuid;
+use actix_web::{error, HttpMessage, HttpRequest, HttpResponse, ResponseError};
+use actix_web::http::StatusCode;
+use k8s_openapi::serde_json;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::models::api::{InvalidParamApi, ProblemDetailsApi};
+use crate::models::service::{IdempotencyError, MAX_IDEMPOTENCY_KEY_LENGTH, Page, Principal};
 
 
 pub mod webhooks;
 pub mod job_done_watchers;
+pub mod job_family_watchers;
+pub mod admin;
+pub mod events;
+pub mod dead_letters;
+pub mod auth;
+pub mod metrics;
+
+pub static IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+pub static NEXT_CURSOR_HEADER: &str = "X-Next-Cursor";
+pub static PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+pub fn page_response<T, A: Serialize>(page: Page<T>, to_api: impl FnMut(T) -> A) -> HttpResponse {
+    let mut http_response = HttpResponse::Ok();
+    if let Some(next_cursor) = page.next_cursor() {
+        http_response.insert_header((NEXT_CURSOR_HEADER, next_cursor.to_string()));
+    }
 
-pub static IDEMPOTENCY_KEY_HEADER: &'static str = "Idempotency-Key";
+    http_response.json(page.into_items()
+        .into_iter()
+        .map(to_api)
+        .collect::<Vec<A>>())
+}
 
-#[derive(Debug)]
-pub struct IdempotencyMap {
-    resource_id_by_idempotency_id: Cache<Uuid, Uuid>,
+pub fn problem_response(status: StatusCode, detail: Option<String>, invalid_params: Vec<InvalidParamApi>) -> HttpResponse {
+    HttpResponse::build(status)
+        .content_type(PROBLEM_JSON_CONTENT_TYPE)
+        .json(ProblemDetailsApi::new(status, detail, invalid_params))
 }
 
-impl IdempotencyMap {
-    pub fn new() -> Self {
-        Self {
-            resource_id_by_idempotency_id: Cache::new(25),
-        }
-    }
+pub fn bad_request(detail: &str, invalid_params: Vec<InvalidParamApi>) -> HttpResponse {
+    problem_response(StatusCode::BAD_REQUEST, Some(detail.to_string()), invalid_params)
+}
+
+pub fn forbidden(detail: &str) -> HttpResponse {
+    problem_response(StatusCode::FORBIDDEN, Some(detail.to_string()), Vec::new())
+}
 
-    pub fn get_resource_id(&self, idempotency_id: &Uuid) -> Option<Uuid> {
-        self.resource_id_by_idempotency_id.get(idempotency_id)
+pub fn not_found(detail: &str) -> HttpResponse {
+    problem_response(StatusCode::NOT_FOUND, Some(detail.to_string()), Vec::new())
+}
+
+pub fn internal_server_error() -> HttpResponse {
+    problem_response(StatusCode::INTERNAL_SERVER_ERROR, None, Vec::new())
+}
+
+pub fn json_error_handler(json_payload_error: error::JsonPayloadError, _: &HttpRequest) -> error::Error {
+    let http_response = problem_response(json_payload_error.status_code(), Some(json_payload_error.to_string()), Vec::new());
+    error::InternalError::from_response(json_payload_error, http_response).into()
+}
+
+pub fn query_error_handler(query_payload_error: error::QueryPayloadError, _: &HttpRequest) -> error::Error {
+    let http_response = problem_response(query_payload_error.status_code(), Some(query_payload_error.to_string()), Vec::new());
+    error::InternalError::from_response(query_payload_error, http_response).into()
+}
+
+pub fn extract_idempotency_key(http_request: &HttpRequest) -> Result<Option<String>, InvalidParamApi> {
+    let Some(header_value) = http_request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+
+    match header_value.to_str() {
+        Ok(idempotency_key) if !idempotency_key.is_empty() && idempotency_key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH => Ok(Some(idempotency_key.to_string())),
+        _ => Err(InvalidParamApi::new(
+            IDEMPOTENCY_KEY_HEADER,
+            &format!("Must contain between 1 and {} visible ASCII characters", MAX_IDEMPOTENCY_KEY_LENGTH),
+        )),
     }
+}
+
+/// The tenant of the authenticated principal, `None` when it isn't scoped or authentication is disabled.
+pub fn tenant(http_request: &HttpRequest) -> Option<String> {
+    http_request.extensions().get::<Principal>()
+        .and_then(|principal| principal.tenant().map(str::to_string))
+}
+
+pub fn fingerprint(request: &impl Serialize) -> String {
+    let request = serde_json::to_vec(request).expect("Request should be serializable!");
+    format!("{:x}", Sha256::digest(request))
+}
 
-    pub fn insert(&self, idempotency_id: &Uuid, resource_id: &Uuid) {
-        self.resource_id_by_idempotency_id.insert(idempotency_id.clone(), resource_id.clone());
+pub fn idempotency_error_response(idempotency_error: &IdempotencyError) -> HttpResponse {
+    match idempotency_error {
+        IdempotencyError::FingerprintMismatch => problem_response(StatusCode::UNPROCESSABLE_ENTITY, Some(idempotency_error.to_string()), Vec::new()),
+        IdempotencyError::InProgress => problem_response(StatusCode::CONFLICT, Some(idempotency_error.to_string()), Vec::new()),
+        IdempotencyError::Repository(_) => internal_server_error(),
     }
 }
\ No newline at end of file
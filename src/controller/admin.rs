@@ -0,0 +1,62 @@
+use actix_web::{delete, get, HttpResponse, post, Responder, web};
+use uuid::Uuid;
+
+use crate::models::api::{ApiKeyApi, CreateApiKeyRequestApi, DestinationApi, InvalidParamApi, PurgeQueryApi, PurgeReportApi};
+use crate::models::service::CreateApiKeyRequest;
+use crate::{controller, service};
+
+#[post("/admin/purge")]
+pub async fn post_purge(query: web::Query<PurgeQueryApi>) -> impl Responder {
+    match service::purge::purge(query.dry_run).await {
+        Ok(purge_report) => HttpResponse::Ok()
+            .json(PurgeReportApi::from(purge_report)),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+#[get("/admin/destinations")]
+pub async fn get_destinations() -> impl Responder {
+    let destinations = service::destinations::get_destinations_state();
+    HttpResponse::Ok().json(destinations.iter().map(DestinationApi::from).collect::<Vec<_>>())
+}
+
+#[post("/admin/api-keys")]
+pub async fn post_api_keys(create_api_key_request: web::Json<CreateApiKeyRequestApi>) -> impl Responder {
+    let create_api_key_request = match CreateApiKeyRequest::try_from(create_api_key_request.0) {
+        Ok(create_api_key_request) => create_api_key_request,
+        Err(error) => {
+            log::warn!("Invalid API key: {}", error);
+            return controller::bad_request("Invalid API key", vec![InvalidParamApi::from(&error)]);
+        },
+    };
+
+    match service::auth::create_api_key(&create_api_key_request).await {
+        Ok(created_api_key) => HttpResponse::Created().json(ApiKeyApi::from(&created_api_key)),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+#[get("/admin/api-keys")]
+pub async fn get_api_keys() -> impl Responder {
+    match service::auth::get_api_keys().await {
+        Ok(api_keys) => HttpResponse::Ok().json(api_keys.iter().map(ApiKeyApi::from).collect::<Vec<_>>()),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+#[delete("/admin/api-keys/{id}")]
+pub async fn delete_api_key(id: web::Path<String>) -> impl Responder {
+    let api_key_id = match Uuid::parse_str(id.as_str()) {
+        Ok(api_key_id) => api_key_id,
+        Err(_) => {
+            log::warn!("Invalid UUID format: {}", id);
+            return controller::bad_request("Invalid API key identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]);
+        },
+    };
+
+    match service::auth::delete_api_key(&api_key_id).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => controller::not_found(&format!("API key {} not found", api_key_id)),
+        Err(_) => controller::internal_server_error(),
+    }
+}
\ No newline at end of file
@@ -0,0 +1,66 @@
+use actix_web::{Error, HttpMessage};
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::{header, Method, StatusCode};
+use actix_web::middleware::Next;
+
+use crate::{controller, service};
+use crate::models::service::{ApiScope, AuthenticationError};
+
+static BEARER_PREFIX: &str = "Bearer ";
+
+pub async fn authenticate(service_request: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    if !service::auth::is_auth_enabled() {
+        return next.call(service_request).await.map(ServiceResponse::map_into_left_body);
+    }
+
+    let token = service_request.headers().get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix(BEARER_PREFIX))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .ok_or(AuthenticationError::MissingToken);
+    let principal = match token {
+        Ok(token) => service::auth::authenticate(token).await,
+        Err(error) => Err(error),
+    };
+
+    let http_response = match principal {
+        Ok(principal) => {
+            let required_scope = required_scope(&service_request);
+            if principal.has_scope(required_scope) {
+                service_request.extensions_mut().insert(principal);
+                return next.call(service_request).await.map(ServiceResponse::map_into_left_body);
+            }
+            log::info!("{} lacks the {} scope for {}", principal.name(), required_scope, service_request.path());
+            controller::forbidden(&format!("The {} scope is required", required_scope))
+        },
+        Err(AuthenticationError::Unavailable(error)) => {
+            log::error!("Failed to authenticate request: {:?}", error);
+            controller::problem_response(StatusCode::SERVICE_UNAVAILABLE, Some("Authentication is unavailable".to_string()), Vec::new())
+        },
+        Err(error) => {
+            let mut http_response = controller::problem_response(StatusCode::UNAUTHORIZED, Some(error.to_string()), Vec::new());
+            http_response.headers_mut().insert(header::WWW_AUTHENTICATE, header::HeaderValue::from_static("Bearer"));
+            http_response
+        },
+    };
+    Ok(service_request.into_response(http_response).map_into_right_body())
+}
+
+fn required_scope(service_request: &ServiceRequest) -> ApiScope {
+    // Routes match the decoded path, e.g. /%61dmin/purge is routed to /admin/purge.
+    let path = service_request.match_info().as_str();
+    if path.starts_with("/admin/") {
+        return ApiScope::Admin;
+    }
+    // Metrics span every tenant, without granting anything else.
+    if path == "/metrics" {
+        return ApiScope::Metrics;
+    }
+
+    match *service_request.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => ApiScope::Read,
+        _ => ApiScope::Write,
+    }
+}
\ No newline at end of file
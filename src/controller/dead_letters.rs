@@ -0,0 +1,78 @@
+use actix_web::{delete, get, HttpRequest, HttpResponse, post, Responder, web};
+use uuid::Uuid;
+
+use crate::controller;
+use crate::models::api::{DeadLetterApi, DeadLetterRedeliveryApi, DeadLettersQueryApi, InvalidParamApi, RedeliverDeadLettersRequestApi};
+use crate::models::service::{DeadLetterFilter, PageRequest};
+use crate::service;
+
+#[get("/dead-letters")]
+async fn get_dead_letters(http_request: HttpRequest, query: web::Query<DeadLettersQueryApi>) -> impl Responder {
+    let dead_letter_filter = match DeadLetterFilter::try_from(&query.0) {
+        Ok(dead_letter_filter) => dead_letter_filter.with_tenant(controller::tenant(&http_request).as_deref()),
+        Err(error) => {
+            log::warn!("Invalid dead letter filter: {}", error);
+            return controller::bad_request(&error.to_string(), Vec::new());
+        },
+    };
+    let page_request = match PageRequest::try_from(&query.0) {
+        Ok(page_request) => page_request,
+        Err(error) => {
+            log::warn!("Invalid page request: {}", error);
+            return controller::bad_request(&error.to_string(), Vec::new());
+        },
+    };
+
+    match service::dead_letters::get_dead_letters(&dead_letter_filter, &page_request).await {
+        Ok(dead_letters) => controller::page_response(dead_letters, |dead_letter| DeadLetterApi::from(&dead_letter)),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+#[get("/dead-letters/{id}")]
+async fn get_dead_letter(http_request: HttpRequest, id: web::Path<String>) -> impl Responder {
+    let id = match Uuid::parse_str(id.as_str()) {
+        Ok(id) => id,
+        Err(_) => {
+            log::warn!("Invalid UUID format: {}", id);
+            return controller::bad_request("Invalid dead letter identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]);
+        },
+    };
+
+    match service::dead_letters::get_dead_letter_by_id(&id, controller::tenant(&http_request).as_deref()).await {
+        Ok(Some((dead_letter, attempts))) => HttpResponse::Ok().json(DeadLetterApi::from(&dead_letter).with_attempts(&attempts)),
+        Ok(None) => controller::not_found(&format!("Dead letter {} not found", id)),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+#[post("/dead-letters/redeliver")]
+async fn post_dead_letters_redeliver(http_request: HttpRequest, redeliver_request: web::Json<RedeliverDeadLettersRequestApi>) -> impl Responder {
+    let ids = match redeliver_request.ids() {
+        Ok(ids) => ids,
+        Err(invalid_param) => return controller::bad_request("Invalid dead letter identifier", vec![invalid_param]),
+    };
+
+    match service::dead_letters::redeliver_dead_letters(&ids, controller::tenant(&http_request).as_deref()).await {
+        // 202: the webhooks are called by the delivery pool.
+        Ok(redeliveries) => HttpResponse::Accepted().json(redeliveries.iter().map(DeadLetterRedeliveryApi::from).collect::<Vec<_>>()),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+#[delete("/dead-letters/{id}")]
+async fn delete_dead_letter(http_request: HttpRequest, id: web::Path<String>) -> impl Responder {
+    let id = match Uuid::parse_str(id.as_str()) {
+        Ok(id) => id,
+        Err(_) => {
+            log::warn!("Invalid UUID format: {}", id);
+            return controller::bad_request("Invalid dead letter identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]);
+        },
+    };
+
+    match service::dead_letters::discard_dead_letter(&id, controller::tenant(&http_request).as_deref()).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => controller::not_found(&format!("Dead letter {} not found", id)),
+        Err(_) => controller::internal_server_error(),
+    }
+}
\ No newline at end of file
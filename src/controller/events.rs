@@ -0,0 +1,109 @@
+use std::convert::Infallible;
+use std::time::Duration;
+
+use actix_web::{get, HttpRequest, HttpResponse, Responder, web};
+use actix_web::http::header;
+use actix_web::web::Bytes;
+use futures_util::{stream, Stream};
+use k8s_openapi::serde_json;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use uuid::Uuid;
+
+use crate::controller;
+use crate::models::api::{EventsQueryApi, InvalidParamApi, JobDoneWatcherApi, JobFamilyDeliveryApi};
+use crate::models::service::{Event, EventFilter};
+use crate::service;
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[get("/events")]
+pub async fn get_events(http_request: HttpRequest, query: web::Query<EventsQueryApi>) -> impl Responder {
+    let event_filter = match EventFilter::try_from(&query.0) {
+        Ok(event_filter) => event_filter.with_tenant(controller::tenant(&http_request).as_deref()),
+        Err(error) => {
+            log::warn!("Invalid event filter: {}", error);
+            return controller::bad_request("Invalid event filter", vec![InvalidParamApi::new("jobName", &error.to_string())]);
+        },
+    };
+
+    event_stream_response(service::events::subscribe_events(), event_filter, None)
+}
+
+#[get("/job-done-watchers/{id}/events")]
+pub async fn get_job_done_watcher_events(http_request: HttpRequest, id: web::Path<String>) -> impl Responder {
+    let id = match Uuid::parse_str(id.as_str()) {
+        Ok(id) => id,
+        Err(_) => {
+            log::warn!("Invalid UUID format: {}", id);
+            return controller::bad_request("Invalid job done watcher identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]);
+        },
+    };
+
+    // Subscribe before reading the current state, so no transition in between is missed.
+    let receiver = service::events::subscribe_events();
+    match service::job_done_watchers::get_job_done_watcher_by_id(&id, controller::tenant(&http_request).as_deref()).await {
+        Ok(Some(job_done_watcher)) => event_stream_response(receiver, EventFilter::for_job_done_watcher(id), Some(Event::JobDoneWatcher(job_done_watcher))),
+        Ok(None) => controller::not_found(&format!("Job done watcher {} not found", id)),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+fn event_stream_response(receiver: Receiver<Event>, event_filter: EventFilter, current: Option<Event>) -> HttpResponse {
+    let close_on_finish = current.is_some();
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(event_stream(EventStream { receiver, event_filter, pending: current, close_on_finish, closed: false }))
+}
+
+struct EventStream {
+    receiver: Receiver<Event>,
+    event_filter: EventFilter,
+    pending: Option<Event>,
+    close_on_finish: bool,
+    closed: bool,
+}
+
+fn event_stream(event_stream: EventStream) -> impl Stream<Item = Result<Bytes, Infallible>> {
+    stream::unfold(event_stream, |mut event_stream| async move {
+        if event_stream.closed {
+            return None;
+        }
+        if let Some(event) = event_stream.pending.take() {
+            event_stream.closed = event_stream.close_on_finish && is_finished(&event);
+            return Some((Ok(to_server_sent_event(&event)), event_stream));
+        }
+
+        loop {
+            match actix_web::rt::time::timeout(KEEP_ALIVE_INTERVAL, event_stream.receiver.recv()).await {
+                Err(_) => return Some((Ok(Bytes::from_static(b": keep-alive\n\n")), event_stream)),
+                Ok(Ok(event)) if event_stream.event_filter.matches(&event) => {
+                    event_stream.closed = event_stream.close_on_finish && is_finished(&event);
+                    return Some((Ok(to_server_sent_event(&event)), event_stream));
+                },
+                Ok(Ok(_)) => continue,
+                Ok(Err(RecvError::Lagged(skipped))) => {
+                    log::warn!("Event stream lagging behind, {} events skipped", skipped);
+                    return Some((Ok(Bytes::from(format!("event: lagged\ndata: {{\"skipped\":{}}}\n\n", skipped))), event_stream));
+                },
+                Ok(Err(RecvError::Closed)) => return None,
+            }
+        }
+    })
+}
+
+fn is_finished(event: &Event) -> bool {
+    matches!(event, Event::JobDoneWatcher(job_done_watcher) if job_done_watcher.status().is_finished())
+}
+
+fn to_server_sent_event(event: &Event) -> Bytes {
+    let (name, data) = match event {
+        Event::JobDoneWatcher(job_done_watcher) =>
+            ("job-done-watcher", serde_json::to_string(&JobDoneWatcherApi::from(job_done_watcher.clone()))),
+        Event::JobFamilyDelivery(job_family_delivery) =>
+            ("job-family-delivery", serde_json::to_string(&JobFamilyDeliveryApi::from(job_family_delivery.clone()))),
+    };
+
+    Bytes::from(format!("event: {}\ndata: {}\n\n", name, data.expect("Event should be serializable!")))
+}
\ No newline at end of file
@@ -1,77 +1,256 @@
 use actix_web::{get, HttpRequest, HttpResponse, post, Responder, web};
+use actix_web::http::StatusCode;
+use actix_web::web::Bytes;
+use k8s_openapi::serde_json;
 use uuid::Uuid;
 
-use crate::controller::{IDEMPOTENCY_KEY_HEADER, IdempotencyMap};
-use crate::models::api::{CreateJobDoneWatcherRequestApi, JobDoneWatcherApi};
+use crate::controller;
+use crate::models::api::{CreateJobDoneWatcherRequestApi, InvalidParamApi, JobDoneTriggerWebhookAttemptApi, JobDoneWatcherApi, JobDoneWatchersQueryApi, JobWaitQueryApi, RedeliverRequestApi, WaitQueryApi};
+use crate::models::service::{CreateJobDoneWatcherError, CreateJobDoneWatcherRequest, IdempotencyClaim, JobDoneWatcher, JobDoneWatcherFilter, JobName, Namespace, PageRequest, RedeliverJobDoneWatcherError, WaitTimeout};
 use crate::service;
 
+static IDEMPOTENCY_SCOPE: &str = "POST /job-done-watchers";
+
 #[post("/job-done-watchers")]
 async fn post_job_done_watchers(
     http_request: HttpRequest,
-    app_state_idempotency_map: web::Data<IdempotencyMap>,
     job_done_watcher: web::Json<CreateJobDoneWatcherRequestApi>
 ) -> impl Responder {
-    let idempotency_key_option = extract_idempotency_key_header_value(&http_request);
-    if let Some(idempotency_key) = &idempotency_key_option {
-        log::info!("Idempotency key found: {}", idempotency_key);
-        if let Some(resource_id) = app_state_idempotency_map.get_resource_id(idempotency_key) {
-            log::info!("Resource ID for idempotency key {}: {}", idempotency_key, resource_id);
-            if let Ok(Some(job_done_watcher)) = service::job_done_watchers::get_job_done_watcher_by_id(&resource_id).await {
-                log::info!("Job-done watcher already exists for idempotency key {}", idempotency_key);
-                return HttpResponse::Ok().json(JobDoneWatcherApi::from(job_done_watcher));
-            }
-        }
-    }
+    let idempotency_key = match controller::extract_idempotency_key(&http_request) {
+        Ok(idempotency_key) => idempotency_key,
+        Err(invalid_param) => return controller::bad_request("Invalid idempotency key", vec![invalid_param]),
+    };
+    let fingerprint = controller::fingerprint(&job_done_watcher.0);
+    let tenant = controller::tenant(&http_request);
 
-    let create_job_done_watcher_request = job_done_watcher.0.try_into().unwrap(); // TODO:
-    let created_job_done_watcher = match service::job_done_watchers::create_job_done_watcher(create_job_done_watcher_request).await {
+    let create_job_done_watcher_request = match CreateJobDoneWatcherRequest::try_from(job_done_watcher.0) {
+        Ok(create_job_done_watcher_request) => create_job_done_watcher_request,
+        Err(error) => {
+            log::warn!("Invalid JobDoneWatcher: {}", error);
+            return controller::bad_request("Invalid job done watcher", vec![InvalidParamApi::from(&error)]);
+        },
+    };
+
+    let idempotency_claim = match &idempotency_key {
+        Some(idempotency_key) => match service::idempotency::claim_idempotency_key(IDEMPOTENCY_SCOPE, tenant.as_deref(), idempotency_key, &fingerprint).await {
+            Ok(IdempotencyClaim::Claimed(claimed_at)) => Some((idempotency_key, claimed_at)),
+            Ok(IdempotencyClaim::Completed(job_done_watcher_id)) => return match service::job_done_watchers::get_job_done_watcher_by_id(&job_done_watcher_id, tenant.as_deref()).await {
+                Ok(Some(job_done_watcher)) => HttpResponse::Ok().json(JobDoneWatcherApi::from(job_done_watcher)),
+                Ok(None) => controller::problem_response(StatusCode::CONFLICT, Some(format!("Job done watcher {} no longer exists", job_done_watcher_id)), Vec::new()),
+                Err(_) => controller::internal_server_error(),
+            },
+            Err(error) => return controller::idempotency_error_response(&error),
+        },
+        None => None,
+    };
+
+    let created_job_done_watcher = match service::job_done_watchers::create_job_done_watcher(create_job_done_watcher_request, tenant.as_deref()).await {
         Ok(created_job_done_watcher) => created_job_done_watcher,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
+        Err(error) => {
+            if let Some((idempotency_key, claimed_at)) = idempotency_claim {
+                service::idempotency::release_idempotency_key(IDEMPOTENCY_SCOPE, tenant.as_deref(), idempotency_key, claimed_at).await;
+            }
+            return match error {
+                CreateJobDoneWatcherError::WebhooksNotFound(webhooks_not_found) => {
+                    let invalid_params = webhooks_not_found.iter()
+                        .map(|(index, webhook_id)| InvalidParamApi::new(
+                            &format!("jobDoneTriggerWebhooks[{}].webhookId", index),
+                            &format!("Webhook {} not found", webhook_id),
+                        ))
+                        .collect();
+                    controller::bad_request("Invalid job done watcher", invalid_params)
+                },
+                CreateJobDoneWatcherError::MissingNamespace =>
+                    controller::bad_request("Invalid job done watcher", vec![InvalidParamApi::new("namespace", &error.to_string())]),
+                CreateJobDoneWatcherError::NamespaceNotAllowed(_) => controller::forbidden(&error.to_string()),
+                CreateJobDoneWatcherError::Repository(_) => controller::internal_server_error(),
+            };
+        },
     };
 
-    if let Some(idempotency_key) = &idempotency_key_option {
-        log::info!("Inserting idempotency key {} for the created watcher", idempotency_key);
-        app_state_idempotency_map.insert(idempotency_key, &created_job_done_watcher.id());
+    if let Some((idempotency_key, claimed_at)) = idempotency_claim {
+        service::idempotency::complete_idempotency_key(IDEMPOTENCY_SCOPE, tenant.as_deref(), idempotency_key, claimed_at, &created_job_done_watcher.id()).await;
     }
 
     HttpResponse::Created().json(JobDoneWatcherApi::from(created_job_done_watcher))
 }
 
-fn extract_idempotency_key_header_value(http_request: &HttpRequest) -> Option<Uuid> {
-    http_request.headers()
-        .get(IDEMPOTENCY_KEY_HEADER)
-        .and_then(|header_value| header_value.to_str().ok())
-        .and_then(|s| Uuid::parse_str(s).ok())
-}
-
-
 #[get("/job-done-watchers")]
-async fn get_job_done_watchers() -> impl Responder {
-    let job_done_watchers = match service::job_done_watchers::get_job_done_watchers().await {
-        Ok(job_done_watchers) => job_done_watchers,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
+async fn get_job_done_watchers(http_request: HttpRequest, query: web::Query<JobDoneWatchersQueryApi>) -> impl Responder {
+    let job_done_watcher_filter = match JobDoneWatcherFilter::try_from(&query.0) {
+        Ok(job_done_watcher_filter) => job_done_watcher_filter.with_tenant(controller::tenant(&http_request).as_deref()),
+        Err(error) => {
+            log::warn!("Invalid JobDoneWatcher filter: {}", error);
+            return controller::bad_request(&error.to_string(), Vec::new());
+        },
+    };
+    let page_request = match PageRequest::try_from(&query.0) {
+        Ok(page_request) => page_request,
+        Err(error) => {
+            log::warn!("Invalid page request: {}", error);
+            return controller::bad_request(&error.to_string(), Vec::new());
+        },
     };
 
-    HttpResponse::Created()
-        .json(job_done_watchers
-            .into_iter()
-            .map(JobDoneWatcherApi::from)
-            .collect::<Vec<JobDoneWatcherApi>>())
+    match service::job_done_watchers::get_job_done_watchers(&job_done_watcher_filter, &page_request).await {
+        Ok(job_done_watchers) => controller::page_response(job_done_watchers, JobDoneWatcherApi::from),
+        Err(_) => controller::internal_server_error(),
+    }
 }
 
 #[get("/job-done-watchers/{id}")]
-async fn get_job_done_watcher(id: web::Path<String>) -> impl Responder {
+async fn get_job_done_watcher(http_request: HttpRequest, id: web::Path<String>) -> impl Responder {
     let id = match Uuid::parse_str(id.as_str()) {
         Ok(id) => id,
         Err(_) => {
             log::warn!("Invalid UUID format: {}", id);
-            return HttpResponse::BadRequest().finish();
+            return controller::bad_request("Invalid job done watcher identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]);
         },
     };
 
-    match service::job_done_watchers::get_job_done_watcher_by_id(&id).await {
-        Ok(None) => HttpResponse::NotFound().finish(),
+    match service::job_done_watchers::get_job_done_watcher_by_id(&id, controller::tenant(&http_request).as_deref()).await {
+        Ok(None) => controller::not_found(&format!("Job done watcher {} not found", id)),
         Ok(Some(job_done_watcher)) => HttpResponse::Ok().json(JobDoneWatcherApi::from(job_done_watcher)),
-        _ => return HttpResponse::InternalServerError().finish(),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+#[get("/job-done-watchers/{id}/wait")]
+async fn get_job_done_watcher_wait(http_request: HttpRequest, id: web::Path<String>, query: web::Query<WaitQueryApi>) -> impl Responder {
+    let id = match Uuid::parse_str(id.as_str()) {
+        Ok(id) => id,
+        Err(_) => {
+            log::warn!("Invalid UUID format: {}", id);
+            return controller::bad_request("Invalid job done watcher identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]);
+        },
+    };
+    let wait_timeout = match WaitTimeout::try_from(&query.0) {
+        Ok(wait_timeout) => wait_timeout,
+        Err(error) => return controller::bad_request("Invalid wait timeout", vec![InvalidParamApi::new("timeout", &error.to_string())]),
+    };
+
+    match service::job_done_watchers::wait_for_job_done_watcher(&id, wait_timeout, controller::tenant(&http_request).as_deref()).await {
+        Ok(Some(job_done_watcher)) => wait_response(job_done_watcher),
+        Ok(None) => controller::not_found(&format!("Job done watcher {} not found", id)),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+#[get("/jobs/{name}/wait")]
+async fn get_job_wait(http_request: HttpRequest, name: web::Path<String>, query: web::Query<JobWaitQueryApi>) -> impl Responder {
+    let job_name = match JobName::new(name.as_str()) {
+        Ok(job_name) => job_name,
+        Err(error) => return controller::bad_request("Invalid job name", vec![InvalidParamApi::new("name", &error.to_string())]),
+    };
+    let namespace = match query.namespace.as_deref().map(Namespace::new).transpose() {
+        Ok(namespace) => namespace,
+        Err(error) => return controller::bad_request("Invalid namespace", vec![InvalidParamApi::new("namespace", &error.to_string())]),
+    };
+    let wait_timeout = match WaitTimeout::try_from(&query.0) {
+        Ok(wait_timeout) => wait_timeout,
+        Err(error) => return controller::bad_request("Invalid wait timeout", vec![InvalidParamApi::new("timeout", &error.to_string())]),
+    };
+
+    let tenant = controller::tenant(&http_request);
+    let job_done_watcher = match service::job_done_watchers::find_latest_job_done_watcher(&job_name, namespace.as_ref(), tenant.as_deref()).await {
+        Ok(Some(job_done_watcher)) => job_done_watcher,
+        Ok(None) => return controller::not_found(&format!("No job done watcher for job {}", job_name)),
+        Err(_) => return controller::internal_server_error(),
+    };
+
+    match service::job_done_watchers::wait_for_job_done_watcher(&job_done_watcher.id(), wait_timeout, tenant.as_deref()).await {
+        Ok(Some(job_done_watcher)) => wait_response(job_done_watcher),
+        Ok(None) => controller::not_found(&format!("Job done watcher {} not found", job_done_watcher.id())),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+fn wait_response(job_done_watcher: JobDoneWatcher) -> HttpResponse {
+    // 202: the wait timed out before the watcher finished.
+    let mut http_response = if job_done_watcher.status().is_finished() { HttpResponse::Ok() } else { HttpResponse::Accepted() };
+    http_response.json(JobDoneWatcherApi::from(job_done_watcher))
+}
+
+#[post("/job-done-watchers/{id}/redeliver")]
+async fn post_job_done_watcher_redeliver(http_request: HttpRequest, id: web::Path<String>, body: Bytes) -> impl Responder {
+    let id = match Uuid::parse_str(id.as_str()) {
+        Ok(id) => id,
+        Err(_) => {
+            log::warn!("Invalid UUID format: {}", id);
+            return controller::bad_request("Invalid job done watcher identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]);
+        },
+    };
+    // The body is optional: without one, every failed trigger is redelivered.
+    let redeliver_request = if body.is_empty() {
+        RedeliverRequestApi::default()
+    } else {
+        match serde_json::from_slice::<RedeliverRequestApi>(&body) {
+            Ok(redeliver_request) => redeliver_request,
+            Err(error) => return controller::bad_request(&format!("Json deserialize error: {}", error), Vec::new()),
+        }
+    };
+    let job_done_trigger_webhook_ids = match redeliver_request.job_done_trigger_webhook_ids() {
+        Ok(job_done_trigger_webhook_ids) => job_done_trigger_webhook_ids,
+        Err(invalid_param) => return controller::bad_request("Invalid job done trigger webhook identifier", vec![invalid_param]),
+    };
+
+    redeliver_response(&id, job_done_trigger_webhook_ids, controller::tenant(&http_request).as_deref()).await
+}
+
+#[post("/job-done-watchers/{id}/triggers/{trigger_id}/redeliver")]
+async fn post_job_done_trigger_webhook_redeliver(http_request: HttpRequest, path: web::Path<(String, String)>) -> impl Responder {
+    let (id, trigger_id) = path.into_inner();
+    let (id, trigger_id) = match (Uuid::parse_str(&id), Uuid::parse_str(&trigger_id)) {
+        (Ok(id), Ok(trigger_id)) => (id, trigger_id),
+        (Err(_), _) => return controller::bad_request("Invalid job done watcher identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]),
+        (_, Err(_)) => return controller::bad_request("Invalid job done trigger webhook identifier", vec![InvalidParamApi::new("triggerId", "Invalid UUID format")]),
+    };
+
+    match service::job_done_watchers::redeliver_job_done_trigger_webhooks(&id, Some(vec![trigger_id]), controller::tenant(&http_request).as_deref()).await {
+        Err(RedeliverJobDoneWatcherError::TriggersNotFound(_)) =>
+            controller::not_found(&format!("Job done trigger webhook {} not found", trigger_id)),
+        result => redeliver_result_response(&id, result),
+    }
+}
+
+#[get("/job-done-watchers/{id}/attempts")]
+async fn get_job_done_watcher_attempts(http_request: HttpRequest, id: web::Path<String>) -> impl Responder {
+    let id = match Uuid::parse_str(id.as_str()) {
+        Ok(id) => id,
+        Err(_) => {
+            log::warn!("Invalid UUID format: {}", id);
+            return controller::bad_request("Invalid job done watcher identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]);
+        },
+    };
+
+    match service::job_done_watchers::get_job_done_trigger_webhook_attempts(&id, controller::tenant(&http_request).as_deref()).await {
+        Ok(Some(attempts)) => HttpResponse::Ok().json(attempts.iter().map(JobDoneTriggerWebhookAttemptApi::from).collect::<Vec<_>>()),
+        Ok(None) => controller::not_found(&format!("Job done watcher {} not found", id)),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+async fn redeliver_response(id: &Uuid, job_done_trigger_webhook_ids: Option<Vec<Uuid>>, tenant: Option<&str>) -> HttpResponse {
+    let result = service::job_done_watchers::redeliver_job_done_trigger_webhooks(id, job_done_trigger_webhook_ids.clone(), tenant).await;
+    match (result, job_done_trigger_webhook_ids) {
+        (Err(RedeliverJobDoneWatcherError::TriggersNotFound(not_found)), Some(job_done_trigger_webhook_ids)) => {
+            let invalid_params = job_done_trigger_webhook_ids.iter()
+                .enumerate()
+                .filter(|(_, id)| not_found.contains(id))
+                .map(|(index, _)| InvalidParamApi::new(&format!("jobDoneTriggerWebhookIds[{}]", index), "Job done trigger webhook not found"))
+                .collect();
+            controller::bad_request("Unknown job done trigger webhooks", invalid_params)
+        },
+        (result, _) => redeliver_result_response(id, result),
+    }
+}
+
+fn redeliver_result_response(id: &Uuid, result: Result<JobDoneWatcher, RedeliverJobDoneWatcherError>) -> HttpResponse {
+    match result {
+        // 202: the webhooks are called by the delivery pool.
+        Ok(job_done_watcher) => HttpResponse::Accepted().json(JobDoneWatcherApi::from(job_done_watcher)),
+        Err(RedeliverJobDoneWatcherError::NotFound) => controller::not_found(&format!("Job done watcher {} not found", id)),
+        Err(RedeliverJobDoneWatcherError::Repository(_)) => controller::internal_server_error(),
+        Err(error) => controller::problem_response(StatusCode::CONFLICT, Some(error.to_string()), Vec::new()),
     }
 }
\ No newline at end of file
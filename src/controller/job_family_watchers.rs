@@ -0,0 +1,20 @@
+use actix_web::{get, HttpRequest, HttpResponse, Responder, web};
+
+use crate::models::api::{JobFamilyDeliveriesQueryApi, JobFamilyDeliveryApi};
+use crate::models::service::JobFamilyDeliveryFilter;
+use crate::{controller, service};
+
+#[get("/job-family-deliveries")]
+pub async fn get_job_family_deliveries(http_request: HttpRequest, query: web::Query<JobFamilyDeliveriesQueryApi>) -> impl Responder {
+    let job_family_delivery_filter: JobFamilyDeliveryFilter = query.into_inner().into();
+    let job_family_delivery_filter = job_family_delivery_filter.with_tenant(controller::tenant(&http_request).as_deref());
+
+    match service::job_family_watcher::get_job_family_deliveries(&job_family_delivery_filter).await {
+        Ok(job_family_deliveries) => HttpResponse::Ok()
+            .json(job_family_deliveries
+                .into_iter()
+                .map(JobFamilyDeliveryApi::from)
+                .collect::<Vec<JobFamilyDeliveryApi>>()),
+        Err(_) => controller::internal_server_error(),
+    }
+}
\ No newline at end of file
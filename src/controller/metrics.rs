@@ -0,0 +1,16 @@
+use actix_web::{get, HttpResponse, Responder};
+
+use crate::{controller, service};
+
+#[get("/metrics")]
+pub async fn get_metrics() -> impl Responder {
+    match service::metrics::render_metrics().await {
+        Ok(metrics) => HttpResponse::Ok()
+            .content_type(service::metrics::CONTENT_TYPE)
+            .body(metrics),
+        Err(error) => {
+            log::error!("Failed to render metrics: {:?}", error);
+            controller::internal_server_error()
+        },
+    }
+}
\ No newline at end of file
@@ -1,47 +1,122 @@
-use actix_web::{get, HttpResponse, post, Responder, web};
+use actix_web::{get, HttpRequest, HttpResponse, post, Responder, web};
+use actix_web::http::StatusCode;
 use uuid::Uuid;
 
-use crate::models::api::{CreateWebhookRequestApi, WebhookApi};
+use crate::controller;
+use crate::models::api::{CreateWebhookRequestApi, InvalidParamApi, TestWebhookRequestApi, TestWebhookResultApi, WebhookApi, WebhooksQueryApi};
+use crate::models::service::{CreateWebhookError, CreateWebhookRequest, IdempotencyClaim, PageRequest, TestWebhookRequest, WebhookFilter};
 use crate::service;
 
+static IDEMPOTENCY_SCOPE: &str = "POST /webhooks";
+
 #[post("/webhooks")]
-pub async fn post_webhooks(webhook: web::Json<CreateWebhookRequestApi>) -> impl Responder {
-    let create_webhook_request = webhook.0.try_into().unwrap(); // TODO:
+pub async fn post_webhooks(http_request: HttpRequest, webhook: web::Json<CreateWebhookRequestApi>) -> impl Responder {
+    let idempotency_key = match controller::extract_idempotency_key(&http_request) {
+        Ok(idempotency_key) => idempotency_key,
+        Err(invalid_param) => return controller::bad_request("Invalid idempotency key", vec![invalid_param]),
+    };
+    let fingerprint = controller::fingerprint(&webhook.0);
+    let tenant = controller::tenant(&http_request);
+
+    let create_webhook_request = match CreateWebhookRequest::try_from(webhook.0) {
+        Ok(create_webhook_request) => create_webhook_request,
+        Err(error) => {
+            log::warn!("Invalid webhook: {}", error);
+            return controller::bad_request("Invalid webhook", vec![InvalidParamApi::from(&error)]);
+        },
+    };
+
+    let idempotency_claim = match &idempotency_key {
+        Some(idempotency_key) => match service::idempotency::claim_idempotency_key(IDEMPOTENCY_SCOPE, tenant.as_deref(), idempotency_key, &fingerprint).await {
+            Ok(IdempotencyClaim::Claimed(claimed_at)) => Some((idempotency_key, claimed_at)),
+            Ok(IdempotencyClaim::Completed(webhook_id)) => return match service::webhooks::get_webhook_by_id(&webhook_id, tenant.as_deref()).await {
+                Ok(Some(webhook)) => HttpResponse::Ok().json(WebhookApi::from(&webhook)),
+                Ok(None) => controller::problem_response(StatusCode::CONFLICT, Some(format!("Webhook {} no longer exists", webhook_id)), Vec::new()),
+                Err(_) => controller::internal_server_error(),
+            },
+            Err(error) => return controller::idempotency_error_response(&error),
+        },
+        None => None,
+    };
 
-    match service::webhooks::create_webhook(create_webhook_request).await {
-        Ok(created_webhook) => HttpResponse::Created()
-            .json(WebhookApi::from(&created_webhook)),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+    match service::webhooks::create_webhook(create_webhook_request, tenant.as_deref()).await {
+        Ok(created_webhook) => {
+            if let Some((idempotency_key, claimed_at)) = idempotency_claim {
+                service::idempotency::complete_idempotency_key(IDEMPOTENCY_SCOPE, tenant.as_deref(), idempotency_key, claimed_at, &created_webhook.id()).await;
+            }
+            HttpResponse::Created().json(WebhookApi::from(&created_webhook))
+        },
+        Err(error) => {
+            if let Some((idempotency_key, claimed_at)) = idempotency_claim {
+                service::idempotency::release_idempotency_key(IDEMPOTENCY_SCOPE, tenant.as_deref(), idempotency_key, claimed_at).await;
+            }
+            match error {
+                CreateWebhookError::Egress(error) => controller::bad_request("Invalid webhook", vec![InvalidParamApi::new("url", &error.to_string())]),
+                CreateWebhookError::UnknownTlsProfile(_) => controller::bad_request("Invalid webhook", vec![InvalidParamApi::new("tlsProfile", &error.to_string())]),
+                CreateWebhookError::Repository(_) => controller::internal_server_error(),
+            }
+        },
     }
 }
 
 #[get("/webhooks")]
-pub async fn get_webhooks() -> impl Responder {
-    match service::webhooks::get_webhooks().await {
-        Ok(webhooks) => HttpResponse::Ok()
-            .json(webhooks.iter()
-                .map(WebhookApi::from)
-                .collect::<Vec<WebhookApi>>()),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+pub async fn get_webhooks(http_request: HttpRequest, query: web::Query<WebhooksQueryApi>) -> impl Responder {
+    let page_request = match PageRequest::try_from(&query.0) {
+        Ok(page_request) => page_request,
+        Err(error) => {
+            log::warn!("Invalid page request: {}", error);
+            return controller::bad_request(&error.to_string(), Vec::new());
+        },
+    };
+
+    let webhook_filter: WebhookFilter = (&query.0).into();
+    let webhook_filter = webhook_filter.with_tenant(controller::tenant(&http_request).as_deref());
+    match service::webhooks::get_webhooks(&webhook_filter, &page_request).await {
+        Ok(webhooks) => controller::page_response(webhooks, |webhook| WebhookApi::from(&webhook)),
+        Err(_) => controller::internal_server_error(),
     }
 }
 
 #[get("/webhooks/{id}")]
-pub async fn get_webhook_by_id(id: web::Path<String>) -> impl Responder {
+pub async fn get_webhook_by_id(http_request: HttpRequest, id: web::Path<String>) -> impl Responder {
     let webhook_id = match Uuid::parse_str(id.as_str()) {
         Ok(webhook_id) => webhook_id,
         Err(_) => {
-            log::warn!("Invalid UUID format: {}", id); // TODO:
-            return HttpResponse::BadRequest().finish();
+            log::warn!("Invalid UUID format: {}", id);
+            return controller::bad_request("Invalid webhook identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]);
         },
     };
 
-    match service::webhooks::get_webhook_by_id(&webhook_id).await {
+    match service::webhooks::get_webhook_by_id(&webhook_id, controller::tenant(&http_request).as_deref()).await {
         Ok(option_webhook) => match option_webhook {
-            None => HttpResponse::NotFound().finish(),
+            None => controller::not_found(&format!("Webhook {} not found", webhook_id)),
             Some(webhook) => HttpResponse::Ok()
                 .json(WebhookApi::from(&webhook)),
         },
-        Err(_) => HttpResponse::InternalServerError().finish(),
+        Err(_) => controller::internal_server_error(),
+    }
+}
+
+#[post("/webhooks/{id}/test")]
+pub async fn post_webhook_test(http_request: HttpRequest, id: web::Path<String>, test_webhook_request: web::Json<TestWebhookRequestApi>) -> impl Responder {
+    let webhook_id = match Uuid::parse_str(id.as_str()) {
+        Ok(webhook_id) => webhook_id,
+        Err(_) => {
+            log::warn!("Invalid UUID format: {}", id);
+            return controller::bad_request("Invalid webhook identifier", vec![InvalidParamApi::new("id", "Invalid UUID format")]);
+        },
+    };
+    let test_webhook_request = match TestWebhookRequest::try_from(test_webhook_request.0) {
+        Ok(test_webhook_request) => test_webhook_request,
+        Err(error) => {
+            log::warn!("Invalid webhook test: {}", error);
+            return controller::bad_request("Invalid webhook test", vec![InvalidParamApi::from(&error)]);
+        },
+    };
+
+    match service::webhooks::test_webhook(&webhook_id, &test_webhook_request, controller::tenant(&http_request).as_deref()).await {
+        Ok(Some(test_webhook_result)) => HttpResponse::Ok().json(TestWebhookResultApi::from(&test_webhook_result)),
+        Ok(None) => controller::not_found(&format!("Webhook {} not found", webhook_id)),
+        Err(_) => controller::internal_server_error(),
     }
 }
\ No newline at end of file
@@ -1,10 +1,26 @@
 use k8s_job_webhooks::{service, setup};
+use k8s_job_webhooks::setup::RunMode;
 
 #[actix_web::main]
 async fn main() -> anyhow::Result<()> {
     setup::init_logging()?;
+    match setup::parse_run_mode()? {
+        RunMode::MigrateOnly => return setup::migrate_database(false).await,
+        RunMode::CheckMigrations => return setup::migrate_database(true).await,
+        RunMode::Server => {},
+    }
+
     setup::init_database().await?;
-    if let Err(_) = setup::parse_job_family_watchers_config_file().await {}
+    setup::init_egress()?;
+    setup::init_delivery_client()?;
+    let _ = setup::parse_job_family_watchers_config_file().await;
+    setup::init_delivery_pool()?;
+    setup::init_destinations()?;
+    setup::init_purge()?;
+    setup::init_idempotency()?;
+    setup::init_dead_letters()?;
+    setup::init_auth().await?;
+    setup::init_tenants()?;
     service::k8s_job_watcher::spawn_k8s_job_watcher();
     setup::init_http_server().await?;
     Ok(())
@@ -1,12 +1,13 @@
 use std::fmt;
 
+use actix_web::http::StatusCode;
 use chrono::{DateTime, Utc};
 use k8s_openapi::serde::Deserialize;
 use serde::Serialize;
 use uuid::Uuid;
 
 use crate::models::service;
-use crate::models::service::{CreateJobDoneTriggerWebhookRequest, CreateJobDoneTriggerWebhookRequestError, CreateJobDoneWatcherRequest, CreateWebhookRequestError, JobDoneTriggerWebhook, JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherStatus, Webhook};
+use crate::models::service::{ApiKey, ApiScope, CircuitState, CreateApiKeyRequest, CreateApiKeyRequestError, CreatedApiKey, CreateJobDoneTriggerWebhookRequest, CreateJobDoneTriggerWebhookRequestError, CreateJobDoneWatcherRequest, CreateJobDoneWatcherRequestError, CreateWebhookRequestError, DeadLetter, DeadLetterFilter, DeadLetterRedelivery, Destination, EventFilter, JobDoneTriggerWebhook, JobDoneTriggerWebhookAttempt, JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherFilter, JobDoneWatcherStatus, JobFamilyDelivery, JobFamilyDeliveryFilter, JobFamilyDeliveryStatus, JobName, JobNameError, JobOutcome, Namespace, PageRequest, PageRequestError, PurgeReport, RenderedWebhookRequest, SortOrder, TestWebhookRequest, TestWebhookRequestError, TestWebhookResult, WaitTimeout, WaitTimeoutError, Webhook, WebhookFilter};
 
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -14,6 +15,8 @@ pub struct CreateWebhookRequestApi {
     pub url: String,
     pub request_body: String,
     pub description: String,
+    pub tls_profile: Option<String>,
+    pub rate_limit_per_second: Option<f64>,
 }
 
 impl TryFrom<CreateWebhookRequestApi> for service::CreateWebhookRequest {
@@ -24,6 +27,8 @@ impl TryFrom<CreateWebhookRequestApi> for service::CreateWebhookRequest {
             &create_webhook_request_api.url,
             &create_webhook_request_api.request_body,
             &create_webhook_request_api.description,
+            create_webhook_request_api.tls_profile.as_deref(),
+            create_webhook_request_api.rate_limit_per_second,
         )
     }
 }
@@ -36,6 +41,12 @@ pub struct WebhookApi {
     pub url: String,
     pub request_body: String,
     pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_per_second: Option<f64>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -46,30 +57,131 @@ impl From<&Webhook> for WebhookApi {
             url: webhook.url().to_string(),
             request_body: webhook.request_body().to_string(),
             description: webhook.description().to_string(),
+            tenant: webhook.tenant().map(str::to_string),
+            tls_profile: webhook.tls_profile().map(str::to_string),
+            rate_limit_per_second: webhook.rate_limit_per_second(),
             created_at: webhook.created_at(),
         }
     }
 }
 
 
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TestWebhookRequestApi {
+    pub job_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u32,
+    #[serde(default)]
+    pub send: bool,
+}
+
+impl TryFrom<TestWebhookRequestApi> for TestWebhookRequest {
+    type Error = TestWebhookRequestError;
+
+    fn try_from(value: TestWebhookRequestApi) -> Result<Self, Self::Error> {
+        TestWebhookRequest::new(&value.job_name, value.namespace.as_deref(), value.timeout_seconds, value.send)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TestWebhookResultApi {
+    pub job_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<RenderedWebhookRequestApi>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<TestWebhookResponseApi>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<&TestWebhookResult> for TestWebhookResultApi {
+    fn from(test_webhook_result: &TestWebhookResult) -> Self {
+        Self {
+            job_name: test_webhook_result.job_name().to_string(),
+            namespace: test_webhook_result.namespace().map(|namespace| namespace.to_string()),
+            request: test_webhook_result.request().map(RenderedWebhookRequestApi::from),
+            response: test_webhook_result.response().map(|response| TestWebhookResponseApi {
+                status: response.status(),
+                headers: HttpHeaderApi::from_pairs(response.headers()),
+            }),
+            latency_ms: test_webhook_result.latency().map(|latency| latency.as_millis() as u64),
+            error: test_webhook_result.error().map(str::to_string),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedWebhookRequestApi {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<HttpHeaderApi>,
+    pub body: String,
+}
+
+impl From<&RenderedWebhookRequest> for RenderedWebhookRequestApi {
+    fn from(rendered_webhook_request: &RenderedWebhookRequest) -> Self {
+        Self {
+            method: rendered_webhook_request.method().to_string(),
+            url: rendered_webhook_request.url().to_string(),
+            headers: HttpHeaderApi::from_pairs(rendered_webhook_request.headers()),
+            body: rendered_webhook_request.body().to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TestWebhookResponseApi {
+    pub status: u16,
+    pub headers: Vec<HttpHeaderApi>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpHeaderApi {
+    pub name: String,
+    pub value: String,
+}
+
+impl HttpHeaderApi {
+    fn from_pairs(headers: &[(String, String)]) -> Vec<Self> {
+        headers.iter()
+            .map(|(name, value)| Self { name: name.clone(), value: value.clone() })
+            .collect()
+    }
+}
+
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateJobDoneWatcherRequestApi {
     pub job_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
     #[serde(default = "default_timeout_seconds")]
     pub timeout_seconds: u32,
     pub job_done_trigger_webhooks: Vec<CreateJobDoneTriggerWebhookRequestApi>,
 }
 
 impl TryFrom<CreateJobDoneWatcherRequestApi> for CreateJobDoneWatcherRequest {
-    type Error = anyhow::Error;
+    type Error = CreateJobDoneWatcherRequestError;
 
     fn try_from(value: CreateJobDoneWatcherRequestApi) -> Result<Self, Self::Error> {
         let mut webhooks = Vec::with_capacity(value.job_done_trigger_webhooks.len());
-        for webhook in value.job_done_trigger_webhooks {
-            webhooks.push(CreateJobDoneTriggerWebhookRequest::try_from(webhook)?);
+        for (index, webhook) in value.job_done_trigger_webhooks.into_iter().enumerate() {
+            webhooks.push(CreateJobDoneTriggerWebhookRequest::try_from(webhook)
+                .map_err(|source| CreateJobDoneWatcherRequestError::InvalidJobDoneTriggerWebhook { index, source })?);
         }
-        CreateJobDoneWatcherRequest::new(&value.job_name, value.timeout_seconds, webhooks)
+        CreateJobDoneWatcherRequest::new(&value.job_name, value.namespace.as_deref(), value.timeout_seconds, webhooks)
     }
 }
 
@@ -117,6 +229,196 @@ impl From<JobDoneTriggerWebhook> for JobDoneTriggerWebhookApi {
     }
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RedeliverRequestApi {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_done_trigger_webhook_ids: Option<Vec<String>>,
+}
+
+impl RedeliverRequestApi {
+    pub fn job_done_trigger_webhook_ids(&self) -> Result<Option<Vec<Uuid>>, InvalidParamApi> {
+        self.job_done_trigger_webhook_ids.as_ref()
+            .map(|ids| ids.iter()
+                .enumerate()
+                .map(|(index, id)| Uuid::parse_str(id)
+                    .map_err(|error| InvalidParamApi::new(&format!("jobDoneTriggerWebhookIds[{}]", index), &error.to_string())))
+                .collect())
+            .transpose()
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct JobDoneTriggerWebhookAttemptApi {
+    pub id: Uuid,
+    pub job_done_trigger_webhook_id: Uuid,
+    pub status: JobDoneTriggerWebhookStatusApi,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<RenderedWebhookRequestApi>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_headers: Option<Vec<HttpHeaderApi>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<String>,
+    pub attempted_at: DateTime<Utc>,
+}
+
+impl From<&JobDoneTriggerWebhookAttempt> for JobDoneTriggerWebhookAttemptApi {
+    fn from(job_done_trigger_webhook_attempt: &JobDoneTriggerWebhookAttempt) -> Self {
+        Self {
+            id: job_done_trigger_webhook_attempt.id(),
+            job_done_trigger_webhook_id: job_done_trigger_webhook_attempt.job_done_trigger_webhook_id(),
+            status: JobDoneTriggerWebhookStatusApi::from(job_done_trigger_webhook_attempt.status()),
+            response_status_code: job_done_trigger_webhook_attempt.response_status_code(),
+            error: job_done_trigger_webhook_attempt.error().map(str::to_string),
+            request: job_done_trigger_webhook_attempt.request().map(RenderedWebhookRequestApi::from),
+            response_headers: job_done_trigger_webhook_attempt.response_headers().map(HttpHeaderApi::from_pairs),
+            response_body: job_done_trigger_webhook_attempt.response_body().map(str::to_string),
+            attempted_at: job_done_trigger_webhook_attempt.attempted_at(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterApi {
+    pub id: Uuid,
+    pub job_done_watcher_id: Uuid,
+    pub job_done_trigger_webhook_id: Uuid,
+    pub webhook_id: Uuid,
+    pub job_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<RenderedWebhookRequestApi>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_headers: Option<Vec<HttpHeaderApi>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<String>,
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_done_trigger_webhook_attempts: Option<Vec<JobDoneTriggerWebhookAttemptApi>>,
+}
+
+impl DeadLetterApi {
+    pub fn with_attempts(mut self, job_done_trigger_webhook_attempts: &[JobDoneTriggerWebhookAttempt]) -> Self {
+        self.job_done_trigger_webhook_attempts = Some(job_done_trigger_webhook_attempts.iter().map(JobDoneTriggerWebhookAttemptApi::from).collect());
+        self
+    }
+}
+
+impl From<&DeadLetter> for DeadLetterApi {
+    fn from(dead_letter: &DeadLetter) -> Self {
+        Self {
+            id: dead_letter.id(),
+            job_done_watcher_id: dead_letter.job_done_watcher_id(),
+            job_done_trigger_webhook_id: dead_letter.job_done_trigger_webhook_id(),
+            webhook_id: dead_letter.webhook_id(),
+            job_name: dead_letter.job_name().to_string(),
+            namespace: dead_letter.namespace().map(|namespace| namespace.to_string()),
+            request: dead_letter.request().map(RenderedWebhookRequestApi::from),
+            response_status_code: dead_letter.response_status_code(),
+            error: dead_letter.error().map(str::to_string),
+            response_headers: dead_letter.response_headers().map(HttpHeaderApi::from_pairs),
+            response_body: dead_letter.response_body().map(str::to_string),
+            attempts: dead_letter.attempts(),
+            created_at: dead_letter.created_at(),
+            job_done_trigger_webhook_attempts: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLettersQueryApi {
+    pub webhook_id: Option<Uuid>,
+    pub job_name: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub order: SortOrderApi,
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+impl TryFrom<&DeadLettersQueryApi> for DeadLetterFilter {
+    type Error = JobNameError;
+
+    fn try_from(value: &DeadLettersQueryApi) -> Result<Self, Self::Error> {
+        Ok(DeadLetterFilter::new(
+            value.webhook_id,
+            value.job_name.as_deref().map(JobName::new).transpose()?,
+            value.from,
+            value.to,
+        ))
+    }
+}
+
+impl TryFrom<&DeadLettersQueryApi> for PageRequest {
+    type Error = PageRequestError;
+
+    fn try_from(value: &DeadLettersQueryApi) -> Result<Self, Self::Error> {
+        PageRequest::new(value.order.into(), value.cursor.as_deref(), value.limit)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RedeliverDeadLettersRequestApi {
+    pub ids: Vec<String>,
+}
+
+impl RedeliverDeadLettersRequestApi {
+    pub fn ids(&self) -> Result<Vec<Uuid>, InvalidParamApi> {
+        if self.ids.is_empty() {
+            return Err(InvalidParamApi::new("ids", "At least one dead letter id is required"));
+        }
+
+        self.ids.iter()
+            .enumerate()
+            .map(|(index, id)| Uuid::parse_str(id)
+                .map_err(|error| InvalidParamApi::new(&format!("ids[{}]", index), &error.to_string())))
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterRedeliveryApi {
+    pub id: Uuid,
+    pub status: DeadLetterRedeliveryStatusApi,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl From<&(Uuid, DeadLetterRedelivery)> for DeadLetterRedeliveryApi {
+    fn from((id, dead_letter_redelivery): &(Uuid, DeadLetterRedelivery)) -> Self {
+        let (status, detail) = match dead_letter_redelivery {
+            DeadLetterRedelivery::Accepted => (DeadLetterRedeliveryStatusApi::Accepted, None),
+            DeadLetterRedelivery::NotFound => (DeadLetterRedeliveryStatusApi::NotFound, None),
+            DeadLetterRedelivery::Rejected(detail) => (DeadLetterRedeliveryStatusApi::Rejected, Some(detail.clone())),
+        };
+        Self { id: *id, status, detail }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeadLetterRedeliveryStatusApi {
+    Accepted,
+    NotFound,
+    Rejected,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, Copy)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum JobDoneTriggerWebhookStatusApi {
@@ -157,6 +459,10 @@ impl From<JobDoneTriggerWebhookStatus> for JobDoneTriggerWebhookStatusApi {
 pub struct JobDoneWatcherApi {
     pub id: Uuid,
     pub job_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
     #[serde(skip_serializing_if = "is_zero")]
     pub timeout_seconds: u32,
     pub status: JobDoneWatcherStatusApi,
@@ -169,6 +475,8 @@ impl From<JobDoneWatcher> for JobDoneWatcherApi {
         JobDoneWatcherApi {
             id: job_done_watcher.id(),
             job_name: job_done_watcher.job_name().to_string(),
+            namespace: job_done_watcher.namespace().map(ToString::to_string),
+            tenant: job_done_watcher.tenant().map(str::to_string),
             timeout_seconds: job_done_watcher.timeout_seconds(),
             status: JobDoneWatcherStatusApi::from(job_done_watcher.status()),
             created_at: job_done_watcher.created_at(),
@@ -209,6 +517,401 @@ impl From<JobDoneWatcherStatus> for JobDoneWatcherStatusApi {
     }
 }
 
+impl From<JobDoneWatcherStatusApi> for JobDoneWatcherStatus {
+    fn from(value: JobDoneWatcherStatusApi) -> Self {
+        match value {
+            JobDoneWatcherStatusApi::Completed => JobDoneWatcherStatus::Completed,
+            JobDoneWatcherStatusApi::PartiallyCompleted => JobDoneWatcherStatus::PartiallyCompleted,
+            JobDoneWatcherStatusApi::Pending => JobDoneWatcherStatus::Pending,
+            JobDoneWatcherStatusApi::Processing => JobDoneWatcherStatus::Processing,
+            JobDoneWatcherStatusApi::Cancelled => JobDoneWatcherStatus::Cancelled,
+            JobDoneWatcherStatusApi::Failed => JobDoneWatcherStatus::Failed,
+            JobDoneWatcherStatusApi::Timeout => JobDoneWatcherStatus::Timeout,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrderApi {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl From<SortOrderApi> for SortOrder {
+    fn from(value: SortOrderApi) -> Self {
+        match value {
+            SortOrderApi::Asc => SortOrder::Ascending,
+            SortOrderApi::Desc => SortOrder::Descending,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JobDoneWatchersQueryApi {
+    pub job_name: Option<String>,
+    pub status: Option<JobDoneWatcherStatusApi>,
+    pub namespace: Option<String>,
+    pub webhook_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub order: SortOrderApi,
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+impl TryFrom<&JobDoneWatchersQueryApi> for JobDoneWatcherFilter {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &JobDoneWatchersQueryApi) -> Result<Self, Self::Error> {
+        Ok(JobDoneWatcherFilter::new(
+            value.job_name.as_deref().map(JobName::new).transpose()?,
+            value.status.map(JobDoneWatcherStatus::from),
+            value.namespace.as_deref().map(Namespace::new).transpose()?,
+            value.webhook_id,
+            value.from,
+            value.to,
+        ))
+    }
+}
+
+impl TryFrom<&JobDoneWatchersQueryApi> for PageRequest {
+    type Error = PageRequestError;
+
+    fn try_from(value: &JobDoneWatchersQueryApi) -> Result<Self, Self::Error> {
+        PageRequest::new(value.order.into(), value.cursor.as_deref(), value.limit)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhooksQueryApi {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub order: SortOrderApi,
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+impl From<&WebhooksQueryApi> for WebhookFilter {
+    fn from(value: &WebhooksQueryApi) -> Self {
+        WebhookFilter::new(value.from, value.to)
+    }
+}
+
+impl TryFrom<&WebhooksQueryApi> for PageRequest {
+    type Error = PageRequestError;
+
+    fn try_from(value: &WebhooksQueryApi) -> Result<Self, Self::Error> {
+        PageRequest::new(value.order.into(), value.cursor.as_deref(), value.limit)
+    }
+}
+
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitQueryApi {
+    pub timeout: Option<u64>,
+}
+
+impl TryFrom<&WaitQueryApi> for WaitTimeout {
+    type Error = WaitTimeoutError;
+
+    fn try_from(value: &WaitQueryApi) -> Result<Self, Self::Error> {
+        WaitTimeout::new(value.timeout)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JobWaitQueryApi {
+    pub namespace: Option<String>,
+    pub timeout: Option<u64>,
+}
+
+impl TryFrom<&JobWaitQueryApi> for WaitTimeout {
+    type Error = WaitTimeoutError;
+
+    fn try_from(value: &JobWaitQueryApi) -> Result<Self, Self::Error> {
+        WaitTimeout::new(value.timeout)
+    }
+}
+
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EventsQueryApi {
+    pub job_name: Option<String>,
+    pub status: Option<JobDoneWatcherStatusApi>,
+}
+
+impl TryFrom<&EventsQueryApi> for EventFilter {
+    type Error = JobNameError;
+
+    fn try_from(value: &EventsQueryApi) -> Result<Self, Self::Error> {
+        Ok(EventFilter::new(
+            value.job_name.as_deref().map(JobName::new).transpose()?,
+            value.status.map(JobDoneWatcherStatus::from),
+        ))
+    }
+}
+
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct JobFamilyDeliveriesQueryApi {
+    pub job_family: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl From<JobFamilyDeliveriesQueryApi> for JobFamilyDeliveryFilter {
+    fn from(value: JobFamilyDeliveriesQueryApi) -> Self {
+        JobFamilyDeliveryFilter::new(value.job_family, value.from, value.to)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct JobFamilyDeliveryApi {
+    pub id: Uuid,
+    pub job_family_watcher_id: Uuid,
+    pub job_family: String,
+    pub job_name: String,
+    pub job_outcome: JobOutcomeApi,
+    pub status: JobFamilyDeliveryStatusApi,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl From<JobFamilyDelivery> for JobFamilyDeliveryApi {
+    fn from(job_family_delivery: JobFamilyDelivery) -> Self {
+        Self {
+            id: job_family_delivery.id(),
+            job_family_watcher_id: job_family_delivery.job_family_watcher_id(),
+            job_family: job_family_delivery.job_family().to_string(),
+            job_name: job_family_delivery.job_name().to_string(),
+            job_outcome: JobOutcomeApi::from(job_family_delivery.job_outcome()),
+            status: JobFamilyDeliveryStatusApi::from(job_family_delivery.status()),
+            attempts: job_family_delivery.attempts(),
+            response_status_code: job_family_delivery.response_status_code(),
+            error: job_family_delivery.error().map(str::to_string),
+            tenant: job_family_delivery.tenant().map(str::to_string),
+            created_at: job_family_delivery.created_at(),
+            last_attempt_at: job_family_delivery.last_attempt_at(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobOutcomeApi {
+    Succeeded,
+    Failed,
+}
+
+impl From<JobOutcome> for JobOutcomeApi {
+    fn from(value: JobOutcome) -> Self {
+        match value {
+            JobOutcome::Succeeded => JobOutcomeApi::Succeeded,
+            JobOutcome::Failed => JobOutcomeApi::Failed,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobFamilyDeliveryStatusApi {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl From<JobFamilyDeliveryStatus> for JobFamilyDeliveryStatusApi {
+    fn from(value: JobFamilyDeliveryStatus) -> Self {
+        match value {
+            JobFamilyDeliveryStatus::Pending => JobFamilyDeliveryStatusApi::Pending,
+            JobFamilyDeliveryStatus::Delivered => JobFamilyDeliveryStatusApi::Delivered,
+            JobFamilyDeliveryStatus::Failed => JobFamilyDeliveryStatusApi::Failed,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeQueryApi {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReportApi {
+    pub dry_run: bool,
+    pub job_done_watchers: Vec<PurgedJobDoneWatchersApi>,
+    pub job_family_deliveries: u64,
+    pub idempotency_keys: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgedJobDoneWatchersApi {
+    pub status: JobDoneWatcherStatusApi,
+    pub count: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CircuitStateApi {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl From<CircuitState> for CircuitStateApi {
+    fn from(value: CircuitState) -> Self {
+        match value {
+            CircuitState::Closed => CircuitStateApi::Closed,
+            CircuitState::Open => CircuitStateApi::Open,
+            CircuitState::HalfOpen => CircuitStateApi::HalfOpen,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DestinationApi {
+    pub destination: String,
+    pub circuit_state: CircuitStateApi,
+    pub consecutive_failures: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opened_at: Option<DateTime<Utc>>,
+    pub queued_deliveries: usize,
+}
+
+impl From<&Destination> for DestinationApi {
+    fn from(destination: &Destination) -> Self {
+        Self {
+            destination: destination.name().to_string(),
+            circuit_state: CircuitStateApi::from(destination.circuit_state()),
+            consecutive_failures: destination.consecutive_failures(),
+            opened_at: destination.opened_at(),
+            queued_deliveries: destination.queued_deliveries(),
+        }
+    }
+}
+
+impl From<PurgeReport> for PurgeReportApi {
+    fn from(value: PurgeReport) -> Self {
+        PurgeReportApi {
+            dry_run: value.dry_run(),
+            job_done_watchers: value.job_done_watchers()
+                .iter()
+                .map(|(status, count)| PurgedJobDoneWatchersApi {
+                    status: JobDoneWatcherStatusApi::from(*status),
+                    count: *count,
+                })
+                .collect(),
+            job_family_deliveries: value.job_family_deliveries(),
+            idempotency_keys: value.idempotency_keys(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScopeApi {
+    Read,
+    Write,
+    Admin,
+    Metrics,
+}
+
+impl From<ApiScope> for ApiScopeApi {
+    fn from(value: ApiScope) -> Self {
+        match value {
+            ApiScope::Read => ApiScopeApi::Read,
+            ApiScope::Write => ApiScopeApi::Write,
+            ApiScope::Admin => ApiScopeApi::Admin,
+            ApiScope::Metrics => ApiScopeApi::Metrics,
+        }
+    }
+}
+
+impl From<ApiScopeApi> for ApiScope {
+    fn from(value: ApiScopeApi) -> Self {
+        match value {
+            ApiScopeApi::Read => ApiScope::Read,
+            ApiScopeApi::Write => ApiScope::Write,
+            ApiScopeApi::Admin => ApiScope::Admin,
+            ApiScopeApi::Metrics => ApiScope::Metrics,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CreateApiKeyRequestApi {
+    pub name: String,
+    pub scopes: Vec<ApiScopeApi>,
+    pub tenant: Option<String>,
+}
+
+impl TryFrom<CreateApiKeyRequestApi> for CreateApiKeyRequest {
+    type Error = CreateApiKeyRequestError;
+
+    fn try_from(value: CreateApiKeyRequestApi) -> Result<Self, Self::Error> {
+        CreateApiKeyRequest::new(&value.name, value.scopes.into_iter().map(ApiScope::from).collect(), value.tenant.as_deref())
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyApi {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<ApiScopeApi>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    pub created_at: DateTime<Utc>,
+    // Only returned on creation, the key is stored hashed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+impl From<&ApiKey> for ApiKeyApi {
+    fn from(api_key: &ApiKey) -> Self {
+        Self {
+            id: api_key.id(),
+            name: api_key.name().to_string(),
+            scopes: api_key.scopes().iter().copied().map(ApiScopeApi::from).collect(),
+            tenant: api_key.tenant().map(str::to_string),
+            created_at: api_key.created_at(),
+            key: None,
+        }
+    }
+}
+
+impl From<&CreatedApiKey> for ApiKeyApi {
+    fn from(created_api_key: &CreatedApiKey) -> Self {
+        Self {
+            key: Some(created_api_key.key().to_string()),
+            ..Self::from(created_api_key.api_key())
+        }
+    }
+}
+
 
 fn is_zero(value: &u32) -> bool {
     *value == 0
@@ -217,3 +920,85 @@ fn is_zero(value: &u32) -> bool {
 fn default_timeout_seconds() -> u32 {
     0
 }
+
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemDetailsApi {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub invalid_params: Vec<InvalidParamApi>,
+}
+
+impl ProblemDetailsApi {
+    pub fn new(status: StatusCode, detail: Option<String>, invalid_params: Vec<InvalidParamApi>) -> Self {
+        Self {
+            problem_type: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail,
+            invalid_params,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidParamApi {
+    pub name: String,
+    pub reason: String,
+}
+
+impl InvalidParamApi {
+    pub fn new(name: &str, reason: &str) -> Self {
+        Self { name: name.to_string(), reason: reason.to_string() }
+    }
+}
+
+impl From<&CreateWebhookRequestError> for InvalidParamApi {
+    fn from(value: &CreateWebhookRequestError) -> Self {
+        match value {
+            CreateWebhookRequestError::InvalidHttpUrl(error) => InvalidParamApi::new("url", &error.to_string()),
+            CreateWebhookRequestError::InvalidRateLimit => InvalidParamApi::new("rateLimitPerSecond", &value.to_string()),
+        }
+    }
+}
+
+impl From<&CreateApiKeyRequestError> for InvalidParamApi {
+    fn from(value: &CreateApiKeyRequestError) -> Self {
+        match value {
+            CreateApiKeyRequestError::InvalidName => InvalidParamApi::new("name", &value.to_string()),
+            CreateApiKeyRequestError::MissingScopes => InvalidParamApi::new("scopes", &value.to_string()),
+            CreateApiKeyRequestError::InvalidTenant => InvalidParamApi::new("tenant", &value.to_string()),
+        }
+    }
+}
+
+impl From<&TestWebhookRequestError> for InvalidParamApi {
+    fn from(value: &TestWebhookRequestError) -> Self {
+        match value {
+            TestWebhookRequestError::InvalidJobName(error) => InvalidParamApi::new("jobName", &error.to_string()),
+            TestWebhookRequestError::InvalidNamespace(error) => InvalidParamApi::new("namespace", &error.to_string()),
+        }
+    }
+}
+
+impl From<&CreateJobDoneWatcherRequestError> for InvalidParamApi {
+    fn from(value: &CreateJobDoneWatcherRequestError) -> Self {
+        match value {
+            CreateJobDoneWatcherRequestError::InvalidJobName(error) => InvalidParamApi::new("jobName", &error.to_string()),
+            CreateJobDoneWatcherRequestError::InvalidNamespace(error) => InvalidParamApi::new("namespace", &error.to_string()),
+            CreateJobDoneWatcherRequestError::InvalidJobDoneTriggerWebhook { index, source } => match source {
+                CreateJobDoneTriggerWebhookRequestError::InvalidWebhookId(error) => InvalidParamApi::new(
+                    &format!("jobDoneTriggerWebhooks[{}].webhookId", index),
+                    &error.to_string(),
+                ),
+            },
+        }
+    }
+}
\ No newline at end of file
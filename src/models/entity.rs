@@ -6,7 +6,7 @@ use k8s_openapi::serde_json;
 use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::models::service::{JobDoneTriggerWebhook, JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherStatus, JobFamilyWatcher, JobName, Webhook};
+use crate::models::service::{ApiKey, ApiScope, DeadLetter, IdempotencyKey, JobDoneTriggerWebhook, JobDoneTriggerWebhookAttempt, JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherStatus, JobFamilyDelivery, JobFamilyDeliveryStatus, JobFamilyState, JobFamilyWatcher, JobFamilyWatcherConditions, JobName, JobOutcome, Namespace, RenderedWebhookRequest, Webhook};
 
 #[derive(sqlx::FromRow, Debug)]
 pub struct WebhookEntity {
@@ -14,6 +14,9 @@ pub struct WebhookEntity {
     pub url: String,
     pub request_body: String,
     pub description: String,
+    pub tenant: Option<String>,
+    pub tls_profile: Option<String>,
+    pub rate_limit_per_second: Option<f64>,
     pub created_at: chrono::DateTime<Utc>,
 }
 
@@ -25,7 +28,9 @@ impl From<WebhookEntity> for Webhook {
             webhook_entity.request_body.as_str(),
             webhook_entity.description.as_str(),
             webhook_entity.created_at
-        )
+        ).with_tenant(webhook_entity.tenant.as_deref())
+         .with_tls_profile(webhook_entity.tls_profile.as_deref())
+         .with_rate_limit_per_second(webhook_entity.rate_limit_per_second)
     }
 }
 
@@ -37,7 +42,9 @@ impl From<&WebhookEntity> for Webhook {
             webhook_entity.request_body.as_str(),
             webhook_entity.description.as_str(),
             webhook_entity.created_at
-        )
+        ).with_tenant(webhook_entity.tenant.as_deref())
+         .with_tls_profile(webhook_entity.tls_profile.as_deref())
+         .with_rate_limit_per_second(webhook_entity.rate_limit_per_second)
     }
 }
 
@@ -47,8 +54,12 @@ pub struct JobDoneWatcherEntity {
     pub id: String,
     pub job_name: String,
     pub timeout_seconds: i64,
+    #[sqlx(try_from = "String")]
     pub status: JobDoneWatcherStatusEntity,
+    pub namespace: Option<String>,
+    pub tenant: Option<String>,
     pub created_at: chrono::DateTime<Utc>,
+    #[sqlx(try_from = "String")]
     pub job_done_trigger_webhooks: JobDoneTriggerWebhooksEntity,
 }
 
@@ -90,11 +101,12 @@ impl From<JobDoneWatcherEntity> for JobDoneWatcher {
         Self::new(
             Uuid::parse_str(&job_done_watcher_entity.id).expect("Uuid from db should be correct!"),
             JobName::new(&job_done_watcher_entity.job_name).expect("Job name should be valid"),
+            job_done_watcher_entity.namespace.as_deref().map(|namespace| Namespace::new(namespace).expect("Namespace should be valid")),
             job_done_watcher_entity.timeout_seconds as u32,
             job_done_watcher_entity.job_done_trigger_webhooks.iter().map(JobDoneTriggerWebhook::from).collect(),
             job_done_watcher_entity.status.into(),
             job_done_watcher_entity.created_at,
-        )
+        ).with_tenant(job_done_watcher_entity.tenant.as_deref())
     }
 }
 
@@ -142,6 +154,8 @@ impl From<String> for JobDoneTriggerWebhooksEntity {
 #[derive(Clone, Debug, Copy, Deserialize)]
 pub enum JobDoneTriggerWebhookStatusEntity {
     Called,
+    // Rows of a replica not yet running the NotCalled status migration.
+    #[serde(alias = "Not Called")]
     NotCalled,
     Failed,
     Timeout,
@@ -206,20 +220,250 @@ impl From<String> for JobDoneWatcherStatusEntity {
 #[derive(Clone, Debug, sqlx::FromRow)]
 pub struct JobFamilyWatcherEntity {
     pub id: String,
+    pub name: String,
     pub job_family: String,
     pub url: String,
     pub request_body: String,
     pub description: String,
+    pub on_outcomes: String,
+    pub min_duration_seconds: Option<i64>,
+    pub consecutive_failures: Option<i64>,
+    pub on_state_change: bool,
+    pub tenant: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
 impl From<JobFamilyWatcherEntity> for JobFamilyWatcher {
     fn from(job_family_watcher_entity: JobFamilyWatcherEntity) -> Self {
+        let on = job_family_watcher_entity.on_outcomes
+            .split(',')
+            .map(|outcome| JobOutcome::try_from(outcome).expect("Job outcome from db should be correct!"))
+            .collect();
+
         Self::new(
+            Uuid::parse_str(&job_family_watcher_entity.id).expect("Uuid from db should be correct!"),
             &job_family_watcher_entity.job_family,
             &job_family_watcher_entity.url,
             &job_family_watcher_entity.request_body,
             &job_family_watcher_entity.description,
+            on,
+            JobFamilyWatcherConditions::new(
+                job_family_watcher_entity.min_duration_seconds.map(|value| value as u64),
+                job_family_watcher_entity.consecutive_failures.map(|value| value as u32),
+                job_family_watcher_entity.on_state_change,
+            ),
         ).expect("JobFamilyWatcher::new should not fail for valid JobFamilyWatcherEntity")
+            .with_name(&job_family_watcher_entity.name)
+            .with_tenant(job_family_watcher_entity.tenant.as_deref())
+    }
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct JobFamilyStateEntity {
+    pub job_family: String,
+    pub last_outcome: String,
+    pub consecutive_failures: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<JobFamilyStateEntity> for JobFamilyState {
+    fn from(job_family_state_entity: JobFamilyStateEntity) -> Self {
+        Self::new(
+            &job_family_state_entity.job_family,
+            JobOutcome::try_from(job_family_state_entity.last_outcome.as_str()).expect("Job outcome from db should be correct!"),
+            job_family_state_entity.consecutive_failures as u32,
+            job_family_state_entity.updated_at,
+        )
     }
-}
\ No newline at end of file
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct JobFamilyDeliveryEntity {
+    pub id: String,
+    pub job_family_watcher_id: String,
+    pub job_family: String,
+    pub job_name: String,
+    pub job_outcome: String,
+    pub status: String,
+    pub attempts: i64,
+    pub response_status_code: Option<i64>,
+    pub error: Option<String>,
+    pub tenant: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl From<JobFamilyDeliveryEntity> for JobFamilyDelivery {
+    fn from(job_family_delivery_entity: JobFamilyDeliveryEntity) -> Self {
+        let mut job_family_delivery = Self::new(
+            Uuid::parse_str(&job_family_delivery_entity.id).expect("Uuid from db should be correct!"),
+            Uuid::parse_str(&job_family_delivery_entity.job_family_watcher_id).expect("Uuid from db should be correct!"),
+            &job_family_delivery_entity.job_family,
+            JobName::new(&job_family_delivery_entity.job_name).expect("Job name should be valid"),
+            JobOutcome::try_from(job_family_delivery_entity.job_outcome.as_str()).expect("Job outcome from db should be correct!"),
+            job_family_delivery_entity.created_at,
+        ).with_tenant(job_family_delivery_entity.tenant.as_deref());
+        job_family_delivery.set_status(match job_family_delivery_entity.status.as_str() {
+            "Pending" => JobFamilyDeliveryStatus::Pending,
+            "Delivered" => JobFamilyDeliveryStatus::Delivered,
+            "Failed" => JobFamilyDeliveryStatus::Failed,
+            _ => panic!("From<JobFamilyDeliveryEntity> JobFamilyDeliveryStatus"),
+        });
+        job_family_delivery.set_attempts(job_family_delivery_entity.attempts as u32);
+        job_family_delivery.set_response_status_code(job_family_delivery_entity.response_status_code.map(|code| code as u16));
+        job_family_delivery.set_error(job_family_delivery_entity.error);
+        if let Some(last_attempt_at) = job_family_delivery_entity.last_attempt_at {
+            job_family_delivery.set_last_attempt_at(last_attempt_at);
+        }
+        job_family_delivery
+    }
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct JobDoneTriggerWebhookAttemptEntity {
+    pub id: String,
+    pub job_done_watcher_id: String,
+    pub job_done_trigger_webhook_id: String,
+    pub status: String,
+    pub response_status_code: Option<i64>,
+    pub error: Option<String>,
+    pub request_method: Option<String>,
+    pub request_url: Option<String>,
+    pub request_headers: Option<String>,
+    pub request_body: Option<String>,
+    pub response_headers: Option<String>,
+    pub response_body: Option<String>,
+    pub attempted_at: DateTime<Utc>,
+}
+
+impl From<JobDoneTriggerWebhookAttemptEntity> for JobDoneTriggerWebhookAttempt {
+    fn from(job_done_trigger_webhook_attempt_entity: JobDoneTriggerWebhookAttemptEntity) -> Self {
+        Self::new(
+            Uuid::parse_str(&job_done_trigger_webhook_attempt_entity.id).expect("Uuid from db should be correct!"),
+            Uuid::parse_str(&job_done_trigger_webhook_attempt_entity.job_done_watcher_id).expect("Uuid from db should be correct!"),
+            Uuid::parse_str(&job_done_trigger_webhook_attempt_entity.job_done_trigger_webhook_id).expect("Uuid from db should be correct!"),
+            JobDoneTriggerWebhookStatus::try_from(job_done_trigger_webhook_attempt_entity.status.as_str()).expect("Status from db should be correct!"),
+            job_done_trigger_webhook_attempt_entity.response_status_code.map(|code| code as u16),
+            job_done_trigger_webhook_attempt_entity.error,
+            job_done_trigger_webhook_attempt_entity.attempted_at,
+        ).with_request(to_rendered_webhook_request(
+            job_done_trigger_webhook_attempt_entity.request_method,
+            job_done_trigger_webhook_attempt_entity.request_url,
+            job_done_trigger_webhook_attempt_entity.request_headers,
+            job_done_trigger_webhook_attempt_entity.request_body,
+        )).with_response(
+            job_done_trigger_webhook_attempt_entity.response_headers.map(|headers| headers_from_json(&headers)),
+            job_done_trigger_webhook_attempt_entity.response_body,
+        )
+    }
+}
+
+fn to_rendered_webhook_request(method: Option<String>, url: Option<String>, headers: Option<String>, body: Option<String>) -> Option<RenderedWebhookRequest> {
+    match (method, url) {
+        (Some(method), Some(url)) => Some(RenderedWebhookRequest::new(
+            &method,
+            &url,
+            headers.map(|headers| headers_from_json(&headers)).unwrap_or_default(),
+            body.as_deref().unwrap_or_default(),
+        )),
+        _ => None,
+    }
+}
+
+fn headers_from_json(headers: &str) -> Vec<(String, String)> {
+    serde_json::from_str(headers).expect("Headers from db should be correct!")
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DeadLetterEntity {
+    pub id: String,
+    pub job_done_watcher_id: String,
+    pub job_done_trigger_webhook_id: String,
+    pub webhook_id: String,
+    pub job_name: String,
+    pub namespace: Option<String>,
+    pub request_method: Option<String>,
+    pub request_url: Option<String>,
+    pub request_headers: Option<String>,
+    pub request_body: Option<String>,
+    pub response_status_code: Option<i64>,
+    pub error: Option<String>,
+    pub response_headers: Option<String>,
+    pub response_body: Option<String>,
+    pub attempts: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DeadLetterEntity> for DeadLetter {
+    fn from(dead_letter_entity: DeadLetterEntity) -> Self {
+        let request = to_rendered_webhook_request(
+            dead_letter_entity.request_method,
+            dead_letter_entity.request_url,
+            dead_letter_entity.request_headers,
+            dead_letter_entity.request_body,
+        );
+
+        Self::new(
+            Uuid::parse_str(&dead_letter_entity.id).expect("Uuid from db should be correct!"),
+            Uuid::parse_str(&dead_letter_entity.job_done_watcher_id).expect("Uuid from db should be correct!"),
+            Uuid::parse_str(&dead_letter_entity.job_done_trigger_webhook_id).expect("Uuid from db should be correct!"),
+            Uuid::parse_str(&dead_letter_entity.webhook_id).expect("Uuid from db should be correct!"),
+            JobName::new(&dead_letter_entity.job_name).expect("Job name should be valid"),
+            dead_letter_entity.namespace.as_deref().map(|namespace| Namespace::new(namespace).expect("Namespace should be valid")),
+            request,
+            dead_letter_entity.response_status_code.map(|code| code as u16),
+            dead_letter_entity.error,
+            dead_letter_entity.attempts as u32,
+            dead_letter_entity.created_at,
+        ).with_response(
+            dead_letter_entity.response_headers.map(|headers| headers_from_json(&headers)),
+            dead_letter_entity.response_body,
+        )
+    }
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct IdempotencyKeyEntity {
+    pub scope: String,
+    pub idempotency_key: String,
+    pub fingerprint: String,
+    pub resource_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<IdempotencyKeyEntity> for IdempotencyKey {
+    fn from(idempotency_key_entity: IdempotencyKeyEntity) -> Self {
+        Self::new(
+            &idempotency_key_entity.scope,
+            &idempotency_key_entity.idempotency_key,
+            &idempotency_key_entity.fingerprint,
+            idempotency_key_entity.resource_id.map(|resource_id| resource_id.parse().expect("Resource id from db should be correct!")),
+            idempotency_key_entity.created_at,
+            idempotency_key_entity.expires_at,
+        )
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ApiKeyEntity {
+    pub id: String,
+    pub name: String,
+    pub key_hash: String,
+    pub scopes: String,
+    pub tenant: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ApiKeyEntity> for ApiKey {
+    fn from(api_key_entity: ApiKeyEntity) -> Self {
+        Self::new(
+            Uuid::parse_str(&api_key_entity.id).expect("Uuid from db should be correct!"),
+            &api_key_entity.name,
+            &api_key_entity.key_hash,
+            ApiScope::parse_list(&api_key_entity.scopes).expect("Scopes from db should be correct!"),
+            api_key_entity.tenant.as_deref(),
+            api_key_entity.created_at,
+        )
+    }
+}
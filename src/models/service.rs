@@ -1,37 +1,63 @@
 use std::fmt;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
+use ipnet::IpNet;
 use thiserror::Error;
 use uuid::Uuid;
 use yaml_rust2::Yaml;
 
-pub use http_url::HttpUrl;
+pub use http_url::{HttpUrl, HttpUrlError};
 pub use job_name::{JobName, JobNameError};
+pub use namespace::{Namespace, NamespaceError};
 
 
 mod job_name;
 
 mod http_url;
 
+mod namespace;
+
 #[derive(Debug, Clone)]
 pub struct CreateWebhookRequest {
     url: HttpUrl,
     request_body: String,
     description: String,
+    tls_profile: Option<String>,
+    rate_limit_per_second: Option<f64>,
 }
 
 #[derive(Debug, Error)]
 pub enum CreateWebhookRequestError {
     #[error("Invalid URL format")]
     InvalidHttpUrl(#[from] http_url::HttpUrlError),
+    #[error("Rate limit must be a positive number of requests per second")]
+    InvalidRateLimit,
+}
+
+#[derive(Debug, Error)]
+pub enum CreateWebhookError {
+    #[error(transparent)]
+    Egress(#[from] EgressError),
+    #[error("Unknown TLS profile: {0}")]
+    UnknownTlsProfile(String),
+    #[error(transparent)]
+    Repository(#[from] anyhow::Error),
 }
 
 impl CreateWebhookRequest {
-    pub fn new(url: &str, request_body: &str, description: &str) -> Result<Self, CreateWebhookRequestError> {
+    pub fn new(url: &str, request_body: &str, description: &str, tls_profile: Option<&str>, rate_limit_per_second: Option<f64>) -> Result<Self, CreateWebhookRequestError> {
+        if rate_limit_per_second.is_some_and(|rate_limit_per_second| !rate_limit_per_second.is_finite() || rate_limit_per_second <= 0.0) {
+            return Err(CreateWebhookRequestError::InvalidRateLimit);
+        }
         Ok(Self {
             url: HttpUrl::new(url)?,
             request_body: request_body.to_string(),
             description: description.to_string(),
+            tls_profile: tls_profile.map(str::to_string),
+            rate_limit_per_second,
         })
     }
 
@@ -44,6 +70,12 @@ impl CreateWebhookRequest {
     pub fn description(&self) -> &str {
         &self.description
     }
+    pub fn tls_profile(&self) -> Option<&str> {
+        self.tls_profile.as_deref()
+    }
+    pub fn rate_limit_per_second(&self) -> Option<f64> {
+        self.rate_limit_per_second
+    }
 }
 
 
@@ -53,6 +85,9 @@ pub struct Webhook {
     url: HttpUrl,
     request_body: String,
     description: String,
+    tenant: Option<String>,
+    tls_profile: Option<String>,
+    rate_limit_per_second: Option<f64>,
     created_at: DateTime<Utc>,
 }
 
@@ -69,6 +104,15 @@ impl Webhook {
     pub fn description(&self) -> &str {
         &self.description
     }
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+    pub fn tls_profile(&self) -> Option<&str> {
+        self.tls_profile.as_deref()
+    }
+    pub fn rate_limit_per_second(&self) -> Option<f64> {
+        self.rate_limit_per_second
+    }
     pub fn created_at(&self) -> DateTime<Utc> {
         self.created_at
     }
@@ -79,15 +123,194 @@ impl Webhook {
             url,
             request_body: request_body.to_string(),
             description: description.to_string(),
+            tenant: None,
+            tls_profile: None,
+            rate_limit_per_second: None,
             created_at
         }
     }
+
+    pub fn with_tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(str::to_string);
+        self
+    }
+
+    pub fn with_tls_profile(mut self, tls_profile: Option<&str>) -> Self {
+        self.tls_profile = tls_profile.map(str::to_string);
+        self
+    }
+
+    pub fn with_rate_limit_per_second(mut self, rate_limit_per_second: Option<f64>) -> Self {
+        self.rate_limit_per_second = rate_limit_per_second;
+        self
+    }
+}
+
+
+#[derive(Debug, Error)]
+pub enum TestWebhookRequestError {
+    #[error("Invalid job name: {0}")]
+    InvalidJobName(#[from] JobNameError),
+    #[error("Invalid namespace: {0}")]
+    InvalidNamespace(#[from] NamespaceError),
+}
+
+#[derive(Clone, Debug)]
+pub struct TestWebhookRequest {
+    job_name: JobName,
+    namespace: Option<Namespace>,
+    timeout_seconds: u32,
+    send: bool,
+}
+
+impl TestWebhookRequest {
+    pub fn new(job_name: &str, namespace: Option<&str>, timeout_seconds: u32, send: bool) -> Result<Self, TestWebhookRequestError> {
+        let job_name = JobName::new(job_name)?;
+        let namespace = namespace.map(Namespace::new).transpose()?;
+        Ok(Self { job_name, namespace, timeout_seconds, send })
+    }
+
+    pub fn job_name(&self) -> &JobName {
+        &self.job_name
+    }
+    pub fn namespace(&self) -> Option<&Namespace> {
+        self.namespace.as_ref()
+    }
+    pub fn timeout_seconds(&self) -> u32 {
+        self.timeout_seconds
+    }
+    pub fn send(&self) -> bool {
+        self.send
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RenderedWebhookRequest {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl RenderedWebhookRequest {
+    pub fn new(method: &str, url: &str, headers: Vec<(String, String)>, body: &str) -> Self {
+        Self { method: method.to_string(), url: url.to_string(), headers, body: body.to_string() }
+    }
+
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TestWebhookResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+}
+
+impl TestWebhookResponse {
+    pub fn new(status: u16, headers: Vec<(String, String)>) -> Self {
+        Self { status, headers }
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TestWebhookResult {
+    job_name: JobName,
+    namespace: Option<Namespace>,
+    request: Option<RenderedWebhookRequest>,
+    response: Option<TestWebhookResponse>,
+    latency: Option<std::time::Duration>,
+    error: Option<String>,
+}
+
+impl TestWebhookResult {
+    pub fn new(test_webhook_request: &TestWebhookRequest) -> Self {
+        Self {
+            job_name: test_webhook_request.job_name().clone(),
+            namespace: test_webhook_request.namespace().cloned(),
+            request: None,
+            response: None,
+            latency: None,
+            error: None,
+        }
+    }
+
+    pub fn job_name(&self) -> &JobName {
+        &self.job_name
+    }
+    pub fn namespace(&self) -> Option<&Namespace> {
+        self.namespace.as_ref()
+    }
+    pub fn request(&self) -> Option<&RenderedWebhookRequest> {
+        self.request.as_ref()
+    }
+    pub fn response(&self) -> Option<&TestWebhookResponse> {
+        self.response.as_ref()
+    }
+    pub fn latency(&self) -> Option<std::time::Duration> {
+        self.latency
+    }
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn set_request(&mut self, request: RenderedWebhookRequest) {
+        self.request = Some(request);
+    }
+    pub fn set_response(&mut self, response: TestWebhookResponse) {
+        self.response = Some(response);
+    }
+    pub fn set_latency(&mut self, latency: std::time::Duration) {
+        self.latency = Some(latency);
+    }
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CreateJobDoneWatcherRequestError {
+    #[error("Invalid job name: {0}")]
+    InvalidJobName(#[from] JobNameError),
+    #[error("Invalid namespace: {0}")]
+    InvalidNamespace(#[from] NamespaceError),
+    #[error("Invalid job done trigger webhook at index {index}: {source}")]
+    InvalidJobDoneTriggerWebhook { index: usize, source: CreateJobDoneTriggerWebhookRequestError },
 }
 
+#[derive(Debug, Error)]
+pub enum CreateJobDoneWatcherError {
+    #[error("Webhooks not found: {0:?}")]
+    WebhooksNotFound(Vec<(usize, Uuid)>),
+    #[error("Namespace is required to watch jobs of a tenant")]
+    MissingNamespace,
+    #[error("Namespace {0} is not allowed for the tenant")]
+    NamespaceNotAllowed(Namespace),
+    #[error(transparent)]
+    Repository(#[from] anyhow::Error),
+}
 
 #[derive(Clone, Debug)]
 pub struct CreateJobDoneWatcherRequest {
     job_name: JobName,
+    namespace: Option<Namespace>,
     timeout_seconds: u32,
     job_done_trigger_webhooks: Vec<CreateJobDoneTriggerWebhookRequest>,
 }
@@ -95,16 +318,21 @@ pub struct CreateJobDoneWatcherRequest {
 impl CreateJobDoneWatcherRequest {
     pub fn new(
         job_name: &str,
+        namespace: Option<&str>,
         timeout_seconds: u32,
         job_done_trigger_webhooks: Vec<CreateJobDoneTriggerWebhookRequest>
-    ) -> anyhow::Result<Self> {
+    ) -> Result<Self, CreateJobDoneWatcherRequestError> {
         let job_name = JobName::new(job_name)?;
-        Ok(Self { job_name, timeout_seconds, job_done_trigger_webhooks })
+        let namespace = namespace.map(Namespace::new).transpose()?;
+        Ok(Self { job_name, namespace, timeout_seconds, job_done_trigger_webhooks })
     }
 
     pub fn job_name(&self) -> &JobName {
         &self.job_name
     }
+    pub fn namespace(&self) -> Option<&Namespace> {
+        self.namespace.as_ref()
+    }
     pub fn timeout_seconds(&self) -> u32 {
         self.timeout_seconds
     }
@@ -182,7 +410,7 @@ impl JobDoneTriggerWebhook {
     }
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq)]
 pub enum JobDoneTriggerWebhookStatus {
     Called,
     NotCalled,
@@ -195,7 +423,7 @@ impl fmt::Display for JobDoneTriggerWebhookStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let status_str = match self {
             JobDoneTriggerWebhookStatus::Called => "Called",
-            JobDoneTriggerWebhookStatus::NotCalled => "Not Called",
+            JobDoneTriggerWebhookStatus::NotCalled => "NotCalled",
             JobDoneTriggerWebhookStatus::Failed => "Failed",
             JobDoneTriggerWebhookStatus::Timeout => "Timeout",
             JobDoneTriggerWebhookStatus::Cancelled => "Cancelled",
@@ -204,6 +432,277 @@ impl fmt::Display for JobDoneTriggerWebhookStatus {
     }
 }
 
+impl JobDoneTriggerWebhookStatus {
+    pub fn is_redeliverable(&self) -> bool {
+        matches!(self, JobDoneTriggerWebhookStatus::Called | JobDoneTriggerWebhookStatus::Failed)
+    }
+}
+
+impl TryFrom<&str> for JobDoneTriggerWebhookStatus {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "Called" => Ok(JobDoneTriggerWebhookStatus::Called),
+            "NotCalled" => Ok(JobDoneTriggerWebhookStatus::NotCalled),
+            "Failed" => Ok(JobDoneTriggerWebhookStatus::Failed),
+            "Timeout" => Ok(JobDoneTriggerWebhookStatus::Timeout),
+            "Cancelled" => Ok(JobDoneTriggerWebhookStatus::Cancelled),
+            _ => Err(anyhow::anyhow!("Invalid job done trigger webhook status: {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct JobDoneTriggerWebhookAttempt {
+    id: Uuid,
+    job_done_watcher_id: Uuid,
+    job_done_trigger_webhook_id: Uuid,
+    status: JobDoneTriggerWebhookStatus,
+    response_status_code: Option<u16>,
+    error: Option<String>,
+    // As sent, missing when the webhook no longer exists or can't be rendered.
+    request: Option<RenderedWebhookRequest>,
+    response_headers: Option<Vec<(String, String)>>,
+    response_body: Option<String>,
+    attempted_at: DateTime<Utc>,
+}
+
+impl JobDoneTriggerWebhookAttempt {
+    pub fn new(
+        id: Uuid,
+        job_done_watcher_id: Uuid,
+        job_done_trigger_webhook_id: Uuid,
+        status: JobDoneTriggerWebhookStatus,
+        response_status_code: Option<u16>,
+        error: Option<String>,
+        attempted_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            job_done_watcher_id,
+            job_done_trigger_webhook_id,
+            status,
+            response_status_code,
+            error,
+            request: None,
+            response_headers: None,
+            response_body: None,
+            attempted_at,
+        }
+    }
+
+    pub fn with_request(mut self, request: Option<RenderedWebhookRequest>) -> Self {
+        self.request = request;
+        self
+    }
+
+    pub fn with_response(mut self, response_headers: Option<Vec<(String, String)>>, response_body: Option<String>) -> Self {
+        self.response_headers = response_headers;
+        self.response_body = response_body;
+        self
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+    pub fn job_done_watcher_id(&self) -> Uuid {
+        self.job_done_watcher_id
+    }
+    pub fn job_done_trigger_webhook_id(&self) -> Uuid {
+        self.job_done_trigger_webhook_id
+    }
+    pub fn status(&self) -> JobDoneTriggerWebhookStatus {
+        self.status
+    }
+    pub fn response_status_code(&self) -> Option<u16> {
+        self.response_status_code
+    }
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+    pub fn request(&self) -> Option<&RenderedWebhookRequest> {
+        self.request.as_ref()
+    }
+    pub fn response_headers(&self) -> Option<&[(String, String)]> {
+        self.response_headers.as_deref()
+    }
+    pub fn response_body(&self) -> Option<&str> {
+        self.response_body.as_deref()
+    }
+    pub fn attempted_at(&self) -> DateTime<Utc> {
+        self.attempted_at
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RedeliverJobDoneWatcherError {
+    #[error("Job done watcher not found")]
+    NotFound,
+    #[error("Job done watcher in status {0} can't be redelivered")]
+    InvalidStatus(JobDoneWatcherStatus),
+    #[error("Job done trigger webhooks not found: {0:?}")]
+    TriggersNotFound(Vec<Uuid>),
+    #[error("Job done trigger webhooks never called: {0:?}")]
+    TriggersNotCalled(Vec<Uuid>),
+    #[error("No failed job done trigger webhook to redeliver")]
+    NothingToRedeliver,
+    #[error("Job done watcher status changed concurrently")]
+    Conflict,
+    #[error(transparent)]
+    Repository(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Debug)]
+pub struct DeadLetter {
+    id: Uuid,
+    job_done_watcher_id: Uuid,
+    job_done_trigger_webhook_id: Uuid,
+    webhook_id: Uuid,
+    job_name: JobName,
+    namespace: Option<Namespace>,
+    // Those of the last attempt.
+    request: Option<RenderedWebhookRequest>,
+    response_status_code: Option<u16>,
+    error: Option<String>,
+    response_headers: Option<Vec<(String, String)>>,
+    response_body: Option<String>,
+    attempts: u32,
+    created_at: DateTime<Utc>,
+}
+
+impl DeadLetter {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: Uuid,
+        job_done_watcher_id: Uuid,
+        job_done_trigger_webhook_id: Uuid,
+        webhook_id: Uuid,
+        job_name: JobName,
+        namespace: Option<Namespace>,
+        request: Option<RenderedWebhookRequest>,
+        response_status_code: Option<u16>,
+        error: Option<String>,
+        attempts: u32,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            job_done_watcher_id,
+            job_done_trigger_webhook_id,
+            webhook_id,
+            job_name,
+            namespace,
+            request,
+            response_status_code,
+            error,
+            response_headers: None,
+            response_body: None,
+            attempts,
+            created_at,
+        }
+    }
+
+    pub fn with_response(mut self, response_headers: Option<Vec<(String, String)>>, response_body: Option<String>) -> Self {
+        self.response_headers = response_headers;
+        self.response_body = response_body;
+        self
+    }
+
+    pub fn with_id_and_created_at(mut self, id: Uuid, created_at: DateTime<Utc>) -> Self {
+        self.id = id;
+        self.created_at = created_at;
+        self
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+    pub fn job_done_watcher_id(&self) -> Uuid {
+        self.job_done_watcher_id
+    }
+    pub fn job_done_trigger_webhook_id(&self) -> Uuid {
+        self.job_done_trigger_webhook_id
+    }
+    pub fn webhook_id(&self) -> Uuid {
+        self.webhook_id
+    }
+    pub fn job_name(&self) -> &JobName {
+        &self.job_name
+    }
+    pub fn namespace(&self) -> Option<&Namespace> {
+        self.namespace.as_ref()
+    }
+    pub fn request(&self) -> Option<&RenderedWebhookRequest> {
+        self.request.as_ref()
+    }
+    pub fn response_status_code(&self) -> Option<u16> {
+        self.response_status_code
+    }
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+    pub fn response_headers(&self) -> Option<&[(String, String)]> {
+        self.response_headers.as_deref()
+    }
+    pub fn response_body(&self) -> Option<&str> {
+        self.response_body.as_deref()
+    }
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DeadLetterFilter {
+    webhook_id: Option<Uuid>,
+    job_name: Option<JobName>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    tenant: Option<String>,
+}
+
+impl DeadLetterFilter {
+    pub fn new(webhook_id: Option<Uuid>, job_name: Option<JobName>, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Self {
+        Self { webhook_id, job_name, from, to, tenant: None }
+    }
+
+    pub fn with_tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(str::to_string);
+        self
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    pub fn webhook_id(&self) -> Option<Uuid> {
+        self.webhook_id
+    }
+
+    pub fn job_name(&self) -> Option<&JobName> {
+        self.job_name.as_ref()
+    }
+
+    pub fn from(&self) -> Option<DateTime<Utc>> {
+        self.from
+    }
+
+    pub fn to(&self) -> Option<DateTime<Utc>> {
+        self.to
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DeadLetterRedelivery {
+    Accepted,
+    NotFound,
+    Rejected(String),
+}
+
 #[derive(Clone, Debug, Copy, PartialEq)]
 pub enum JobDoneWatcherStatus {
     Completed,
@@ -215,6 +714,16 @@ pub enum JobDoneWatcherStatus {
     Timeout,
 }
 
+impl JobDoneWatcherStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobDoneWatcherStatus::Pending | JobDoneWatcherStatus::Processing)
+    }
+
+    pub fn is_redeliverable(&self) -> bool {
+        matches!(self, JobDoneWatcherStatus::Completed | JobDoneWatcherStatus::PartiallyCompleted | JobDoneWatcherStatus::Failed)
+    }
+}
+
 impl fmt::Display for JobDoneWatcherStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let status_str = match self {
@@ -235,8 +744,10 @@ impl fmt::Display for JobDoneWatcherStatus {
 pub struct JobDoneWatcher {
     id: Uuid,
     job_name: JobName,
+    namespace: Option<Namespace>,
     timeout_seconds: u32,
     status: JobDoneWatcherStatus,
+    tenant: Option<String>,
     created_at: DateTime<Utc>,
     job_done_trigger_webhooks: Vec<JobDoneTriggerWebhook>,
 }
@@ -245,12 +756,18 @@ impl JobDoneWatcher {
     pub fn new(
         id: Uuid,
         job_name: JobName,
+        namespace: Option<Namespace>,
         timeout_seconds: u32,
         job_done_trigger_webhooks: Vec<JobDoneTriggerWebhook>,
         status: JobDoneWatcherStatus,
         created_at: DateTime<Utc>,
     ) -> Self {
-        Self { id, job_name, timeout_seconds, status, created_at, job_done_trigger_webhooks }
+        Self { id, job_name, namespace, timeout_seconds, status, tenant: None, created_at, job_done_trigger_webhooks }
+    }
+
+    pub fn with_tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(str::to_string);
+        self
     }
 
     pub fn set_status(&mut self, status: JobDoneWatcherStatus) {
@@ -265,12 +782,20 @@ impl JobDoneWatcher {
         &self.job_name
     }
 
+    pub fn namespace(&self) -> Option<&Namespace> {
+        self.namespace.as_ref()
+    }
+
     pub fn timeout_seconds(&self) -> u32 {
         self.timeout_seconds
     }
 
     pub fn status(&self) -> JobDoneWatcherStatus {
-        self.status.clone()
+        self.status
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
     }
 
     pub fn created_at(&self) -> DateTime<Utc> {
@@ -287,24 +812,120 @@ impl JobDoneWatcher {
 }
 
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JobOutcome {
+    Succeeded,
+    Failed,
+}
+
+impl fmt::Display for JobOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome_str = match self {
+            JobOutcome::Succeeded => "succeeded",
+            JobOutcome::Failed => "failed",
+        };
+        write!(f, "{}", outcome_str)
+    }
+}
+
+impl TryFrom<&str> for JobOutcome {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.trim() {
+            "succeeded" => Ok(JobOutcome::Succeeded),
+            "failed" => Ok(JobOutcome::Failed),
+            _ => Err(anyhow::anyhow!("Invalid job outcome: {} (expected 'succeeded' or 'failed')", value)),
+        }
+    }
+}
+
+
+#[derive(Clone, Debug, Default)]
+pub struct JobFamilyWatcherConditions {
+    min_duration_seconds: Option<u64>,
+    consecutive_failures: Option<u32>,
+    on_state_change: bool,
+}
+
+impl JobFamilyWatcherConditions {
+    pub fn new(min_duration_seconds: Option<u64>, consecutive_failures: Option<u32>, on_state_change: bool) -> Self {
+        Self { min_duration_seconds, consecutive_failures, on_state_change }
+    }
+
+    pub fn min_duration_seconds(&self) -> Option<u64> {
+        self.min_duration_seconds
+    }
+
+    pub fn consecutive_failures(&self) -> Option<u32> {
+        self.consecutive_failures
+    }
+
+    pub fn on_state_change(&self) -> bool {
+        self.on_state_change
+    }
+}
+
+
 #[derive(Clone, Debug)]
 pub struct JobFamilyWatcher {
+    id: Uuid,
+    name: String,
     job_family: String,
     url: HttpUrl,
     request_body: String,
-    description: String
+    description: String,
+    on: Vec<JobOutcome>,
+    conditions: JobFamilyWatcherConditions,
+    tenant: Option<String>,
 }
 
 impl JobFamilyWatcher {
-    pub fn new(job_family: &str, url: &str, request_body: &str, description: &str) -> anyhow::Result<Self> {
+    pub fn new(
+        id: Uuid,
+        job_family: &str,
+        url: &str,
+        request_body: &str,
+        description: &str,
+        on: Vec<JobOutcome>,
+        conditions: JobFamilyWatcherConditions,
+    ) -> anyhow::Result<Self> {
+        if on.is_empty() {
+            return Err(anyhow::anyhow!("At least one job outcome must be specified for job family {}", job_family));
+        }
+
         Ok(Self {
+            id,
+            name: job_family.to_string(),
             job_family: job_family.to_string(),
             url: HttpUrl::new(url)?,
             request_body: request_body.to_string(),
-            description: description.to_string()
+            description: description.to_string(),
+            on,
+            conditions,
+            tenant: None,
         })
     }
 
+    /// Names a watcher apart from the other watchers of its job family, the name defaults to the job family.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(str::to_string);
+        self
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn job_family(&self) -> &str {
         &self.job_family
     }
@@ -320,30 +941,1280 @@ impl JobFamilyWatcher {
     pub fn description(&self) -> &str {
         &self.description
     }
-}
+
+    pub fn on(&self) -> &Vec<JobOutcome> {
+        &self.on
+    }
+
+    pub fn conditions(&self) -> &JobFamilyWatcherConditions {
+        &self.conditions
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+}
 
 impl TryFrom<Yaml> for JobFamilyWatcher {
     type Error = anyhow::Error;
 
     fn try_from(yaml: Yaml) -> Result<Self, Self::Error> {
         let job_family = extract_yaml_string(&yaml, "jobFamily")?;
+        let name = extract_yaml_string(&yaml, "name").unwrap_or_else(|_| job_family.clone());
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Invalid value for key: name"));
+        }
         let url = extract_yaml_string(&yaml, "url")?;
         let request_body = extract_yaml_string(&yaml, "requestBody").unwrap_or_default();
         let description = extract_yaml_string(&yaml, "description").unwrap_or_default();
+        let on = match &yaml["on"] {
+            Yaml::BadValue => vec![JobOutcome::Succeeded],
+            Yaml::Array(outcomes) => outcomes.iter()
+                .map(|outcome| match outcome {
+                    Yaml::String(outcome) => JobOutcome::try_from(outcome.as_str()),
+                    _ => Err(anyhow::anyhow!("Invalid value in 'on' for job family {}", job_family)),
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            _ => return Err(anyhow::anyhow!("Invalid value for key: on")),
+        };
+
+        let conditions = &yaml["conditions"];
+        let conditions = JobFamilyWatcherConditions::new(
+            extract_yaml_u64(conditions, "minDurationSeconds")?,
+            extract_yaml_u64(conditions, "consecutiveFailures")?.map(|value| value as u32),
+            conditions["onStateChange"].as_bool().unwrap_or(false),
+        );
+
+        let tenant = extract_yaml_string(&yaml, "tenant").ok();
 
         Ok(Self::new(
+            Uuid::new_v4(),
             &job_family,
             &url,
             &request_body,
             &description,
-        )?)
+            on,
+            conditions,
+        )?.with_name(&name).with_tenant(tenant.as_deref()))
     }
 }
 
 
-fn extract_yaml_string(yaml: &Yaml, key: &str) -> Result<String, anyhow::Error> {
-    match &yaml[key] {
-        Yaml::String(value) => Ok(value.clone()),
-        _ => Err(anyhow::anyhow!("Missing or invalid value for key: {}", key)),
+#[derive(Clone, Debug)]
+pub struct JobFamilyState {
+    job_family: String,
+    last_outcome: JobOutcome,
+    consecutive_failures: u32,
+    updated_at: DateTime<Utc>,
+}
+
+impl JobFamilyState {
+    pub fn new(job_family: &str, last_outcome: JobOutcome, consecutive_failures: u32, updated_at: DateTime<Utc>) -> Self {
+        Self { job_family: job_family.to_string(), last_outcome, consecutive_failures, updated_at }
+    }
+
+    pub fn job_family(&self) -> &str {
+        &self.job_family
+    }
+
+    pub fn last_outcome(&self) -> JobOutcome {
+        self.last_outcome
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JobFamilyDeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl fmt::Display for JobFamilyDeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status_str = match self {
+            JobFamilyDeliveryStatus::Pending => "Pending",
+            JobFamilyDeliveryStatus::Delivered => "Delivered",
+            JobFamilyDeliveryStatus::Failed => "Failed",
+        };
+        write!(f, "{}", status_str)
+    }
+}
+
+
+#[derive(Clone, Debug)]
+pub struct JobFamilyDelivery {
+    id: Uuid,
+    job_family_watcher_id: Uuid,
+    job_family: String,
+    job_name: JobName,
+    job_outcome: JobOutcome,
+    status: JobFamilyDeliveryStatus,
+    attempts: u32,
+    response_status_code: Option<u16>,
+    error: Option<String>,
+    tenant: Option<String>,
+    created_at: DateTime<Utc>,
+    last_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl JobFamilyDelivery {
+    pub fn new(
+        id: Uuid,
+        job_family_watcher_id: Uuid,
+        job_family: &str,
+        job_name: JobName,
+        job_outcome: JobOutcome,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            job_family_watcher_id,
+            job_family: job_family.to_string(),
+            job_name,
+            job_outcome,
+            status: JobFamilyDeliveryStatus::Pending,
+            attempts: 0,
+            response_status_code: None,
+            error: None,
+            tenant: None,
+            created_at,
+            last_attempt_at: None,
+        }
+    }
+
+    pub fn with_tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(str::to_string);
+        self
+    }
+
+    pub fn set_status(&mut self, status: JobFamilyDeliveryStatus) {
+        self.status = status;
+    }
+
+    pub fn set_attempts(&mut self, attempts: u32) {
+        self.attempts = attempts;
+    }
+
+    pub fn set_response_status_code(&mut self, response_status_code: Option<u16>) {
+        self.response_status_code = response_status_code;
+    }
+
+    pub fn set_error(&mut self, error: Option<String>) {
+        self.error = error;
+    }
+
+    pub fn set_last_attempt_at(&mut self, last_attempt_at: DateTime<Utc>) {
+        self.last_attempt_at = Some(last_attempt_at);
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn job_family_watcher_id(&self) -> Uuid {
+        self.job_family_watcher_id
+    }
+
+    pub fn job_family(&self) -> &str {
+        &self.job_family
+    }
+
+    pub fn job_name(&self) -> &JobName {
+        &self.job_name
+    }
+
+    pub fn job_outcome(&self) -> JobOutcome {
+        self.job_outcome
+    }
+
+    pub fn status(&self) -> JobFamilyDeliveryStatus {
+        self.status
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn response_status_code(&self) -> Option<u16> {
+        self.response_status_code
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn last_attempt_at(&self) -> Option<DateTime<Utc>> {
+        self.last_attempt_at
+    }
+}
+
+
+#[derive(Clone, Debug, Default)]
+pub struct JobFamilyDeliveryFilter {
+    job_family: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    tenant: Option<String>,
+}
+
+impl JobFamilyDeliveryFilter {
+    pub fn new(job_family: Option<String>, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Self {
+        Self { job_family, from, to, tenant: None }
+    }
+
+    pub fn with_tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(str::to_string);
+        self
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    pub fn job_family(&self) -> Option<&str> {
+        self.job_family.as_deref()
+    }
+
+    pub fn from(&self) -> Option<DateTime<Utc>> {
+        self.from
+    }
+
+    pub fn to(&self) -> Option<DateTime<Utc>> {
+        self.to
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct JobDoneWatcherFilter {
+    job_name: Option<JobName>,
+    status: Option<JobDoneWatcherStatus>,
+    namespace: Option<Namespace>,
+    webhook_id: Option<Uuid>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    tenant: Option<String>,
+}
+
+impl JobDoneWatcherFilter {
+    pub fn new(
+        job_name: Option<JobName>,
+        status: Option<JobDoneWatcherStatus>,
+        namespace: Option<Namespace>,
+        webhook_id: Option<Uuid>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self { job_name, status, namespace, webhook_id, from, to, tenant: None }
+    }
+
+    /// Restricts the results to the resources of a tenant, all of them when `None`.
+    pub fn with_tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(str::to_string);
+        self
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    pub fn job_name(&self) -> Option<&JobName> {
+        self.job_name.as_ref()
+    }
+
+    pub fn status(&self) -> Option<JobDoneWatcherStatus> {
+        self.status
+    }
+
+    pub fn namespace(&self) -> Option<&Namespace> {
+        self.namespace.as_ref()
+    }
+
+    pub fn webhook_id(&self) -> Option<Uuid> {
+        self.webhook_id
+    }
+
+    pub fn from(&self) -> Option<DateTime<Utc>> {
+        self.from
+    }
+
+    pub fn to(&self) -> Option<DateTime<Utc>> {
+        self.to
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct WebhookFilter {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    tenant: Option<String>,
+}
+
+impl WebhookFilter {
+    pub fn new(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Self {
+        Self { from, to, tenant: None }
+    }
+
+    pub fn with_tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(str::to_string);
+        self
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    pub fn from(&self) -> Option<DateTime<Utc>> {
+        self.from
+    }
+
+    pub fn to(&self) -> Option<DateTime<Utc>> {
+        self.to
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageCursor {
+    created_at: DateTime<Utc>,
+    id: Uuid,
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid page cursor")]
+pub struct PageCursorError;
+
+impl PageCursor {
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+impl fmt::Display for PageCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.created_at.to_rfc3339_opts(SecondsFormat::AutoSi, true), self.id)
+    }
+}
+
+impl FromStr for PageCursor {
+    type Err = PageCursorError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (created_at, id) = value.split_once('_').ok_or(PageCursorError)?;
+        Ok(Self {
+            created_at: DateTime::parse_from_rfc3339(created_at).map_err(|_| PageCursorError)?.with_timezone(&Utc),
+            id: Uuid::parse_str(id).map_err(|_| PageCursorError)?,
+        })
+    }
+}
+
+pub const DEFAULT_PAGE_LIMIT: u32 = 100;
+pub const MAX_PAGE_LIMIT: u32 = 1000;
+
+#[derive(Clone, Debug)]
+pub struct PageRequest {
+    sort_order: SortOrder,
+    after: Option<PageCursor>,
+    limit: u32,
+}
+
+#[derive(Debug, Error)]
+pub enum PageRequestError {
+    #[error("Page limit must be between 1 and {MAX_PAGE_LIMIT}")]
+    InvalidLimit,
+    #[error(transparent)]
+    InvalidCursor(#[from] PageCursorError),
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self { sort_order: SortOrder::default(), after: None, limit: DEFAULT_PAGE_LIMIT }
+    }
+}
+
+impl PageRequest {
+    pub fn new(sort_order: SortOrder, after: Option<&str>, limit: Option<u32>) -> Result<Self, PageRequestError> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        if limit == 0 || limit > MAX_PAGE_LIMIT {
+            return Err(PageRequestError::InvalidLimit);
+        }
+        let after = after.map(PageCursor::from_str).transpose()?;
+        Ok(Self { sort_order, after, limit })
+    }
+
+    pub fn sort_order(&self) -> SortOrder {
+        self.sort_order
+    }
+
+    pub fn is_descending(&self) -> bool {
+        self.sort_order == SortOrder::Descending
+    }
+
+    pub fn after(&self) -> Option<PageCursor> {
+        self.after
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    // One extra item tells whether a next page exists.
+    pub fn with_lookahead(&self) -> Self {
+        Self { limit: self.limit + 1, ..self.clone() }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Page<T> {
+    items: Vec<T>,
+    next_cursor: Option<PageCursor>,
+}
+
+impl<T> Page<T> {
+    pub fn from_lookahead(mut items: Vec<T>, page_request: &PageRequest, cursor_of: impl Fn(&T) -> PageCursor) -> Self {
+        let next_cursor = if items.len() > page_request.limit() as usize {
+            items.truncate(page_request.limit() as usize);
+            items.last().map(cursor_of)
+        } else {
+            None
+        };
+        Self { items, next_cursor }
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+
+    pub fn next_cursor(&self) -> Option<PageCursor> {
+        self.next_cursor
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RetentionPolicy {
+    job_done_watcher_retentions: Vec<(JobDoneWatcherStatus, chrono::Duration)>,
+    job_family_delivery_retention: Option<chrono::Duration>,
+}
+
+impl RetentionPolicy {
+    pub fn new(
+        job_done_watcher_retentions: Vec<(JobDoneWatcherStatus, chrono::Duration)>,
+        job_family_delivery_retention: Option<chrono::Duration>
+    ) -> Self {
+        Self { job_done_watcher_retentions, job_family_delivery_retention }
+    }
+
+    pub fn job_done_watcher_retentions(&self) -> &[(JobDoneWatcherStatus, chrono::Duration)] {
+        &self.job_done_watcher_retentions
+    }
+
+    pub fn job_family_delivery_retention(&self) -> Option<chrono::Duration> {
+        self.job_family_delivery_retention
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.job_done_watcher_retentions.is_empty() && self.job_family_delivery_retention.is_none()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PurgeReport {
+    dry_run: bool,
+    job_done_watchers: Vec<(JobDoneWatcherStatus, u64)>,
+    job_family_deliveries: u64,
+    idempotency_keys: u64,
+}
+
+impl PurgeReport {
+    pub fn new(dry_run: bool) -> Self {
+        Self { dry_run, job_done_watchers: Vec::new(), job_family_deliveries: 0, idempotency_keys: 0 }
+    }
+
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    pub fn job_done_watchers(&self) -> &[(JobDoneWatcherStatus, u64)] {
+        &self.job_done_watchers
+    }
+
+    pub fn job_family_deliveries(&self) -> u64 {
+        self.job_family_deliveries
+    }
+
+    pub fn idempotency_keys(&self) -> u64 {
+        self.idempotency_keys
+    }
+
+    pub fn add_job_done_watchers(&mut self, status: JobDoneWatcherStatus, count: u64) {
+        self.job_done_watchers.push((status, count));
+    }
+
+    pub fn set_job_family_deliveries(&mut self, count: u64) {
+        self.job_family_deliveries = count;
     }
-}
\ No newline at end of file
+
+    pub fn set_idempotency_keys(&mut self, count: u64) {
+        self.idempotency_keys = count;
+    }
+}
+
+pub const DEFAULT_WAIT_TIMEOUT_SECONDS: u64 = 30;
+pub const MAX_WAIT_TIMEOUT_SECONDS: u64 = 300;
+
+#[derive(Clone, Copy, Debug)]
+pub struct WaitTimeout(std::time::Duration);
+
+#[derive(Debug, Error)]
+#[error("Wait timeout must be between 0 and {MAX_WAIT_TIMEOUT_SECONDS} seconds")]
+pub struct WaitTimeoutError;
+
+impl WaitTimeout {
+    pub fn new(seconds: Option<u64>) -> Result<Self, WaitTimeoutError> {
+        match seconds.unwrap_or(DEFAULT_WAIT_TIMEOUT_SECONDS) {
+            seconds if seconds > MAX_WAIT_TIMEOUT_SECONDS => Err(WaitTimeoutError),
+            seconds => Ok(Self(std::time::Duration::from_secs(seconds))),
+        }
+    }
+
+    pub fn duration(&self) -> std::time::Duration {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Event {
+    JobDoneWatcher(JobDoneWatcher),
+    JobFamilyDelivery(JobFamilyDelivery),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    job_done_watcher_id: Option<Uuid>,
+    job_name: Option<JobName>,
+    status: Option<JobDoneWatcherStatus>,
+    tenant: Option<String>,
+}
+
+impl EventFilter {
+    pub fn new(job_name: Option<JobName>, status: Option<JobDoneWatcherStatus>) -> Self {
+        Self { job_done_watcher_id: None, job_name, status, tenant: None }
+    }
+
+    pub fn for_job_done_watcher(job_done_watcher_id: Uuid) -> Self {
+        Self { job_done_watcher_id: Some(job_done_watcher_id), job_name: None, status: None, tenant: None }
+    }
+
+    pub fn with_tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(str::to_string);
+        self
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        let tenant = match event {
+            Event::JobDoneWatcher(job_done_watcher) => job_done_watcher.tenant(),
+            Event::JobFamilyDelivery(job_family_delivery) => job_family_delivery.tenant(),
+        };
+        if self.tenant.as_deref().is_some_and(|expected_tenant| tenant != Some(expected_tenant)) {
+            return false;
+        }
+
+        match event {
+            Event::JobDoneWatcher(job_done_watcher) =>
+                self.job_done_watcher_id.is_none_or(|id| job_done_watcher.id() == id)
+                    && self.job_name.as_ref().is_none_or(|job_name| job_done_watcher.job_name() == job_name.as_str())
+                    && self.status.is_none_or(|status| job_done_watcher.status() == status),
+            // The status filter is a Job Done Watcher status, deliveries never match it.
+            Event::JobFamilyDelivery(job_family_delivery) =>
+                self.job_done_watcher_id.is_none()
+                    && self.status.is_none()
+                    && self.job_name.as_ref().is_none_or(|job_name| job_family_delivery.job_name().as_str() == job_name.as_str()),
+        }
+    }
+}
+
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+#[derive(Clone, Debug)]
+pub struct IdempotencyKey {
+    scope: String,
+    key: String,
+    fingerprint: String,
+    resource_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+impl IdempotencyKey {
+    /// Scope of the keys of a tenant, so that tenants never see each other's keys.
+    pub fn tenant_scope(scope: &str, tenant: Option<&str>) -> String {
+        match tenant {
+            Some(tenant) => format!("{} tenant:{}", scope, tenant),
+            None => scope.to_string(),
+        }
+    }
+
+    pub fn new(
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        resource_id: Option<Uuid>,
+        created_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>
+    ) -> Self {
+        Self {
+            scope: scope.to_string(),
+            key: key.to_string(),
+            fingerprint: fingerprint.to_string(),
+            resource_id,
+            created_at,
+            expires_at,
+        }
+    }
+
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+    pub fn resource_id(&self) -> Option<Uuid> {
+        self.resource_id
+    }
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    pub fn set_resource_id(&mut self, resource_id: Uuid) {
+        self.resource_id = Some(resource_id);
+    }
+
+    /// A key still in progress whose claim was made before `stale_claimed_before`, left behind by a request that never finished.
+    pub fn is_stale_claim(&self, stale_claimed_before: DateTime<Utc>) -> bool {
+        self.resource_id.is_none() && self.created_at < stale_claimed_before
+    }
+
+    pub fn is_claimed_at(&self, claimed_at: DateTime<Utc>) -> bool {
+        self.resource_id.is_none() && self.created_at == claimed_at
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+#[derive(Debug)]
+pub enum IdempotencyClaim {
+    /// Claimed at the given time, which completing or releasing the key must present.
+    Claimed(DateTime<Utc>),
+    Completed(Uuid),
+}
+
+#[derive(Debug, Error)]
+pub enum IdempotencyError {
+    #[error("Idempotency key already used with a different request")]
+    FingerprintMismatch,
+    #[error("A request with the same idempotency key is in progress")]
+    InProgress,
+    #[error(transparent)]
+    Repository(#[from] anyhow::Error),
+}
+
+pub const API_KEY_PREFIX: &str = "kjw_";
+pub const MAX_API_KEY_NAME_LENGTH: usize = 255;
+pub const MAX_TENANT_LENGTH: usize = 63;
+
+// Ordered: a scope grants the scopes below it, except metrics only granted by itself and admin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiScope {
+    Read,
+    Write,
+    Admin,
+    Metrics,
+}
+
+impl fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope_str = match self {
+            ApiScope::Read => "read",
+            ApiScope::Write => "write",
+            ApiScope::Admin => "admin",
+            ApiScope::Metrics => "metrics",
+        };
+        write!(f, "{}", scope_str)
+    }
+}
+
+impl TryFrom<&str> for ApiScope {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "read" => Ok(ApiScope::Read),
+            "write" => Ok(ApiScope::Write),
+            "admin" => Ok(ApiScope::Admin),
+            "metrics" => Ok(ApiScope::Metrics),
+            _ => Err(anyhow::anyhow!("Invalid API scope: {}", value)),
+        }
+    }
+}
+
+impl ApiScope {
+    pub fn grants(&self, required_scope: ApiScope) -> bool {
+        match (*self, required_scope) {
+            (ApiScope::Admin, _) | (ApiScope::Metrics, ApiScope::Metrics) => true,
+            (ApiScope::Metrics, _) | (_, ApiScope::Metrics) => false,
+            (scope, required_scope) => scope >= required_scope,
+        }
+    }
+
+    pub fn parse_list(value: &str) -> anyhow::Result<Vec<ApiScope>> {
+        value.split(',')
+            .map(str::trim)
+            .filter(|scope| !scope.is_empty())
+            .map(ApiScope::try_from)
+            .collect()
+    }
+
+    pub fn join(scopes: &[ApiScope]) -> String {
+        scopes.iter().map(ApiScope::to_string).collect::<Vec<_>>().join(",")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ApiKey {
+    id: Uuid,
+    name: String,
+    key_hash: String,
+    scopes: Vec<ApiScope>,
+    tenant: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn new(id: Uuid, name: &str, key_hash: &str, scopes: Vec<ApiScope>, tenant: Option<&str>, created_at: DateTime<Utc>) -> Self {
+        Self { id, name: name.to_string(), key_hash: key_hash.to_string(), scopes, tenant: tenant.map(str::to_string), created_at }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn key_hash(&self) -> &str {
+        &self.key_hash
+    }
+    pub fn scopes(&self) -> &[ApiScope] {
+        &self.scopes
+    }
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CreateApiKeyRequestError {
+    #[error("Name must be between 1 and {MAX_API_KEY_NAME_LENGTH} characters")]
+    InvalidName,
+    #[error("At least one scope is required")]
+    MissingScopes,
+    #[error("Tenant must be between 1 and {MAX_TENANT_LENGTH} characters")]
+    InvalidTenant,
+}
+
+#[derive(Clone, Debug)]
+pub struct CreateApiKeyRequest {
+    name: String,
+    scopes: Vec<ApiScope>,
+    tenant: Option<String>,
+}
+
+impl CreateApiKeyRequest {
+    pub fn new(name: &str, scopes: Vec<ApiScope>, tenant: Option<&str>) -> Result<Self, CreateApiKeyRequestError> {
+        if name.trim().is_empty() || name.len() > MAX_API_KEY_NAME_LENGTH {
+            return Err(CreateApiKeyRequestError::InvalidName);
+        }
+        if scopes.is_empty() {
+            return Err(CreateApiKeyRequestError::MissingScopes);
+        }
+        if tenant.is_some_and(|tenant| tenant.trim().is_empty() || tenant.len() > MAX_TENANT_LENGTH) {
+            return Err(CreateApiKeyRequestError::InvalidTenant);
+        }
+
+        Ok(Self { name: name.to_string(), scopes, tenant: tenant.map(str::to_string) })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn scopes(&self) -> &[ApiScope] {
+        &self.scopes
+    }
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+}
+
+/// An API key as returned once, on creation.
+#[derive(Clone, Debug)]
+pub struct CreatedApiKey {
+    api_key: ApiKey,
+    key: String,
+}
+
+impl CreatedApiKey {
+    pub fn new(api_key: ApiKey, key: String) -> Self {
+        Self { api_key, key }
+    }
+
+    pub fn api_key(&self) -> &ApiKey {
+        &self.api_key
+    }
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Principal {
+    name: String,
+    scopes: Vec<ApiScope>,
+    // Sees and owns the resources of its tenant only, all of them when `None`.
+    tenant: Option<String>,
+}
+
+impl Principal {
+    pub fn new(name: &str, scopes: Vec<ApiScope>, tenant: Option<&str>) -> Self {
+        Self { name: name.to_string(), scopes, tenant: tenant.map(str::to_string) }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    pub fn has_scope(&self, required_scope: ApiScope) -> bool {
+        self.scopes.iter().any(|scope| scope.grants(required_scope))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuthProvider {
+    ApiKey,
+    ServiceAccount,
+}
+
+impl TryFrom<&str> for AuthProvider {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "api-key" => Ok(AuthProvider::ApiKey),
+            "service-account" => Ok(AuthProvider::ServiceAccount),
+            _ => Err(anyhow::anyhow!("Invalid authentication provider: {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AuthSettings {
+    providers: Vec<AuthProvider>,
+    service_account_scopes: Vec<ApiScope>,
+    // "namespace/name" or "namespace/*", any service account when empty.
+    service_accounts: Vec<String>,
+    token_audiences: Vec<String>,
+}
+
+impl AuthSettings {
+    pub fn new(
+        providers: Vec<AuthProvider>,
+        service_account_scopes: Vec<ApiScope>,
+        service_accounts: Vec<String>,
+        token_audiences: Vec<String>,
+    ) -> Self {
+        Self { providers, service_account_scopes, service_accounts, token_audiences }
+    }
+
+    pub fn is_enabled(&self, provider: AuthProvider) -> bool {
+        self.providers.contains(&provider)
+    }
+
+    pub fn service_account_scopes(&self) -> &[ApiScope] {
+        &self.service_account_scopes
+    }
+
+    pub fn token_audiences(&self) -> &[String] {
+        &self.token_audiences
+    }
+
+    pub fn allows_service_account(&self, namespace: &str, name: &str) -> bool {
+        self.service_accounts.is_empty() || self.service_accounts.iter().any(|service_account| {
+            service_account.split_once('/')
+                .is_some_and(|(allowed_namespace, allowed_name)| allowed_namespace == namespace && (allowed_name == "*" || allowed_name == name))
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AuthenticationError {
+    #[error("Missing bearer token")]
+    MissingToken,
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+    #[error("Authentication is unavailable")]
+    Unavailable(#[source] anyhow::Error),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct EgressPolicy {
+    allowed_cidrs: Vec<IpNet>,
+    denied_cidrs: Vec<IpNet>,
+    // "host" or "*.domain", matched case-insensitively.
+    allowed_hosts: Vec<String>,
+    denied_hosts: Vec<String>,
+}
+
+impl EgressPolicy {
+    pub fn new(allowed_cidrs: Vec<IpNet>, denied_cidrs: Vec<IpNet>, allowed_hosts: Vec<String>, denied_hosts: Vec<String>) -> Self {
+        Self { allowed_cidrs, denied_cidrs, allowed_hosts, denied_hosts }
+    }
+
+    /// Checks the host of a URL, an address literal being checked like a resolved address.
+    pub fn check_url(&self, url: &url::Url) -> Result<(), EgressError> {
+        match url.host() {
+            Some(url::Host::Domain(host)) => self.check_host(host),
+            Some(url::Host::Ipv4(address)) => self.check_address_literal(IpAddr::V4(address)),
+            Some(url::Host::Ipv6(address)) => self.check_address_literal(IpAddr::V6(address)),
+            None => Err(EgressError::HostNotAllowed(String::new())),
+        }
+    }
+
+    pub fn check_host(&self, host: &str) -> Result<(), EgressError> {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let matches = |pattern: &String| {
+            let pattern = pattern.to_ascii_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(domain) => host.strip_suffix(domain).is_some_and(|subdomain| subdomain.ends_with('.')),
+                None => host == pattern,
+            }
+        };
+
+        if self.denied_hosts.iter().any(matches) || (!self.allowed_hosts.is_empty() && !self.allowed_hosts.iter().any(matches)) {
+            return Err(EgressError::HostNotAllowed(host));
+        }
+        Ok(())
+    }
+
+    pub fn check_address(&self, address: IpAddr) -> Result<(), EgressError> {
+        // An IPv4-mapped IPv6 address reaches the IPv4 one.
+        let address = address.to_canonical();
+        if self.denied_cidrs.iter().any(|cidr| cidr.contains(&address))
+            || (!self.allowed_cidrs.is_empty() && !self.allowed_cidrs.iter().any(|cidr| cidr.contains(&address))) {
+            return Err(EgressError::AddressNotAllowed(address));
+        }
+        Ok(())
+    }
+
+    fn check_address_literal(&self, address: IpAddr) -> Result<(), EgressError> {
+        // With allowed hosts, an address is only reachable when explicitly allowed.
+        if !self.allowed_hosts.is_empty() && !self.allowed_cidrs.iter().any(|cidr| cidr.contains(&address.to_canonical())) {
+            return Err(EgressError::AddressNotAllowed(address));
+        }
+        self.check_address(address)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum EgressError {
+    #[error("Host {0} is not allowed by the egress policy")]
+    HostNotAllowed(String),
+    #[error("Address {0} is not allowed by the egress policy")]
+    AddressNotAllowed(IpAddr),
+    #[error("Failed to resolve {0}: {1}")]
+    Unresolvable(String, String),
+}
+
+#[derive(Clone, Debug)]
+pub struct DestinationSettings {
+    rate_limit_per_second: Option<f64>,
+    rate_limit_burst: u32,
+    // The circuit breaker is disabled without a threshold.
+    circuit_breaker_failures: Option<u32>,
+    circuit_breaker_open_duration: std::time::Duration,
+    // Hosts and webhook rate limits unused for that long are forgotten.
+    idle_timeout: std::time::Duration,
+}
+
+impl DestinationSettings {
+    pub fn new(
+        rate_limit_per_second: Option<f64>,
+        rate_limit_burst: u32,
+        circuit_breaker_failures: Option<u32>,
+        circuit_breaker_open_duration: std::time::Duration,
+        idle_timeout: std::time::Duration
+    ) -> Self {
+        Self { rate_limit_per_second, rate_limit_burst, circuit_breaker_failures, circuit_breaker_open_duration, idle_timeout }
+    }
+
+    pub fn rate_limit_per_second(&self) -> Option<f64> {
+        self.rate_limit_per_second
+    }
+    pub fn rate_limit_burst(&self) -> u32 {
+        self.rate_limit_burst
+    }
+    pub fn circuit_breaker_failures(&self) -> Option<u32> {
+        self.circuit_breaker_failures
+    }
+    pub fn circuit_breaker_open_duration(&self) -> std::time::Duration {
+        self.circuit_breaker_open_duration
+    }
+    pub fn idle_timeout(&self) -> std::time::Duration {
+        self.idle_timeout
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// A receiver of deliveries, identified by the host and port of its URL.
+#[derive(Clone, Debug)]
+pub struct Destination {
+    name: String,
+    circuit_state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<DateTime<Utc>>,
+    queued_deliveries: usize,
+}
+
+impl Destination {
+    pub fn new(name: &str, circuit_state: CircuitState, consecutive_failures: u32, opened_at: Option<DateTime<Utc>>, queued_deliveries: usize) -> Self {
+        Self { name: name.to_string(), circuit_state, consecutive_failures, opened_at, queued_deliveries }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn circuit_state(&self) -> CircuitState {
+        self.circuit_state
+    }
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+    pub fn opened_at(&self) -> Option<DateTime<Utc>> {
+        self.opened_at
+    }
+    pub fn queued_deliveries(&self) -> usize {
+        self.queued_deliveries
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeliveryKind {
+    JobDoneTrigger,
+    JobFamily,
+    Test,
+}
+
+impl fmt::Display for DeliveryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind_str = match self {
+            DeliveryKind::JobDoneTrigger => "job_done_trigger",
+            DeliveryKind::JobFamily => "job_family",
+            DeliveryKind::Test => "test",
+        };
+        write!(f, "{}", kind_str)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DeliveryClientSettings {
+    connect_timeout: std::time::Duration,
+    read_timeout: Option<std::time::Duration>,
+    max_idle_connections_per_host: usize,
+    http_proxy: Option<String>,
+    https_proxy: Option<String>,
+    no_proxy: Option<String>,
+}
+
+impl DeliveryClientSettings {
+    pub fn new(connect_timeout: std::time::Duration, read_timeout: Option<std::time::Duration>, max_idle_connections_per_host: usize) -> Self {
+        Self { connect_timeout, read_timeout, max_idle_connections_per_host, http_proxy: None, https_proxy: None, no_proxy: None }
+    }
+
+    pub fn with_proxies(mut self, http_proxy: Option<&str>, https_proxy: Option<&str>, no_proxy: Option<&str>) -> Self {
+        self.http_proxy = http_proxy.map(str::to_string);
+        self.https_proxy = https_proxy.map(str::to_string);
+        self.no_proxy = no_proxy.map(str::to_string);
+        self
+    }
+
+    pub fn connect_timeout(&self) -> std::time::Duration {
+        self.connect_timeout
+    }
+    pub fn read_timeout(&self) -> Option<std::time::Duration> {
+        self.read_timeout
+    }
+    pub fn max_idle_connections_per_host(&self) -> usize {
+        self.max_idle_connections_per_host
+    }
+    pub fn http_proxy(&self) -> Option<&str> {
+        self.http_proxy.as_deref()
+    }
+    pub fn https_proxy(&self) -> Option<&str> {
+        self.https_proxy.as_deref()
+    }
+    pub fn no_proxy(&self) -> Option<&str> {
+        self.no_proxy.as_deref()
+    }
+}
+
+/// TLS configuration of the client calling webhooks; the CA bundle is trusted in addition to the built-in roots.
+#[derive(Clone, Debug, Default)]
+pub struct ClientTlsProfile {
+    ca_file: Option<PathBuf>,
+    client_cert_file: Option<PathBuf>,
+    client_key_file: Option<PathBuf>,
+    min_version: Option<reqwest::tls::Version>,
+}
+
+impl ClientTlsProfile {
+    pub fn new(ca_file: Option<PathBuf>, client_cert_file: Option<PathBuf>, client_key_file: Option<PathBuf>, min_version: Option<&str>) -> anyhow::Result<Self> {
+        if client_cert_file.is_some() != client_key_file.is_some() {
+            return Err(anyhow::anyhow!("A client certificate and its key must be set together"));
+        }
+        // The native TLS backend can't require TLS 1.3.
+        let min_version = min_version.map(|min_version| match min_version {
+            "1.0" => Ok(reqwest::tls::Version::TLS_1_0),
+            "1.1" => Ok(reqwest::tls::Version::TLS_1_1),
+            "1.2" => Ok(reqwest::tls::Version::TLS_1_2),
+            _ => Err(anyhow::anyhow!("Unsupported minimum TLS version: {} (expected 1.0, 1.1 or 1.2)", min_version)),
+        }).transpose()?;
+        Ok(Self { ca_file, client_cert_file, client_key_file, min_version })
+    }
+
+    pub fn ca_file(&self) -> Option<&Path> {
+        self.ca_file.as_deref()
+    }
+    pub fn client_cert_file(&self) -> Option<&Path> {
+        self.client_cert_file.as_deref()
+    }
+    pub fn client_key_file(&self) -> Option<&Path> {
+        self.client_key_file.as_deref()
+    }
+    pub fn min_version(&self) -> Option<reqwest::tls::Version> {
+        self.min_version
+    }
+}
+
+impl TryFrom<&Yaml> for ClientTlsProfile {
+    type Error = anyhow::Error;
+
+    fn try_from(yaml: &Yaml) -> Result<Self, Self::Error> {
+        let min_version = match &yaml["minVersion"] {
+            Yaml::BadValue => None,
+            // An unquoted 1.2 is read as a number.
+            Yaml::String(min_version) | Yaml::Real(min_version) => Some(min_version.as_str()),
+            _ => return Err(anyhow::anyhow!("Missing or invalid value for key: minVersion")),
+        };
+        Self::new(
+            extract_yaml_string(yaml, "caFile").ok().map(PathBuf::from),
+            extract_yaml_string(yaml, "clientCertFile").ok().map(PathBuf::from),
+            extract_yaml_string(yaml, "clientKeyFile").ok().map(PathBuf::from),
+            min_version,
+        )
+    }
+}
+
+
+#[derive(Clone, Debug)]
+pub struct TlsSettings {
+    cert_file: PathBuf,
+    key_file: PathBuf,
+    // Client certificates are required and verified against it when set.
+    client_ca_file: Option<PathBuf>,
+}
+
+impl TlsSettings {
+    pub fn new(cert_file: PathBuf, key_file: PathBuf, client_ca_file: Option<PathBuf>) -> Self {
+        Self { cert_file, key_file, client_ca_file }
+    }
+
+    pub fn cert_file(&self) -> &Path {
+        &self.cert_file
+    }
+
+    pub fn key_file(&self) -> &Path {
+        &self.key_file
+    }
+
+    pub fn client_ca_file(&self) -> Option<&Path> {
+        self.client_ca_file.as_deref()
+    }
+}
+
+
+fn extract_yaml_string(yaml: &Yaml, key: &str) -> Result<String, anyhow::Error> {
+    match &yaml[key] {
+        Yaml::String(value) => Ok(value.clone()),
+        _ => Err(anyhow::anyhow!("Missing or invalid value for key: {}", key)),
+    }
+}
+
+fn extract_yaml_u64(yaml: &Yaml, key: &str) -> Result<Option<u64>, anyhow::Error> {
+    match &yaml[key] {
+        Yaml::BadValue => Ok(None),
+        Yaml::Integer(value) if *value >= 0 => Ok(Some(*value as u64)),
+        _ => Err(anyhow::anyhow!("Missing or invalid value for key: {}", key)),
+    }
+}
@@ -5,7 +5,7 @@ use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum HttpUrlError {
-    #[error("Invalid URL format")]
+    #[error("Invalid URL format: {0}")]
     InvalidHttpUrl(#[from] url::ParseError),
 
     #[error("Only http/https scheme is supported")]
@@ -33,7 +33,7 @@ impl HttpUrl {
 
 impl Display for HttpUrl {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0.to_string())
+        write!(f, "{}", self.0)
     }
 }
 
@@ -0,0 +1,57 @@
+use std::fmt;
+use std::ops::Deref;
+use thiserror::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Namespace(String);
+
+#[derive(Debug, Error)]
+pub enum NamespaceError {
+    #[error("Namespace must contain between 1 and 63 characters.")]
+    InvalidLength,
+    #[error("Namespace can only contain lowercase alphanumeric characters and '-'.")]
+    InvalidCharacters,
+    #[error("Namespace must start and end with an alphanumeric character.")]
+    InvalidBoundaryCharacter,
+}
+
+impl Namespace {
+    pub fn new(name: &str) -> Result<Self, NamespaceError> {
+        if name.is_empty() || name.len() > 63 {
+            return Err(NamespaceError::InvalidLength);
+        }
+        if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+            return Err(NamespaceError::InvalidCharacters);
+        }
+        if name.starts_with('-') || name.ends_with('-') {
+            return Err(NamespaceError::InvalidBoundaryCharacter);
+        }
+        Ok(Namespace(name.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Deref for Namespace {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TryFrom<&str> for Namespace {
+    type Error = NamespaceError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Namespace::new(value)
+    }
+}
\ No newline at end of file
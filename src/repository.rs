@@ -1,19 +1,43 @@
-use sqlx::{Database, Pool, Sqlite, SqlitePool};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_rwlock::RwLock;
+use chrono::{DateTime, Utc};
+use sqlx::{Database, MySql, MySqlPool, PgPool, Pool, Postgres, Sqlite, SqlitePool};
 use sqlx::pool::PoolConnection;
-use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use uuid::Uuid;
+
+use crate::models::service::{ApiKey, DeadLetter, IdempotencyKey, JobDoneTriggerWebhookAttempt, JobDoneWatcher, JobFamilyDelivery, JobFamilyState, JobFamilyWatcher, PageRequest, Webhook};
 
+pub use api_keys::get_api_key_repository;
+pub use api_keys::ApiKeyRepository;
+pub use api_keys::set_api_key_repository;
+pub use dead_letters::get_dead_letter_repository;
+pub use dead_letters::DeadLetterRepository;
+pub use dead_letters::set_dead_letter_repository;
+pub use idempotency_keys::get_idempotency_key_repository;
+pub use idempotency_keys::IdempotencyKeyRepository;
+pub use idempotency_keys::set_idempotency_key_repository;
 pub use job_done_watchers::get_job_done_watcher_repository;
-pub use job_done_watchers::InMemoryJobDoneWatcherRepository;
+pub use job_done_watchers::JobDoneWatcherRepository;
 pub use job_done_watchers::set_job_done_watcher_repository;
 pub use job_family_watcher::get_job_family_watcher_repository;
+pub use job_family_watcher::JobFamilyWatcherRepository;
 pub use job_family_watcher::set_job_family_watcher_repository;
+pub use metered::MeteredRepository;
 pub use webhooks::get_webhook_repository;
-pub use webhooks::InMemoryWebhookRepository;
+pub use webhooks::WebhookRepository;
 pub use webhooks::set_webhook_repository;
 
 mod webhooks;
 mod job_done_watchers;
 mod job_family_watcher;
+mod idempotency_keys;
+mod dead_letters;
+mod api_keys;
+mod metered;
 
 #[derive(Clone)]
 pub struct SqliteDatabase {
@@ -23,7 +47,7 @@ pub struct SqliteDatabase {
 impl SqliteDatabase {
     pub async fn connect(url: &str) -> anyhow::Result<Self> {
         Ok(Self {
-            pool_connection: SqlitePool::connect(url).await?
+            pool_connection: SqlitePool::connect_with(SqliteConnectOptions::from_str(url)?.create_if_missing(true)).await?
         })
     }
 
@@ -39,6 +63,81 @@ impl SqliteDatabase {
     }
 }
 
+#[derive(Clone)]
+pub struct PostgresDatabase {
+    pool_connection: Pool<Postgres>,
+}
+
+impl PostgresDatabase {
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            pool_connection: PgPool::connect(url).await?
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct MySqlDatabase {
+    pool_connection: Pool<MySql>,
+}
+
+impl MySqlDatabase {
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            pool_connection: MySqlPool::connect(url).await?
+        })
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct InMemoryDatabase {
+    state: Arc<RwLock<InMemoryState>>,
+}
+
+#[derive(Default)]
+struct InMemoryState {
+    webhooks: HashMap<Uuid, Webhook>,
+    job_done_watchers: HashMap<Uuid, JobDoneWatcher>,
+    job_done_trigger_webhook_attempts: HashMap<Uuid, Vec<JobDoneTriggerWebhookAttempt>>,
+    job_family_watchers: HashMap<Uuid, JobFamilyWatcher>,
+    job_family_states: HashMap<String, JobFamilyState>,
+    job_family_deliveries: HashMap<Uuid, JobFamilyDelivery>,
+    idempotency_keys: HashMap<(String, String), IdempotencyKey>,
+    dead_letters: HashMap<Uuid, DeadLetter>,
+    api_keys: HashMap<Uuid, ApiKey>,
+}
+
+impl InMemoryDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// Keyset pagination on (created_at, id), the same order the SQL backends use.
+fn paginate_in_memory<T>(
+    mut items: Vec<T>,
+    page_request: &PageRequest,
+    key: impl Fn(&T) -> (DateTime<Utc>, Uuid)
+) -> Vec<T> {
+    items.sort_by_key(&key);
+    if page_request.is_descending() {
+        items.reverse();
+    }
+
+    items.into_iter()
+        .filter(|item| page_request.after().is_none_or(|after| {
+            let after = (after.created_at(), after.id());
+            if page_request.is_descending() { key(item) < after } else { key(item) > after }
+        }))
+        .take(page_request.limit() as usize)
+        .collect()
+}
+
+// Request and response headers are stored as a JSON list of name and value pairs.
+fn headers_to_json(headers: Option<&[(String, String)]>) -> anyhow::Result<Option<String>> {
+    Ok(headers.map(k8s_openapi::serde_json::to_string).transpose()?)
+}
+
 #[async_trait::async_trait]
 pub trait SqlxAcquire {
     type DB: Database;
@@ -52,4 +151,61 @@ impl SqlxAcquire for SqliteDatabase {
     async fn acquire(&self) -> anyhow::Result<PoolConnection<Self::DB>> {
         Ok(self.pool_connection.acquire().await?)
     }
-}
\ No newline at end of file
+}
+
+#[async_trait::async_trait]
+impl SqlxAcquire for PostgresDatabase {
+    type DB = Postgres;
+
+    async fn acquire(&self) -> anyhow::Result<PoolConnection<Self::DB>> {
+        Ok(self.pool_connection.acquire().await?)
+    }
+}
+
+#[async_trait::async_trait]
+impl SqlxAcquire for MySqlDatabase {
+    type DB = MySql;
+
+    async fn acquire(&self) -> anyhow::Result<PoolConnection<Self::DB>> {
+        Ok(self.pool_connection.acquire().await?)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait SqlxMigrationsTable {
+    /// Unlike `Migrate::ensure_migrations_table`, doesn't create the table.
+    async fn has_migrations_table(&self) -> anyhow::Result<bool>;
+}
+
+#[async_trait::async_trait]
+impl SqlxMigrationsTable for SqliteDatabase {
+    async fn has_migrations_table(&self) -> anyhow::Result<bool> {
+        let mut conn = self.acquire().await?;
+        let count = sqlx::query_file_scalar!("queries/sqlite/count_migrations_tables.sql")
+            .fetch_one(&mut *conn)
+            .await?;
+        Ok(count > 0)
+    }
+}
+
+#[async_trait::async_trait]
+impl SqlxMigrationsTable for PostgresDatabase {
+    async fn has_migrations_table(&self) -> anyhow::Result<bool> {
+        let mut conn = self.acquire().await?;
+        let count: i64 = sqlx::query_scalar(include_str!("../queries/postgres/count_migrations_tables.sql"))
+            .fetch_one(&mut *conn)
+            .await?;
+        Ok(count > 0)
+    }
+}
+
+#[async_trait::async_trait]
+impl SqlxMigrationsTable for MySqlDatabase {
+    async fn has_migrations_table(&self) -> anyhow::Result<bool> {
+        let mut conn = self.acquire().await?;
+        let count: i64 = sqlx::query_scalar(include_str!("../queries/mysql/count_migrations_tables.sql"))
+            .fetch_one(&mut *conn)
+            .await?;
+        Ok(count > 0)
+    }
+}
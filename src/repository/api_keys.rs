@@ -0,0 +1,231 @@
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::models::entity::ApiKeyEntity;
+use crate::models::service::{ApiKey, ApiScope};
+use crate::repository::{InMemoryDatabase, MySqlDatabase, PostgresDatabase, SqliteDatabase, SqlxAcquire};
+
+static API_KEY_REPOSITORY: OnceLock<Arc<dyn ApiKeyRepository>> = OnceLock::new();
+
+pub fn set_api_key_repository(api_key_repository: impl ApiKeyRepository + 'static) {
+    if API_KEY_REPOSITORY.set(Arc::new(api_key_repository)).is_err() {
+        panic!("You can't set Api Key Repository twice!");
+    }
+}
+
+pub fn get_api_key_repository() -> Arc<dyn ApiKeyRepository> {
+    Arc::clone(API_KEY_REPOSITORY.get().expect("Should be set!"))
+}
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn create_api_key(&self, api_key: &ApiKey) -> anyhow::Result<()>;
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> anyhow::Result<Option<ApiKey>>;
+    async fn find_all_api_keys(&self) -> anyhow::Result<Vec<ApiKey>>;
+    async fn delete_api_key(&self, api_key_id: &Uuid) -> anyhow::Result<bool>;
+}
+
+#[async_trait]
+impl ApiKeyRepository for InMemoryDatabase {
+    async fn create_api_key(&self, api_key: &ApiKey) -> anyhow::Result<()> {
+        let mut state = self.state.write().await;
+        if state.api_keys.values().any(|existing| existing.key_hash() == api_key.key_hash()) {
+            return Err(anyhow!("Api key with the same hash already exists!"));
+        }
+
+        state.api_keys.insert(api_key.id(), api_key.clone());
+        Ok(())
+    }
+
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> anyhow::Result<Option<ApiKey>> {
+        Ok(self.state.read().await.api_keys.values()
+            .find(|api_key| api_key.key_hash() == key_hash)
+            .cloned())
+    }
+
+    async fn find_all_api_keys(&self) -> anyhow::Result<Vec<ApiKey>> {
+        let mut api_keys: Vec<ApiKey> = self.state.read().await.api_keys.values().cloned().collect();
+        api_keys.sort_by_key(|api_key| (api_key.created_at(), api_key.id()));
+        Ok(api_keys)
+    }
+
+    async fn delete_api_key(&self, api_key_id: &Uuid) -> anyhow::Result<bool> {
+        Ok(self.state.write().await.api_keys.remove(api_key_id).is_some())
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for SqliteDatabase {
+    async fn create_api_key(&self, api_key: &ApiKey) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let id = api_key.id().to_string();
+        let name = api_key.name();
+        let key_hash = api_key.key_hash();
+        let scopes = ApiScope::join(api_key.scopes());
+        let created_at = api_key.created_at();
+        let tenant = api_key.tenant();
+        sqlx::query_file!("queries/sqlite/insert_api_key.sql", id, name, key_hash, scopes, created_at, tenant)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> anyhow::Result<Option<ApiKey>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_file_as!(ApiKeyEntity, "queries/sqlite/find_api_key_by_hash.sql", key_hash)
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(ApiKey::from))
+    }
+
+    async fn find_all_api_keys(&self) -> anyhow::Result<Vec<ApiKey>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_file_as!(ApiKeyEntity, "queries/sqlite/find_all_api_keys.sql")
+            .fetch_all(&mut *conn)
+            .await?
+            .into_iter()
+            .map(ApiKey::from)
+            .collect())
+    }
+
+    async fn delete_api_key(&self, api_key_id: &Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let api_key_id = api_key_id.to_string();
+        Ok(sqlx::query_file!("queries/sqlite/delete_api_key.sql", api_key_id)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected() > 0)
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PostgresDatabase {
+    async fn create_api_key(&self, api_key: &ApiKey) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/insert_api_key.sql"))
+            .bind(api_key.id().to_string())
+            .bind(api_key.name())
+            .bind(api_key.key_hash())
+            .bind(ApiScope::join(api_key.scopes()))
+            .bind(api_key.created_at())
+            .bind(api_key.tenant())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> anyhow::Result<Option<ApiKey>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_as::<_, ApiKeyEntity>(include_str!("../../queries/postgres/find_api_key_by_hash.sql"))
+            .bind(key_hash)
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(ApiKey::from))
+    }
+
+    async fn find_all_api_keys(&self) -> anyhow::Result<Vec<ApiKey>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_as::<_, ApiKeyEntity>(include_str!("../../queries/postgres/find_all_api_keys.sql"))
+            .fetch_all(&mut *conn)
+            .await?
+            .into_iter()
+            .map(ApiKey::from)
+            .collect())
+    }
+
+    async fn delete_api_key(&self, api_key_id: &Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query(include_str!("../../queries/postgres/delete_api_key.sql"))
+            .bind(api_key_id.to_string())
+            .execute(&mut *conn)
+            .await?
+            .rows_affected() > 0)
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for MySqlDatabase {
+    async fn create_api_key(&self, api_key: &ApiKey) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/insert_api_key.sql"))
+            .bind(api_key.id().to_string())
+            .bind(api_key.name())
+            .bind(api_key.key_hash())
+            .bind(ApiScope::join(api_key.scopes()))
+            .bind(api_key.created_at())
+            .bind(api_key.tenant())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> anyhow::Result<Option<ApiKey>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_as::<_, ApiKeyEntity>(include_str!("../../queries/mysql/find_api_key_by_hash.sql"))
+            .bind(key_hash)
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(ApiKey::from))
+    }
+
+    async fn find_all_api_keys(&self) -> anyhow::Result<Vec<ApiKey>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_as::<_, ApiKeyEntity>(include_str!("../../queries/mysql/find_all_api_keys.sql"))
+            .fetch_all(&mut *conn)
+            .await?
+            .into_iter()
+            .map(ApiKey::from)
+            .collect())
+    }
+
+    async fn delete_api_key(&self, api_key_id: &Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query(include_str!("../../queries/mysql/delete_api_key.sql"))
+            .bind(api_key_id.to_string())
+            .execute(&mut *conn)
+            .await?
+            .rows_affected() > 0)
+    }
+}
@@ -0,0 +1,407 @@
+use std::sync::{Arc, OnceLock};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::Acquire;
+use uuid::Uuid;
+
+use crate::models::entity::DeadLetterEntity;
+use crate::models::service::{DeadLetter, DeadLetterFilter, PageRequest};
+use crate::repository::{headers_to_json, paginate_in_memory, InMemoryDatabase, MySqlDatabase, PostgresDatabase, SqliteDatabase, SqlxAcquire};
+
+static DEAD_LETTER_REPOSITORY: OnceLock<Arc<dyn DeadLetterRepository>> = OnceLock::new();
+
+pub fn set_dead_letter_repository(dead_letter_repository: impl DeadLetterRepository + 'static) {
+    if DEAD_LETTER_REPOSITORY.set(Arc::new(dead_letter_repository)).is_err() {
+        panic!("You can't set Dead Letter Repository twice!");
+    }
+}
+
+pub fn get_dead_letter_repository() -> Arc<dyn DeadLetterRepository> {
+    Arc::clone(DEAD_LETTER_REPOSITORY.get().expect("Should be set!"))
+}
+
+#[async_trait]
+pub trait DeadLetterRepository: Send + Sync {
+    /// Updates the dead letter of the same job done trigger webhook in place, keeping its id and creation time, if any.
+    async fn save_dead_letter(&self, dead_letter: &DeadLetter) -> anyhow::Result<DeadLetter>;
+    async fn find_all_dead_letters(&self, dead_letter_filter: &DeadLetterFilter, page_request: &PageRequest) -> anyhow::Result<Vec<DeadLetter>>;
+    async fn find_dead_letter_by_id(&self, id: &Uuid) -> anyhow::Result<Option<DeadLetter>>;
+    async fn delete_dead_letter(&self, id: &Uuid) -> anyhow::Result<bool>;
+    async fn delete_dead_letter_by_job_done_trigger_webhook_id(&self, job_done_trigger_webhook_id: &Uuid) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+impl DeadLetterRepository for InMemoryDatabase {
+    async fn save_dead_letter(&self, dead_letter: &DeadLetter) -> anyhow::Result<DeadLetter> {
+        let mut state = self.state.write().await;
+        let dead_letter = match state.dead_letters.values().find(|existing| existing.job_done_trigger_webhook_id() == dead_letter.job_done_trigger_webhook_id()) {
+            Some(existing) => dead_letter.clone().with_id_and_created_at(existing.id(), existing.created_at()),
+            None => dead_letter.clone(),
+        };
+        state.dead_letters.insert(dead_letter.id(), dead_letter.clone());
+        Ok(dead_letter)
+    }
+
+    async fn find_all_dead_letters(&self, dead_letter_filter: &DeadLetterFilter, page_request: &PageRequest) -> anyhow::Result<Vec<DeadLetter>> {
+        let state = self.state.read().await;
+
+        let dead_letters: Vec<DeadLetter> = state.dead_letters.values()
+            .filter(|dead_letter| dead_letter_filter.webhook_id().is_none_or(|webhook_id| dead_letter.webhook_id() == webhook_id))
+            .filter(|dead_letter| dead_letter_filter.job_name().is_none_or(|job_name| dead_letter.job_name().as_str() == job_name.as_str()))
+            .filter(|dead_letter| dead_letter_filter.from().is_none_or(|from| dead_letter.created_at() >= from))
+            .filter(|dead_letter| dead_letter_filter.to().is_none_or(|to| dead_letter.created_at() <= to))
+            .filter(|dead_letter| dead_letter_filter.tenant().is_none_or(|tenant| state.job_done_watchers.get(&dead_letter.job_done_watcher_id())
+                .is_some_and(|job_done_watcher| job_done_watcher.tenant() == Some(tenant))))
+            .cloned()
+            .collect();
+        Ok(paginate_in_memory(dead_letters, page_request, |dead_letter| (dead_letter.created_at(), dead_letter.id())))
+    }
+
+    async fn find_dead_letter_by_id(&self, id: &Uuid) -> anyhow::Result<Option<DeadLetter>> {
+        Ok(self.state.read().await.dead_letters.get(id).cloned())
+    }
+
+    async fn delete_dead_letter(&self, id: &Uuid) -> anyhow::Result<bool> {
+        Ok(self.state.write().await.dead_letters.remove(id).is_some())
+    }
+
+    async fn delete_dead_letter_by_job_done_trigger_webhook_id(&self, job_done_trigger_webhook_id: &Uuid) -> anyhow::Result<()> {
+        self.state.write().await.dead_letters.retain(|_, dead_letter| dead_letter.job_done_trigger_webhook_id() != *job_done_trigger_webhook_id);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeadLetterRepository for SqliteDatabase {
+    async fn save_dead_letter(&self, dead_letter: &DeadLetter) -> anyhow::Result<DeadLetter> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let mut tx = conn.begin().await?;
+
+        let id = dead_letter.id().to_string();
+        let job_done_watcher_id = dead_letter.job_done_watcher_id().to_string();
+        let job_done_trigger_webhook_id = dead_letter.job_done_trigger_webhook_id().to_string();
+        let webhook_id = dead_letter.webhook_id().to_string();
+        let job_name = dead_letter.job_name().to_string();
+        let namespace = dead_letter.namespace().map(|namespace| namespace.to_string());
+        let request_method = dead_letter.request().map(|request| request.method());
+        let request_url = dead_letter.request().map(|request| request.url());
+        let request_headers = headers_to_json(dead_letter.request().map(|request| request.headers()))?;
+        let request_body = dead_letter.request().map(|request| request.body());
+        let response_status_code = dead_letter.response_status_code().map(i64::from);
+        let error = dead_letter.error();
+        let response_headers = headers_to_json(dead_letter.response_headers())?;
+        let response_body = dead_letter.response_body();
+        let attempts = i64::from(dead_letter.attempts());
+        let created_at = dead_letter.created_at();
+        sqlx::query_file!("queries/sqlite/upsert_dead_letter.sql",
+            id,
+            job_done_watcher_id,
+            job_done_trigger_webhook_id,
+            webhook_id,
+            job_name,
+            namespace,
+            request_method,
+            request_url,
+            request_headers,
+            request_body,
+            response_status_code,
+            error,
+            response_headers,
+            response_body,
+            attempts,
+            created_at
+        ).execute(&mut *tx)
+         .await?;
+        let dead_letter_entity = sqlx::query_file_as!(DeadLetterEntity, "queries/sqlite/find_dead_letter_by_job_done_trigger_webhook_id.sql", job_done_trigger_webhook_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(DeadLetter::from(dead_letter_entity))
+    }
+
+    async fn find_all_dead_letters(&self, dead_letter_filter: &DeadLetterFilter, page_request: &PageRequest) -> anyhow::Result<Vec<DeadLetter>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let webhook_id = dead_letter_filter.webhook_id().map(|webhook_id| webhook_id.to_string());
+        let job_name = dead_letter_filter.job_name().map(|job_name| job_name.to_string());
+        let from = dead_letter_filter.from();
+        let to = dead_letter_filter.to();
+        let after_created_at = page_request.after().map(|after| after.created_at());
+        let after_id = page_request.after().map(|after| after.id().to_string());
+        let descending = page_request.is_descending();
+        let limit = page_request.limit();
+        let tenant = dead_letter_filter.tenant();
+        let dead_letter_entities: Vec<DeadLetterEntity> = sqlx::query_file_as!(DeadLetterEntity,
+            "queries/sqlite/find_all_dead_letters.sql",
+            webhook_id,
+            job_name,
+            from,
+            to,
+            after_created_at,
+            after_id,
+            descending,
+            limit,
+            tenant
+        ).fetch_all(&mut *conn)
+         .await?;
+
+        Ok(dead_letter_entities.into_iter().map(DeadLetter::from).collect())
+    }
+
+    async fn find_dead_letter_by_id(&self, id: &Uuid) -> anyhow::Result<Option<DeadLetter>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let id = id.to_string();
+        Ok(sqlx::query_file_as!(DeadLetterEntity, "queries/sqlite/find_dead_letter_by_id.sql", id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(DeadLetter::from))
+    }
+
+    async fn delete_dead_letter(&self, id: &Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let id = id.to_string();
+        Ok(sqlx::query_file!("queries/sqlite/delete_dead_letter.sql", id)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected() > 0)
+    }
+
+    async fn delete_dead_letter_by_job_done_trigger_webhook_id(&self, job_done_trigger_webhook_id: &Uuid) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_done_trigger_webhook_id = job_done_trigger_webhook_id.to_string();
+        sqlx::query_file!("queries/sqlite/delete_dead_letter_by_job_done_trigger_webhook_id.sql", job_done_trigger_webhook_id)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeadLetterRepository for PostgresDatabase {
+    async fn save_dead_letter(&self, dead_letter: &DeadLetter) -> anyhow::Result<DeadLetter> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let mut tx = conn.begin().await?;
+
+        sqlx::query(include_str!("../../queries/postgres/upsert_dead_letter.sql"))
+            .bind(dead_letter.id().to_string())
+            .bind(dead_letter.job_done_watcher_id().to_string())
+            .bind(dead_letter.job_done_trigger_webhook_id().to_string())
+            .bind(dead_letter.webhook_id().to_string())
+            .bind(dead_letter.job_name().to_string())
+            .bind(dead_letter.namespace().map(|namespace| namespace.to_string()))
+            .bind(dead_letter.request().map(|request| request.method()))
+            .bind(dead_letter.request().map(|request| request.url()))
+            .bind(headers_to_json(dead_letter.request().map(|request| request.headers()))?)
+            .bind(dead_letter.request().map(|request| request.body()))
+            .bind(dead_letter.response_status_code().map(i64::from))
+            .bind(dead_letter.error())
+            .bind(headers_to_json(dead_letter.response_headers())?)
+            .bind(dead_letter.response_body())
+            .bind(i64::from(dead_letter.attempts()))
+            .bind(dead_letter.created_at())
+            .execute(&mut *tx)
+            .await?;
+        let dead_letter_entity = sqlx::query_as::<_, DeadLetterEntity>(include_str!("../../queries/postgres/find_dead_letter_by_job_done_trigger_webhook_id.sql"))
+            .bind(dead_letter.job_done_trigger_webhook_id().to_string())
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(DeadLetter::from(dead_letter_entity))
+    }
+
+    async fn find_all_dead_letters(&self, dead_letter_filter: &DeadLetterFilter, page_request: &PageRequest) -> anyhow::Result<Vec<DeadLetter>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let dead_letter_entities: Vec<DeadLetterEntity> =
+            sqlx::query_as(include_str!("../../queries/postgres/find_all_dead_letters.sql"))
+                .bind(dead_letter_filter.webhook_id().map(|webhook_id| webhook_id.to_string()))
+                .bind(dead_letter_filter.job_name().map(|job_name| job_name.to_string()))
+                .bind(dead_letter_filter.from())
+                .bind(dead_letter_filter.to())
+                .bind(page_request.after().map(|after| after.created_at()))
+                .bind(page_request.after().map(|after| after.id().to_string()))
+                .bind(page_request.is_descending())
+                .bind(page_request.limit() as i64)
+                .bind(dead_letter_filter.tenant())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(dead_letter_entities.into_iter().map(DeadLetter::from).collect())
+    }
+
+    async fn find_dead_letter_by_id(&self, id: &Uuid) -> anyhow::Result<Option<DeadLetter>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_as::<_, DeadLetterEntity>(include_str!("../../queries/postgres/find_dead_letter_by_id.sql"))
+            .bind(id.to_string())
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(DeadLetter::from))
+    }
+
+    async fn delete_dead_letter(&self, id: &Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query(include_str!("../../queries/postgres/delete_dead_letter.sql"))
+            .bind(id.to_string())
+            .execute(&mut *conn)
+            .await?
+            .rows_affected() > 0)
+    }
+
+    async fn delete_dead_letter_by_job_done_trigger_webhook_id(&self, job_done_trigger_webhook_id: &Uuid) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/delete_dead_letter_by_job_done_trigger_webhook_id.sql"))
+            .bind(job_done_trigger_webhook_id.to_string())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeadLetterRepository for MySqlDatabase {
+    async fn save_dead_letter(&self, dead_letter: &DeadLetter) -> anyhow::Result<DeadLetter> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let mut tx = conn.begin().await?;
+
+        sqlx::query(include_str!("../../queries/mysql/upsert_dead_letter.sql"))
+            .bind(dead_letter.id().to_string())
+            .bind(dead_letter.job_done_watcher_id().to_string())
+            .bind(dead_letter.job_done_trigger_webhook_id().to_string())
+            .bind(dead_letter.webhook_id().to_string())
+            .bind(dead_letter.job_name().to_string())
+            .bind(dead_letter.namespace().map(|namespace| namespace.to_string()))
+            .bind(dead_letter.request().map(|request| request.method()))
+            .bind(dead_letter.request().map(|request| request.url()))
+            .bind(headers_to_json(dead_letter.request().map(|request| request.headers()))?)
+            .bind(dead_letter.request().map(|request| request.body()))
+            .bind(dead_letter.response_status_code().map(i64::from))
+            .bind(dead_letter.error())
+            .bind(headers_to_json(dead_letter.response_headers())?)
+            .bind(dead_letter.response_body())
+            .bind(i64::from(dead_letter.attempts()))
+            .bind(dead_letter.created_at())
+            .execute(&mut *tx)
+            .await?;
+        let dead_letter_entity = sqlx::query_as::<_, DeadLetterEntity>(include_str!("../../queries/mysql/find_dead_letter_by_job_done_trigger_webhook_id.sql"))
+            .bind(dead_letter.job_done_trigger_webhook_id().to_string())
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(DeadLetter::from(dead_letter_entity))
+    }
+
+    async fn find_all_dead_letters(&self, dead_letter_filter: &DeadLetterFilter, page_request: &PageRequest) -> anyhow::Result<Vec<DeadLetter>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let webhook_id = dead_letter_filter.webhook_id().map(|webhook_id| webhook_id.to_string());
+        let job_name = dead_letter_filter.job_name().map(|job_name| job_name.to_string());
+        let after_created_at = page_request.after().map(|after| after.created_at());
+        let after_id = page_request.after().map(|after| after.id().to_string());
+        let descending = page_request.is_descending();
+        let dead_letter_entities: Vec<DeadLetterEntity> =
+            sqlx::query_as(include_str!("../../queries/mysql/find_all_dead_letters.sql"))
+                .bind(webhook_id.as_deref())
+                .bind(webhook_id.as_deref())
+                .bind(job_name.as_deref())
+                .bind(job_name.as_deref())
+                .bind(dead_letter_filter.from())
+                .bind(dead_letter_filter.from())
+                .bind(dead_letter_filter.to())
+                .bind(dead_letter_filter.to())
+                .bind(dead_letter_filter.tenant())
+                .bind(dead_letter_filter.tenant())
+                .bind(after_created_at)
+                .bind(descending)
+                .bind(after_created_at)
+                .bind(after_id.as_deref())
+                .bind(descending)
+                .bind(after_created_at)
+                .bind(after_id.as_deref())
+                .bind(descending)
+                .bind(descending)
+                .bind(page_request.limit())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(dead_letter_entities.into_iter().map(DeadLetter::from).collect())
+    }
+
+    async fn find_dead_letter_by_id(&self, id: &Uuid) -> anyhow::Result<Option<DeadLetter>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_as::<_, DeadLetterEntity>(include_str!("../../queries/mysql/find_dead_letter_by_id.sql"))
+            .bind(id.to_string())
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(DeadLetter::from))
+    }
+
+    async fn delete_dead_letter(&self, id: &Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query(include_str!("../../queries/mysql/delete_dead_letter.sql"))
+            .bind(id.to_string())
+            .execute(&mut *conn)
+            .await?
+            .rows_affected() > 0)
+    }
+
+    async fn delete_dead_letter_by_job_done_trigger_webhook_id(&self, job_done_trigger_webhook_id: &Uuid) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/delete_dead_letter_by_job_done_trigger_webhook_id.sql"))
+            .bind(job_done_trigger_webhook_id.to_string())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+}
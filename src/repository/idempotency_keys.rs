@@ -0,0 +1,388 @@
+use std::sync::{Arc, OnceLock};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::entity::IdempotencyKeyEntity;
+use crate::models::service::IdempotencyKey;
+use crate::repository::{InMemoryDatabase, MySqlDatabase, PostgresDatabase, SqliteDatabase, SqlxAcquire};
+
+static IDEMPOTENCY_KEY_REPOSITORY: OnceLock<Arc<dyn IdempotencyKeyRepository>> = OnceLock::new();
+
+pub fn set_idempotency_key_repository(idempotency_key_repository: impl IdempotencyKeyRepository + 'static) {
+    if IDEMPOTENCY_KEY_REPOSITORY.set(Arc::new(idempotency_key_repository)).is_err() {
+        panic!("You can't set Idempotency Key Repository twice!");
+    }
+}
+
+pub fn get_idempotency_key_repository() -> Arc<dyn IdempotencyKeyRepository> {
+    Arc::clone(IDEMPOTENCY_KEY_REPOSITORY.get().expect("Should be set!"))
+}
+
+#[async_trait]
+pub trait IdempotencyKeyRepository: Send + Sync {
+    async fn find_idempotency_key(&self, scope: &str, key: &str) -> anyhow::Result<Option<IdempotencyKey>>;
+    /// Inserts the key unless a non-expired key with the same scope exists, returns whether it was inserted.
+    /// A key still in progress is taken over once claimed before `stale_claimed_before`.
+    async fn insert_idempotency_key(&self, idempotency_key: &IdempotencyKey, stale_claimed_before: DateTime<Utc>) -> anyhow::Result<bool>;
+    /// Completes the key if it is still in progress under the claim made at `claimed_at`.
+    async fn update_idempotency_key_resource_id(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>, resource_id: &Uuid) -> anyhow::Result<()>;
+    /// Releases the key if it is still in progress under the claim made at `claimed_at`.
+    async fn delete_idempotency_key(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>) -> anyhow::Result<()>;
+    async fn count_expired_idempotency_keys(&self, now: DateTime<Utc>) -> anyhow::Result<u64>;
+    async fn delete_expired_idempotency_keys(&self, now: DateTime<Utc>, limit: u32) -> anyhow::Result<u64>;
+}
+
+fn is_unique_violation(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::Database(database_error) if database_error.is_unique_violation())
+}
+
+#[async_trait]
+impl IdempotencyKeyRepository for InMemoryDatabase {
+    async fn find_idempotency_key(&self, scope: &str, key: &str) -> anyhow::Result<Option<IdempotencyKey>> {
+        Ok(self.state.read().await.idempotency_keys.get(&(scope.to_string(), key.to_string())).cloned())
+    }
+
+    async fn insert_idempotency_key(&self, idempotency_key: &IdempotencyKey, stale_claimed_before: DateTime<Utc>) -> anyhow::Result<bool> {
+        let mut state = self.state.write().await;
+        let id = (idempotency_key.scope().to_string(), idempotency_key.key().to_string());
+        if state.idempotency_keys.get(&id).is_some_and(|existing| !existing.is_expired(idempotency_key.created_at())
+            && !existing.is_stale_claim(stale_claimed_before)) {
+            return Ok(false);
+        }
+
+        state.idempotency_keys.insert(id, idempotency_key.clone());
+        Ok(true)
+    }
+
+    async fn update_idempotency_key_resource_id(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>, resource_id: &Uuid) -> anyhow::Result<()> {
+        let mut state = self.state.write().await;
+        if let Some(idempotency_key) = state.idempotency_keys.get_mut(&(scope.to_string(), key.to_string()))
+            .filter(|idempotency_key| idempotency_key.is_claimed_at(claimed_at)) {
+            idempotency_key.set_resource_id(*resource_id);
+        }
+        Ok(())
+    }
+
+    async fn delete_idempotency_key(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>) -> anyhow::Result<()> {
+        let mut state = self.state.write().await;
+        let id = (scope.to_string(), key.to_string());
+        if state.idempotency_keys.get(&id).is_some_and(|idempotency_key| idempotency_key.is_claimed_at(claimed_at)) {
+            state.idempotency_keys.remove(&id);
+        }
+        Ok(())
+    }
+
+    async fn count_expired_idempotency_keys(&self, now: DateTime<Utc>) -> anyhow::Result<u64> {
+        Ok(self.state.read().await.idempotency_keys.values()
+            .filter(|idempotency_key| idempotency_key.is_expired(now))
+            .count() as u64)
+    }
+
+    async fn delete_expired_idempotency_keys(&self, now: DateTime<Utc>, limit: u32) -> anyhow::Result<u64> {
+        let mut state = self.state.write().await;
+
+        let mut expired_idempotency_keys: Vec<(DateTime<Utc>, (String, String))> = state.idempotency_keys.values()
+            .filter(|idempotency_key| idempotency_key.is_expired(now))
+            .map(|idempotency_key| (idempotency_key.expires_at(), (idempotency_key.scope().to_string(), idempotency_key.key().to_string())))
+            .collect();
+        expired_idempotency_keys.sort();
+        expired_idempotency_keys.truncate(limit as usize);
+
+        for (_, id) in &expired_idempotency_keys {
+            state.idempotency_keys.remove(id);
+        }
+
+        Ok(expired_idempotency_keys.len() as u64)
+    }
+}
+
+#[async_trait]
+impl IdempotencyKeyRepository for SqliteDatabase {
+    async fn find_idempotency_key(&self, scope: &str, key: &str) -> anyhow::Result<Option<IdempotencyKey>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_file_as!(IdempotencyKeyEntity, "queries/sqlite/find_idempotency_key.sql", scope, key)
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(IdempotencyKey::from))
+    }
+
+    async fn insert_idempotency_key(&self, idempotency_key: &IdempotencyKey, stale_claimed_before: DateTime<Utc>) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let scope = idempotency_key.scope();
+        let key = idempotency_key.key();
+        let fingerprint = idempotency_key.fingerprint();
+        let resource_id = idempotency_key.resource_id().map(|resource_id| resource_id.to_string());
+        let created_at = idempotency_key.created_at();
+        let expires_at = idempotency_key.expires_at();
+        sqlx::query_file!("queries/sqlite/delete_expired_idempotency_key.sql", scope, key, created_at, stale_claimed_before)
+            .execute(&mut *conn)
+            .await?;
+
+        match sqlx::query_file!("queries/sqlite/insert_idempotency_key.sql", scope, key, fingerprint, resource_id, created_at, expires_at)
+            .execute(&mut *conn)
+            .await {
+            Ok(_) => Ok(true),
+            Err(error) if is_unique_violation(&error) => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn update_idempotency_key_resource_id(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>, resource_id: &Uuid) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let resource_id = resource_id.to_string();
+        sqlx::query_file!("queries/sqlite/update_idempotency_key_resource_id.sql", scope, key, claimed_at, resource_id)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_idempotency_key(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query_file!("queries/sqlite/delete_idempotency_key.sql", scope, key, claimed_at)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn count_expired_idempotency_keys(&self, now: DateTime<Utc>) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let count = sqlx::query_file_scalar!("queries/sqlite/count_expired_idempotency_keys.sql", now)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    async fn delete_expired_idempotency_keys(&self, now: DateTime<Utc>, limit: u32) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_file!("queries/sqlite/delete_expired_idempotency_keys.sql", now, limit)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected())
+    }
+}
+
+#[async_trait]
+impl IdempotencyKeyRepository for PostgresDatabase {
+    async fn find_idempotency_key(&self, scope: &str, key: &str) -> anyhow::Result<Option<IdempotencyKey>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_as::<_, IdempotencyKeyEntity>(include_str!("../../queries/postgres/find_idempotency_key.sql"))
+            .bind(scope)
+            .bind(key)
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(IdempotencyKey::from))
+    }
+
+    async fn insert_idempotency_key(&self, idempotency_key: &IdempotencyKey, stale_claimed_before: DateTime<Utc>) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/delete_expired_idempotency_key.sql"))
+            .bind(idempotency_key.scope())
+            .bind(idempotency_key.key())
+            .bind(idempotency_key.created_at())
+            .bind(stale_claimed_before)
+            .execute(&mut *conn)
+            .await?;
+
+        match sqlx::query(include_str!("../../queries/postgres/insert_idempotency_key.sql"))
+            .bind(idempotency_key.scope())
+            .bind(idempotency_key.key())
+            .bind(idempotency_key.fingerprint())
+            .bind(idempotency_key.resource_id().map(|resource_id| resource_id.to_string()))
+            .bind(idempotency_key.created_at())
+            .bind(idempotency_key.expires_at())
+            .execute(&mut *conn)
+            .await {
+            Ok(_) => Ok(true),
+            Err(error) if is_unique_violation(&error) => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn update_idempotency_key_resource_id(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>, resource_id: &Uuid) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/update_idempotency_key_resource_id.sql"))
+            .bind(scope)
+            .bind(key)
+            .bind(claimed_at)
+            .bind(resource_id.to_string())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_idempotency_key(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/delete_idempotency_key.sql"))
+            .bind(scope)
+            .bind(key)
+            .bind(claimed_at)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn count_expired_idempotency_keys(&self, now: DateTime<Utc>) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let count: i64 = sqlx::query_scalar(include_str!("../../queries/postgres/count_expired_idempotency_keys.sql"))
+            .bind(now)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    async fn delete_expired_idempotency_keys(&self, now: DateTime<Utc>, limit: u32) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query(include_str!("../../queries/postgres/delete_expired_idempotency_keys.sql"))
+            .bind(now)
+            .bind(limit as i64)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected())
+    }
+}
+
+#[async_trait]
+impl IdempotencyKeyRepository for MySqlDatabase {
+    async fn find_idempotency_key(&self, scope: &str, key: &str) -> anyhow::Result<Option<IdempotencyKey>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_as::<_, IdempotencyKeyEntity>(include_str!("../../queries/mysql/find_idempotency_key.sql"))
+            .bind(scope)
+            .bind(key)
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(IdempotencyKey::from))
+    }
+
+    async fn insert_idempotency_key(&self, idempotency_key: &IdempotencyKey, stale_claimed_before: DateTime<Utc>) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/delete_expired_idempotency_key.sql"))
+            .bind(idempotency_key.scope())
+            .bind(idempotency_key.key())
+            .bind(idempotency_key.created_at())
+            .bind(stale_claimed_before)
+            .execute(&mut *conn)
+            .await?;
+
+        match sqlx::query(include_str!("../../queries/mysql/insert_idempotency_key.sql"))
+            .bind(idempotency_key.scope())
+            .bind(idempotency_key.key())
+            .bind(idempotency_key.fingerprint())
+            .bind(idempotency_key.resource_id().map(|resource_id| resource_id.to_string()))
+            .bind(idempotency_key.created_at())
+            .bind(idempotency_key.expires_at())
+            .execute(&mut *conn)
+            .await {
+            Ok(_) => Ok(true),
+            Err(error) if is_unique_violation(&error) => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn update_idempotency_key_resource_id(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>, resource_id: &Uuid) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/update_idempotency_key_resource_id.sql"))
+            .bind(resource_id.to_string())
+            .bind(scope)
+            .bind(key)
+            .bind(claimed_at)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_idempotency_key(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/delete_idempotency_key.sql"))
+            .bind(scope)
+            .bind(key)
+            .bind(claimed_at)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn count_expired_idempotency_keys(&self, now: DateTime<Utc>) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let count: i64 = sqlx::query_scalar(include_str!("../../queries/mysql/count_expired_idempotency_keys.sql"))
+            .bind(now)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    async fn delete_expired_idempotency_keys(&self, now: DateTime<Utc>, limit: u32) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query(include_str!("../../queries/mysql/delete_expired_idempotency_keys.sql"))
+            .bind(now)
+            .bind(limit)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected())
+    }
+}
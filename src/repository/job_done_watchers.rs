@@ -2,18 +2,14 @@ use std::ops::Deref;
 use std::sync::{Arc, OnceLock};
 
 use anyhow::{anyhow, Context};
-use async_rwlock::RwLock;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use futures_util::stream;
-use futures_util::StreamExt;
-use moka::sync::Cache;
-use sqlx::Acquire;
+use sqlx::{Acquire, MySqlConnection};
 use uuid::Uuid;
 
-use crate::models::entity::JobDoneWatcherEntity;
-use crate::models::service::{JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherStatus, JobName};
-use crate::repository::{SqliteDatabase, SqlxAcquire};
+use crate::models::entity::{JobDoneTriggerWebhookAttemptEntity, JobDoneWatcherEntity, JobDoneWatcherStatusEntity};
+use crate::models::service::{JobDoneTriggerWebhookAttempt, JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherFilter, JobDoneWatcherStatus, JobName, Namespace, PageRequest};
+use crate::repository::{headers_to_json, paginate_in_memory, InMemoryDatabase, MySqlDatabase, PostgresDatabase, SqliteDatabase, SqlxAcquire};
 
 #[async_trait]
 pub trait JobDoneWatcherRepository: Send + Sync {
@@ -22,7 +18,11 @@ pub trait JobDoneWatcherRepository: Send + Sync {
         job_name: &JobName,
         status: JobDoneWatcherStatus
     ) -> anyhow::Result<Vec<JobDoneWatcher>>;
-    async fn find_all_watchers(&self) -> anyhow::Result<Vec<JobDoneWatcher>>;
+    async fn find_all_watchers(
+        &self,
+        job_done_watcher_filter: &JobDoneWatcherFilter,
+        page_request: &PageRequest
+    ) -> anyhow::Result<Vec<JobDoneWatcher>>;
     async fn find_watcher_by_id(&self, id: &Uuid) -> anyhow::Result<Option<JobDoneWatcher>>;
     async fn create_watcher(&self, job_done_watcher: &JobDoneWatcher) -> anyhow::Result<()>;
     async fn update_watcher_status(&self, id: &Uuid, job_done_watcher_status: JobDoneWatcherStatus) -> anyhow::Result<()>;
@@ -31,10 +31,11 @@ pub trait JobDoneWatcherRepository: Send + Sync {
         id: &Uuid,
         status: JobDoneWatcherStatus,
         new_status: JobDoneWatcherStatus
-    ) -> anyhow::Result<()>;
+    ) -> anyhow::Result<bool>;
     async fn update_watchers_status_by_job_name_and_status(
         &self,
         job_name: &JobName,
+        namespace: &Namespace,
         status: JobDoneWatcherStatus,
         new_status: JobDoneWatcherStatus
     ) -> anyhow::Result<Vec<JobDoneWatcher>>;
@@ -45,12 +46,26 @@ pub trait JobDoneWatcherRepository: Send + Sync {
         job_done_trigger_webhook_status: JobDoneTriggerWebhookStatus,
         job_done_trigger_webhook_called_at: DateTime<Utc>,
     ) -> anyhow::Result<()>;
+    async fn insert_job_done_trigger_webhook_attempt(&self, job_done_trigger_webhook_attempt: &JobDoneTriggerWebhookAttempt) -> anyhow::Result<()>;
+    async fn find_job_done_trigger_webhook_attempts(&self, job_done_watcher_id: &Uuid) -> anyhow::Result<Vec<JobDoneTriggerWebhookAttempt>>;
+    async fn count_watchers_by_status(&self) -> anyhow::Result<Vec<(JobDoneWatcherStatus, u64)>>;
+    async fn count_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>
+    ) -> anyhow::Result<u64>;
+    async fn delete_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>,
+        limit: u32
+    ) -> anyhow::Result<u64>;
 }
 
 pub static JOB_DONE_WATCHER_REPOSITORY: OnceLock<Arc<dyn JobDoneWatcherRepository>> = OnceLock::new();
 
 pub fn set_job_done_watcher_repository(job_done_watcher_repository: impl JobDoneWatcherRepository + 'static) {
-    if let Err(_) = JOB_DONE_WATCHER_REPOSITORY.set(Arc::new(job_done_watcher_repository)) {
+    if JOB_DONE_WATCHER_REPOSITORY.set(Arc::new(job_done_watcher_repository)).is_err() {
         panic!("You can't set Webhook Repository twice!");
     }
 }
@@ -59,75 +74,79 @@ pub fn get_job_done_watcher_repository() -> Arc<dyn JobDoneWatcherRepository> {
     Arc::clone(JOB_DONE_WATCHER_REPOSITORY.get().expect("Should be set!"))
 }
 
-pub struct InMemoryJobDoneWatcherRepository {
-    job_done_watcher_by_id: Cache<String, Arc<RwLock<JobDoneWatcher>>>,
-}
-
-impl InMemoryJobDoneWatcherRepository {
-    pub fn new() -> Self {
-        Self {
-            job_done_watcher_by_id: Cache::new(15),
-        }
-    }
-}
-
 #[async_trait]
-impl JobDoneWatcherRepository for InMemoryJobDoneWatcherRepository {
+impl JobDoneWatcherRepository for InMemoryDatabase {
     async fn find_all_watchers_by_job_name_and_status(
         &self,
         job_name: &JobName,
         status: JobDoneWatcherStatus
     ) -> anyhow::Result<Vec<JobDoneWatcher>> {
-        Ok(stream::iter(self.job_done_watcher_by_id.iter())
-            .filter_map(|(_, job_done_watcher): (_, Arc<RwLock<JobDoneWatcher>>)| {
-                let job_done_watcher = Arc::clone(&job_done_watcher);
-                async move {
-                    let job_done_watcher = job_done_watcher.read().await;
-                    if job_done_watcher.job_name() == job_name.as_str() && job_done_watcher.status() == status {
-                        Some(job_done_watcher.clone())
-                    } else {
-                        None
-                    }
-                }
-            })
-            .collect::<Vec<_>>()
-            .await)
+        let state = self.state.read().await;
+
+        let mut job_done_watchers: Vec<JobDoneWatcher> = state.job_done_watchers.values()
+            .filter(|job_done_watcher| job_done_watcher.job_name() == job_name.as_str() && job_done_watcher.status() == status)
+            .cloned()
+            .collect();
+        job_done_watchers.sort_by_key(JobDoneWatcher::created_at);
+        Ok(job_done_watchers)
     }
 
-    async fn find_all_watchers(&self) -> anyhow::Result<Vec<JobDoneWatcher>> {
-        Ok(stream::iter(self.job_done_watcher_by_id.iter())
-            .then(|(_, job_done_watcher)| {
-                let job_done_watcher = Arc::clone(&job_done_watcher);
-                async move { job_done_watcher.read().await.clone() }
-            })
-            .collect::<Vec<_>>()
-            .await)
+    async fn find_all_watchers(
+        &self,
+        job_done_watcher_filter: &JobDoneWatcherFilter,
+        page_request: &PageRequest
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
+        let state = self.state.read().await;
+
+        let job_done_watchers: Vec<JobDoneWatcher> = state.job_done_watchers.values()
+            .filter(|job_done_watcher| job_done_watcher_filter.job_name()
+                .is_none_or(|job_name| job_done_watcher.job_name() == job_name.as_str()))
+            .filter(|job_done_watcher| job_done_watcher_filter.status()
+                .is_none_or(|status| job_done_watcher.status() == status))
+            .filter(|job_done_watcher| job_done_watcher_filter.namespace()
+                .is_none_or(|namespace| job_done_watcher.namespace() == Some(namespace)))
+            .filter(|job_done_watcher| job_done_watcher_filter.webhook_id()
+                .is_none_or(|webhook_id| job_done_watcher.job_done_trigger_webhooks()
+                    .iter()
+                    .any(|job_done_trigger_webhook| job_done_trigger_webhook.webhook_id() == webhook_id)))
+            .filter(|job_done_watcher| job_done_watcher_filter.from()
+                .is_none_or(|from| job_done_watcher.created_at() >= from))
+            .filter(|job_done_watcher| job_done_watcher_filter.to()
+                .is_none_or(|to| job_done_watcher.created_at() <= to))
+            .filter(|job_done_watcher| job_done_watcher_filter.tenant()
+                .is_none_or(|tenant| job_done_watcher.tenant() == Some(tenant)))
+            .cloned()
+            .collect();
+        Ok(paginate_in_memory(job_done_watchers, page_request, |job_done_watcher| (job_done_watcher.created_at(), job_done_watcher.id())))
     }
 
     async fn find_watcher_by_id(&self, id: &Uuid) -> anyhow::Result<Option<JobDoneWatcher>> {
-        if let Some(job_done_watcher) = self.job_done_watcher_by_id.get(&id.to_string()) {
-            let job_done_watcher = Arc::clone(&job_done_watcher);
-            let job_done_watcher = job_done_watcher.read().await;
-            Ok(Some(job_done_watcher.clone()))
-        } else {
-            Ok(None)
-        }
+        Ok(self.state.read().await.job_done_watchers.get(id).cloned())
     }
 
     async fn create_watcher(&self, job_done_watcher: &JobDoneWatcher) -> anyhow::Result<()> {
-        self.job_done_watcher_by_id.insert(job_done_watcher.id().to_string(), Arc::new(RwLock::new(job_done_watcher.clone())));
+        let mut state = self.state.write().await;
+        if state.job_done_watchers.contains_key(&job_done_watcher.id()) {
+            return Err(anyhow!("Job Done Watcher with id {} already exists!", job_done_watcher.id()));
+        }
+
+        if let Some(job_done_trigger_webhook) = job_done_watcher.job_done_trigger_webhooks()
+            .iter()
+            .find(|job_done_trigger_webhook| !state.webhooks.contains_key(&job_done_trigger_webhook.webhook_id()))
+        {
+            return Err(anyhow!("Webhook with id {} not found!", job_done_trigger_webhook.webhook_id()));
+        }
+
+        state.job_done_watchers.insert(job_done_watcher.id(), job_done_watcher.clone());
         Ok(())
     }
 
     async fn update_watcher_status(&self, id: &Uuid, job_done_watcher_status: JobDoneWatcherStatus) -> anyhow::Result<()> {
-        let id = id.to_string();
-        if let Some(job_done_watcher) = self.job_done_watcher_by_id.get(&id) {
-            let mut job_done_watcher = job_done_watcher.write().await;
-            job_done_watcher.set_status(job_done_watcher_status);
-            Ok(())
-        } else {
-            return Err(anyhow!("Job Done Watcher with id {} not found!", id));
+        if let Some(job_done_watcher) = self.state.write().await.job_done_watchers.get_mut(id) {
+            set_in_memory_watcher_status(job_done_watcher, job_done_watcher_status);
         }
+
+        Ok(())
     }
 
     async fn update_watcher_status_by_status(
@@ -135,40 +154,35 @@ impl JobDoneWatcherRepository for InMemoryJobDoneWatcherRepository {
         id: &Uuid,
         status: JobDoneWatcherStatus,
         new_status: JobDoneWatcherStatus
-    ) -> anyhow::Result<()> {
-        let id_str = id.to_string();
-        if let Some(job_done_watcher) = self.job_done_watcher_by_id.get(&id_str) {
-            let mut watcher = job_done_watcher.write().await;
-
-            if watcher.status() == status {
-                watcher.set_status(new_status);
-            }
-
-            Ok(())
-        } else {
-            Err(anyhow!("Job Done Watcher with id {} not found!", id_str))
+    ) -> anyhow::Result<bool> {
+        match self.state.write().await.job_done_watchers.get_mut(id) {
+            Some(job_done_watcher) if job_done_watcher.status() == status => {
+                set_in_memory_watcher_status(job_done_watcher, new_status);
+                Ok(true)
+            },
+            _ => Ok(false),
         }
     }
 
     async fn update_watchers_status_by_job_name_and_status(
         &self,
         job_name: &JobName,
+        namespace: &Namespace,
         status: JobDoneWatcherStatus,
         new_status: JobDoneWatcherStatus
     ) -> anyhow::Result<Vec<JobDoneWatcher>> {
-        let mut updated_watchers = Vec::new();
-
-        for (_, job_done_watcher) in &self.job_done_watcher_by_id {
-            let job_done_watcher = Arc::clone(&job_done_watcher);
-            let mut watcher = job_done_watcher.write().await;
-
-            if watcher.job_name() == job_name.as_str() && watcher.status() == status {
-                watcher.set_status(new_status);
-                updated_watchers.push(watcher.clone());
-            }
-        }
-
-        Ok(updated_watchers)
+        let mut state = self.state.write().await;
+
+        let mut updated_job_done_watchers: Vec<JobDoneWatcher> = state.job_done_watchers.values_mut()
+            .filter(|job_done_watcher| job_done_watcher.job_name() == job_name.as_str() && job_done_watcher.status() == status)
+            .filter(|job_done_watcher| job_done_watcher.namespace().is_none_or(|watcher_namespace| watcher_namespace == namespace))
+            .map(|job_done_watcher| {
+                set_in_memory_watcher_status(job_done_watcher, new_status);
+                job_done_watcher.clone()
+            })
+            .collect();
+        updated_job_done_watchers.sort_by_key(JobDoneWatcher::created_at);
+        Ok(updated_job_done_watchers)
     }
 
     async fn update_job_done_trigger_webhook_status_and_called_at(
@@ -178,29 +192,91 @@ impl JobDoneWatcherRepository for InMemoryJobDoneWatcherRepository {
         job_done_trigger_webhook_status: JobDoneTriggerWebhookStatus,
         job_done_trigger_webhook_called_at: DateTime<Utc>
     ) -> anyhow::Result<()> {
-        let id_str = id.to_string();
+        if let Some(job_done_watcher) = self.state.write().await.job_done_watchers.get_mut(id) {
+            if let Some(job_done_trigger_webhook) = job_done_watcher.job_done_trigger_webhooks_mut()
+                .iter_mut()
+                .find(|job_done_trigger_webhook| job_done_trigger_webhook.id() == *job_done_trigger_webhook_id)
+            {
+                job_done_trigger_webhook.set_status(job_done_trigger_webhook_status);
+                job_done_trigger_webhook.set_called_at(job_done_trigger_webhook_called_at);
+            }
+        }
 
-        if let Some(job_done_watcher) = self.job_done_watcher_by_id.get(&id_str) {
-            let mut watcher = job_done_watcher.write().await;
+        Ok(())
+    }
 
-            if let Some(trigger_webhook) = watcher
-                .job_done_trigger_webhooks_mut()
-                .into_iter()
-                .find(|wh| wh.id() == *job_done_trigger_webhook_id)
-            {
-                trigger_webhook.set_status(job_done_trigger_webhook_status);
-                trigger_webhook.set_called_at(job_done_trigger_webhook_called_at);
-                Ok(())
-            } else {
-                Err(anyhow!(
-                    "Job Done Trigger Webhook with id {} not found for Job Done Watcher {}",
-                    job_done_trigger_webhook_id,
-                    id
-                ))
+    async fn insert_job_done_trigger_webhook_attempt(&self, job_done_trigger_webhook_attempt: &JobDoneTriggerWebhookAttempt) -> anyhow::Result<()> {
+        self.state.write().await.job_done_trigger_webhook_attempts
+            .entry(job_done_trigger_webhook_attempt.job_done_watcher_id())
+            .or_default()
+            .push(job_done_trigger_webhook_attempt.clone());
+
+        Ok(())
+    }
+
+    async fn find_job_done_trigger_webhook_attempts(&self, job_done_watcher_id: &Uuid) -> anyhow::Result<Vec<JobDoneTriggerWebhookAttempt>> {
+        let mut job_done_trigger_webhook_attempts = self.state.read().await.job_done_trigger_webhook_attempts
+            .get(job_done_watcher_id)
+            .cloned()
+            .unwrap_or_default();
+        job_done_trigger_webhook_attempts.sort_by_key(|attempt| (attempt.attempted_at(), attempt.id()));
+        Ok(job_done_trigger_webhook_attempts)
+    }
+
+    async fn count_watchers_by_status(&self) -> anyhow::Result<Vec<(JobDoneWatcherStatus, u64)>> {
+        let mut watchers_by_status: Vec<(JobDoneWatcherStatus, u64)> = Vec::new();
+        for job_done_watcher in self.state.read().await.job_done_watchers.values() {
+            match watchers_by_status.iter_mut().find(|(status, _)| *status == job_done_watcher.status()) {
+                Some((_, count)) => *count += 1,
+                None => watchers_by_status.push((job_done_watcher.status(), 1)),
             }
-        } else {
-            Err(anyhow!("Job Done Watcher with id {} not found!", id))
         }
+        Ok(watchers_by_status)
+    }
+
+    async fn count_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>
+    ) -> anyhow::Result<u64> {
+        Ok(self.state.read().await.job_done_watchers.values()
+            .filter(|job_done_watcher| job_done_watcher.status() == status && job_done_watcher.created_at() < created_before)
+            .count() as u64)
+    }
+
+    async fn delete_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>,
+        limit: u32
+    ) -> anyhow::Result<u64> {
+        let mut state = self.state.write().await;
+
+        let mut expired_job_done_watchers: Vec<(DateTime<Utc>, Uuid)> = state.job_done_watchers.values()
+            .filter(|job_done_watcher| job_done_watcher.status() == status && job_done_watcher.created_at() < created_before)
+            .map(|job_done_watcher| (job_done_watcher.created_at(), job_done_watcher.id()))
+            .collect();
+        expired_job_done_watchers.sort();
+        expired_job_done_watchers.truncate(limit as usize);
+
+        for (_, id) in &expired_job_done_watchers {
+            state.job_done_watchers.remove(id);
+            state.job_done_trigger_webhook_attempts.remove(id);
+            state.dead_letters.retain(|_, dead_letter| dead_letter.job_done_watcher_id() != *id);
+        }
+
+        Ok(expired_job_done_watchers.len() as u64)
+    }
+}
+
+// Mirrors job_done_trigger_webhooks_timeout_trigger of the SQL backends.
+fn set_in_memory_watcher_status(job_done_watcher: &mut JobDoneWatcher, status: JobDoneWatcherStatus) {
+    job_done_watcher.set_status(status);
+
+    if status == JobDoneWatcherStatus::Timeout {
+        job_done_watcher.job_done_trigger_webhooks_mut()
+            .iter_mut()
+            .for_each(|job_done_trigger_webhook| job_done_trigger_webhook.set_status(JobDoneTriggerWebhookStatus::Timeout));
     }
 }
 
@@ -228,16 +304,42 @@ impl JobDoneWatcherRepository for SqliteDatabase {
         Ok(job_done_watcher_entities.into_iter().map(JobDoneWatcher::from).collect())
     }
 
-    async fn find_all_watchers(&self) -> anyhow::Result<Vec<JobDoneWatcher>> {
+    async fn find_all_watchers(
+        &self,
+        job_done_watcher_filter: &JobDoneWatcherFilter,
+        page_request: &PageRequest
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
         let mut conn = self.acquire()
             .await
             .with_context(|| "Unable to acquire a database connection".to_string())?;
 
-
+        let job_name = job_done_watcher_filter.job_name().map(ToString::to_string);
+        let status = job_done_watcher_filter.status().map(|status| status.to_string());
+        let namespace = job_done_watcher_filter.namespace().map(ToString::to_string);
+        let webhook_id = job_done_watcher_filter.webhook_id().map(|webhook_id| webhook_id.to_string());
+        let from = job_done_watcher_filter.from();
+        let to = job_done_watcher_filter.to();
+        let after_created_at = page_request.after().map(|after| after.created_at());
+        let after_id = page_request.after().map(|after| after.id().to_string());
+        let descending = page_request.is_descending();
+        let limit = page_request.limit();
+        let tenant = job_done_watcher_filter.tenant();
         let job_done_watcher_entities: Vec<JobDoneWatcherEntity> =
-            sqlx::query_file_as!(JobDoneWatcherEntity, "queries/sqlite/find_all_watchers.sql")
-                .fetch_all(&mut *conn)
-                .await?;
+            sqlx::query_file_as!(JobDoneWatcherEntity,
+                "queries/sqlite/find_all_watchers.sql",
+                job_name,
+                status,
+                namespace,
+                webhook_id,
+                from,
+                to,
+                after_created_at,
+                after_id,
+                descending,
+                limit,
+                tenant
+            ).fetch_all(&mut *conn)
+             .await?;
 
         Ok(job_done_watcher_entities.into_iter().map(JobDoneWatcher::from).collect())
     }
@@ -266,14 +368,18 @@ impl JobDoneWatcherRepository for SqliteDatabase {
         let job_done_watcher_job_name = job_done_watcher.job_name();
         let job_done_watcher_timeout_seconds = job_done_watcher.timeout_seconds();
         let job_done_watcher_status = job_done_watcher.status().to_string();
+        let job_done_watcher_namespace = job_done_watcher.namespace().map(ToString::to_string);
         let job_done_watcher_created_at = job_done_watcher.created_at();
+        let job_done_watcher_tenant = job_done_watcher.tenant();
 
         sqlx::query_file!("queries/sqlite/insert_job_done_watcher.sql",
             job_done_watcher_id,
             job_done_watcher_job_name,
             job_done_watcher_timeout_seconds,
             job_done_watcher_status,
-            job_done_watcher_created_at
+            job_done_watcher_namespace,
+            job_done_watcher_created_at,
+            job_done_watcher_tenant
         ).execute(&mut *tx)
          .await?;
 
@@ -337,7 +443,7 @@ impl JobDoneWatcherRepository for SqliteDatabase {
         id: &Uuid,
         status: JobDoneWatcherStatus,
         new_status: JobDoneWatcherStatus
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<bool> {
         let mut conn = self.acquire()
             .await
             .with_context(|| "Unable to acquire a database connection".to_string())?;
@@ -347,16 +453,20 @@ impl JobDoneWatcherRepository for SqliteDatabase {
         let id = id.to_string();
         let status = status.to_string();
         let new_status = new_status.to_string();
-        let _ = sqlx::query_file!("queries/sqlite/update_watcher_status_by_status.sql", id, status, new_status).execute(&mut *tx).await?;
+        let updated = sqlx::query_file!("queries/sqlite/update_watcher_status_by_status.sql", id, status, new_status)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
 
         tx.commit().await?;
 
-        Ok(())
+        Ok(updated > 0)
     }
 
     async fn update_watchers_status_by_job_name_and_status(
         &self,
         job_name: &JobName,
+        namespace: &Namespace,
         status: JobDoneWatcherStatus,
         new_status: JobDoneWatcherStatus
     ) -> anyhow::Result<Vec<JobDoneWatcher>> {
@@ -377,10 +487,11 @@ impl JobDoneWatcherRepository for SqliteDatabase {
         let job_name = job_name.to_string();
         let status = status.to_string();
         let new_status = new_status.to_string();
+        let namespace = namespace.to_string();
         let ids: Vec<String> = sqlx::query_file_as!(
             Id,
             "queries/sqlite/update_watchers_status_by_job_name_and_status.sql",
-            job_name, status, new_status
+            job_name, status, new_status, namespace
         ).fetch_all(&mut *tx).await?
             .iter()
             .map(|id| id.to_string())
@@ -428,4 +539,750 @@ impl JobDoneWatcherRepository for SqliteDatabase {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    async fn insert_job_done_trigger_webhook_attempt(&self, job_done_trigger_webhook_attempt: &JobDoneTriggerWebhookAttempt) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let id = job_done_trigger_webhook_attempt.id().to_string();
+        let job_done_watcher_id = job_done_trigger_webhook_attempt.job_done_watcher_id().to_string();
+        let job_done_trigger_webhook_id = job_done_trigger_webhook_attempt.job_done_trigger_webhook_id().to_string();
+        let status = job_done_trigger_webhook_attempt.status().to_string();
+        let response_status_code = job_done_trigger_webhook_attempt.response_status_code().map(i64::from);
+        let error = job_done_trigger_webhook_attempt.error();
+        let request = job_done_trigger_webhook_attempt.request();
+        let request_method = request.map(|request| request.method());
+        let request_url = request.map(|request| request.url());
+        let request_headers = headers_to_json(request.map(|request| request.headers()))?;
+        let request_body = request.map(|request| request.body());
+        let response_headers = headers_to_json(job_done_trigger_webhook_attempt.response_headers())?;
+        let response_body = job_done_trigger_webhook_attempt.response_body();
+        let attempted_at = job_done_trigger_webhook_attempt.attempted_at();
+        sqlx::query_file!("queries/sqlite/insert_job_done_trigger_webhook_attempt.sql",
+            id,
+            job_done_watcher_id,
+            job_done_trigger_webhook_id,
+            status,
+            response_status_code,
+            error,
+            request_method,
+            request_url,
+            request_headers,
+            request_body,
+            response_headers,
+            response_body,
+            attempted_at
+        ).execute(&mut *conn)
+         .await?;
+
+        Ok(())
+    }
+
+    async fn find_job_done_trigger_webhook_attempts(&self, job_done_watcher_id: &Uuid) -> anyhow::Result<Vec<JobDoneTriggerWebhookAttempt>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_done_watcher_id = job_done_watcher_id.to_string();
+        let job_done_trigger_webhook_attempt_entities: Vec<JobDoneTriggerWebhookAttemptEntity> =
+            sqlx::query_file_as!(JobDoneTriggerWebhookAttemptEntity, "queries/sqlite/find_job_done_trigger_webhook_attempts.sql", job_done_watcher_id)
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_done_trigger_webhook_attempt_entities.into_iter().map(JobDoneTriggerWebhookAttempt::from).collect())
+    }
+
+    async fn count_watchers_by_status(&self) -> anyhow::Result<Vec<(JobDoneWatcherStatus, u64)>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let watchers_by_status = sqlx::query_file!("queries/sqlite/count_watchers_by_status.sql")
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok(watchers_by_status.into_iter()
+            .map(|row| (JobDoneWatcherStatusEntity::from(row.status).into(), row.count as u64))
+            .collect())
+    }
+
+    async fn count_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>
+    ) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let status = status.to_string();
+        let count = sqlx::query_file_scalar!("queries/sqlite/count_watchers_by_status_created_before.sql", status, created_before)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    async fn delete_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>,
+        limit: u32
+    ) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let mut tx = conn.begin().await?;
+
+        let status = status.to_string();
+        sqlx::query_file!("queries/sqlite/delete_dead_letters_by_watcher_status_created_before.sql", status, created_before, limit)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query_file!("queries/sqlite/delete_job_done_trigger_webhook_attempts_by_watcher_status_created_before.sql", status, created_before, limit)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query_file!("queries/sqlite/delete_job_done_trigger_webhooks_by_watcher_status_created_before.sql", status, created_before, limit)
+            .execute(&mut *tx)
+            .await?;
+        let deleted = sqlx::query_file!("queries/sqlite/delete_watchers_by_status_created_before.sql", status, created_before, limit)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+
+        Ok(deleted)
+    }
+}
+
+#[async_trait]
+impl JobDoneWatcherRepository for PostgresDatabase {
+    async fn find_all_watchers_by_job_name_and_status(
+        &self,
+        job_name: &JobName,
+        status: JobDoneWatcherStatus
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_done_watcher_entities: Vec<JobDoneWatcherEntity> =
+            sqlx::query_as(include_str!("../../queries/postgres/find_all_watchers_by_job_name_and_status.sql"))
+                .bind(job_name.as_str())
+                .bind(status.to_string())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_done_watcher_entities.into_iter().map(JobDoneWatcher::from).collect())
+    }
+
+    async fn find_all_watchers(
+        &self,
+        job_done_watcher_filter: &JobDoneWatcherFilter,
+        page_request: &PageRequest
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_done_watcher_entities: Vec<JobDoneWatcherEntity> =
+            sqlx::query_as(include_str!("../../queries/postgres/find_all_watchers.sql"))
+                .bind(job_done_watcher_filter.job_name().map(ToString::to_string))
+                .bind(job_done_watcher_filter.status().map(|status| status.to_string()))
+                .bind(job_done_watcher_filter.namespace().map(ToString::to_string))
+                .bind(job_done_watcher_filter.webhook_id().map(|webhook_id| webhook_id.to_string()))
+                .bind(job_done_watcher_filter.from())
+                .bind(job_done_watcher_filter.to())
+                .bind(page_request.after().map(|after| after.created_at()))
+                .bind(page_request.after().map(|after| after.id().to_string()))
+                .bind(page_request.is_descending())
+                .bind(page_request.limit() as i64)
+                .bind(job_done_watcher_filter.tenant())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_done_watcher_entities.into_iter().map(JobDoneWatcher::from).collect())
+    }
+
+    async fn find_watcher_by_id(&self, id: &Uuid) -> anyhow::Result<Option<JobDoneWatcher>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_done_watcher_entity: Option<JobDoneWatcherEntity> =
+            sqlx::query_as(include_str!("../../queries/postgres/find_watcher_by_id.sql"))
+                .bind(id.to_string())
+                .fetch_optional(&mut *conn)
+                .await?;
+
+        Ok(job_done_watcher_entity.map(JobDoneWatcher::from))
+    }
+
+    async fn create_watcher(&self, job_done_watcher: &JobDoneWatcher) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let mut tx = conn.begin().await?;
+
+        let job_done_watcher_id = job_done_watcher.id().to_string();
+        sqlx::query(include_str!("../../queries/postgres/insert_job_done_watcher.sql"))
+            .bind(&job_done_watcher_id)
+            .bind(job_done_watcher.job_name())
+            .bind(job_done_watcher.timeout_seconds() as i64)
+            .bind(job_done_watcher.status().to_string())
+            .bind(job_done_watcher.namespace().map(ToString::to_string))
+            .bind(job_done_watcher.created_at())
+            .bind(job_done_watcher.tenant())
+            .execute(&mut *tx)
+            .await?;
+
+        if !job_done_watcher.job_done_trigger_webhooks().is_empty() {
+            let mut query_builder = sqlx::QueryBuilder::new(
+                "INSERT INTO job_done_trigger_webhooks (id, webhook_id, job_done_watcher_id, timeout_seconds, status)"
+            );
+
+            query_builder.push_values(
+                job_done_watcher.job_done_trigger_webhooks(),
+                |mut builder, job_done_trigger_webhook| {
+                    builder.push_bind(job_done_trigger_webhook.id().to_string())
+                        .push_bind(job_done_trigger_webhook.webhook_id().to_string())
+                        .push_bind(job_done_watcher_id.clone())
+                        .push_bind(job_done_trigger_webhook.timeout_seconds() as i64)
+                        .push_bind(job_done_trigger_webhook.status().to_string());
+                }
+            );
+
+            query_builder.build().execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn update_watcher_status(&self, id: &Uuid, new_status: JobDoneWatcherStatus) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/update_watcher_status.sql"))
+            .bind(id.to_string())
+            .bind(new_status.to_string())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn update_watcher_status_by_status(
+        &self,
+        id: &Uuid,
+        status: JobDoneWatcherStatus,
+        new_status: JobDoneWatcherStatus
+    ) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let updated = sqlx::query(include_str!("../../queries/postgres/update_watcher_status_by_status.sql"))
+            .bind(id.to_string())
+            .bind(status.to_string())
+            .bind(new_status.to_string())
+            .execute(&mut *conn)
+            .await?
+            .rows_affected();
+
+        Ok(updated > 0)
+    }
+
+    async fn update_watchers_status_by_job_name_and_status(
+        &self,
+        job_name: &JobName,
+        namespace: &Namespace,
+        status: JobDoneWatcherStatus,
+        new_status: JobDoneWatcherStatus
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_done_watcher_entities: Vec<JobDoneWatcherEntity> =
+            sqlx::query_as(include_str!("../../queries/postgres/update_watchers_status_by_job_name_and_status.sql"))
+                .bind(job_name.as_str())
+                .bind(status.to_string())
+                .bind(new_status.to_string())
+                .bind(namespace.as_str())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_done_watcher_entities.into_iter().map(JobDoneWatcher::from).collect())
+    }
+
+    async fn update_job_done_trigger_webhook_status_and_called_at(
+        &self,
+        id: &Uuid,
+        job_done_trigger_webhook_id: &Uuid,
+        job_done_trigger_webhook_status: JobDoneTriggerWebhookStatus,
+        job_done_trigger_webhook_called_at: DateTime<Utc>
+    ) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/update_job_done_trigger_webhook_status_and_called_at.sql"))
+            .bind(id.to_string())
+            .bind(job_done_trigger_webhook_id.to_string())
+            .bind(job_done_trigger_webhook_status.to_string())
+            .bind(job_done_trigger_webhook_called_at)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn insert_job_done_trigger_webhook_attempt(&self, job_done_trigger_webhook_attempt: &JobDoneTriggerWebhookAttempt) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let request = job_done_trigger_webhook_attempt.request();
+        sqlx::query(include_str!("../../queries/postgres/insert_job_done_trigger_webhook_attempt.sql"))
+            .bind(job_done_trigger_webhook_attempt.id().to_string())
+            .bind(job_done_trigger_webhook_attempt.job_done_watcher_id().to_string())
+            .bind(job_done_trigger_webhook_attempt.job_done_trigger_webhook_id().to_string())
+            .bind(job_done_trigger_webhook_attempt.status().to_string())
+            .bind(job_done_trigger_webhook_attempt.response_status_code().map(i64::from))
+            .bind(job_done_trigger_webhook_attempt.error())
+            .bind(request.map(|request| request.method()))
+            .bind(request.map(|request| request.url()))
+            .bind(headers_to_json(request.map(|request| request.headers()))?)
+            .bind(request.map(|request| request.body()))
+            .bind(headers_to_json(job_done_trigger_webhook_attempt.response_headers())?)
+            .bind(job_done_trigger_webhook_attempt.response_body())
+            .bind(job_done_trigger_webhook_attempt.attempted_at())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_job_done_trigger_webhook_attempts(&self, job_done_watcher_id: &Uuid) -> anyhow::Result<Vec<JobDoneTriggerWebhookAttempt>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_done_trigger_webhook_attempt_entities: Vec<JobDoneTriggerWebhookAttemptEntity> =
+            sqlx::query_as(include_str!("../../queries/postgres/find_job_done_trigger_webhook_attempts.sql"))
+                .bind(job_done_watcher_id.to_string())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_done_trigger_webhook_attempt_entities.into_iter().map(JobDoneTriggerWebhookAttempt::from).collect())
+    }
+
+    async fn count_watchers_by_status(&self) -> anyhow::Result<Vec<(JobDoneWatcherStatus, u64)>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let watchers_by_status: Vec<(String, i64)> = sqlx::query_as(include_str!("../../queries/postgres/count_watchers_by_status.sql"))
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok(watchers_by_status.into_iter()
+            .map(|(status, count)| (JobDoneWatcherStatusEntity::from(status).into(), count as u64))
+            .collect())
+    }
+
+    async fn count_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>
+    ) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let count: i64 = sqlx::query_scalar(include_str!("../../queries/postgres/count_watchers_by_status_created_before.sql"))
+            .bind(status.to_string())
+            .bind(created_before)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    async fn delete_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>,
+        limit: u32
+    ) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query(include_str!("../../queries/postgres/delete_watchers_by_status_created_before.sql"))
+            .bind(status.to_string())
+            .bind(created_before)
+            .bind(limit as i64)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected())
+    }
+}
+
+#[async_trait]
+impl JobDoneWatcherRepository for MySqlDatabase {
+    async fn find_all_watchers_by_job_name_and_status(
+        &self,
+        job_name: &JobName,
+        status: JobDoneWatcherStatus
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_done_watcher_entities: Vec<JobDoneWatcherEntity> =
+            sqlx::query_as(include_str!("../../queries/mysql/find_all_watchers_by_job_name_and_status.sql"))
+                .bind(job_name.as_str())
+                .bind(status.to_string())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_done_watcher_entities.into_iter().map(JobDoneWatcher::from).collect())
+    }
+
+    async fn find_all_watchers(
+        &self,
+        job_done_watcher_filter: &JobDoneWatcherFilter,
+        page_request: &PageRequest
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_name = job_done_watcher_filter.job_name().map(ToString::to_string);
+        let status = job_done_watcher_filter.status().map(|status| status.to_string());
+        let namespace = job_done_watcher_filter.namespace().map(ToString::to_string);
+        let webhook_id = job_done_watcher_filter.webhook_id().map(|webhook_id| webhook_id.to_string());
+        let after_created_at = page_request.after().map(|after| after.created_at());
+        let after_id = page_request.after().map(|after| after.id().to_string());
+        let descending = page_request.is_descending();
+        let job_done_watcher_entities: Vec<JobDoneWatcherEntity> =
+            sqlx::query_as(include_str!("../../queries/mysql/find_all_watchers.sql"))
+                .bind(&job_name)
+                .bind(&job_name)
+                .bind(&status)
+                .bind(&status)
+                .bind(&namespace)
+                .bind(&namespace)
+                .bind(&webhook_id)
+                .bind(&webhook_id)
+                .bind(job_done_watcher_filter.from())
+                .bind(job_done_watcher_filter.from())
+                .bind(job_done_watcher_filter.to())
+                .bind(job_done_watcher_filter.to())
+                .bind(job_done_watcher_filter.tenant())
+                .bind(job_done_watcher_filter.tenant())
+                .bind(after_created_at)
+                .bind(descending)
+                .bind(after_created_at)
+                .bind(&after_id)
+                .bind(descending)
+                .bind(after_created_at)
+                .bind(&after_id)
+                .bind(descending)
+                .bind(descending)
+                .bind(page_request.limit())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_done_watcher_entities.into_iter().map(JobDoneWatcher::from).collect())
+    }
+
+    async fn find_watcher_by_id(&self, id: &Uuid) -> anyhow::Result<Option<JobDoneWatcher>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_done_watcher_entity: Option<JobDoneWatcherEntity> =
+            sqlx::query_as(include_str!("../../queries/mysql/find_watcher_by_id.sql"))
+                .bind(id.to_string())
+                .fetch_optional(&mut *conn)
+                .await?;
+
+        Ok(job_done_watcher_entity.map(JobDoneWatcher::from))
+    }
+
+    async fn create_watcher(&self, job_done_watcher: &JobDoneWatcher) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let mut tx = conn.begin().await?;
+
+        let job_done_watcher_id = job_done_watcher.id().to_string();
+        sqlx::query(include_str!("../../queries/mysql/insert_job_done_watcher.sql"))
+            .bind(&job_done_watcher_id)
+            .bind(job_done_watcher.job_name())
+            .bind(job_done_watcher.timeout_seconds() as i64)
+            .bind(job_done_watcher.status().to_string())
+            .bind(job_done_watcher.namespace().map(ToString::to_string))
+            .bind(job_done_watcher.created_at())
+            .bind(job_done_watcher.tenant())
+            .execute(&mut *tx)
+            .await?;
+
+        if !job_done_watcher.job_done_trigger_webhooks().is_empty() {
+            let mut query_builder = sqlx::QueryBuilder::new(
+                "INSERT INTO job_done_trigger_webhooks (id, webhook_id, job_done_watcher_id, timeout_seconds, status)"
+            );
+
+            query_builder.push_values(
+                job_done_watcher.job_done_trigger_webhooks(),
+                |mut builder, job_done_trigger_webhook| {
+                    builder.push_bind(job_done_trigger_webhook.id().to_string())
+                        .push_bind(job_done_trigger_webhook.webhook_id().to_string())
+                        .push_bind(job_done_watcher_id.clone())
+                        .push_bind(job_done_trigger_webhook.timeout_seconds() as i64)
+                        .push_bind(job_done_trigger_webhook.status().to_string());
+                }
+            );
+
+            query_builder.build().execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn update_watcher_status(&self, id: &Uuid, new_status: JobDoneWatcherStatus) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/update_watcher_status.sql"))
+            .bind(new_status.to_string())
+            .bind(id.to_string())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn update_watcher_status_by_status(
+        &self,
+        id: &Uuid,
+        status: JobDoneWatcherStatus,
+        new_status: JobDoneWatcherStatus
+    ) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let updated = sqlx::query(include_str!("../../queries/mysql/update_watcher_status_by_status.sql"))
+            .bind(new_status.to_string())
+            .bind(id.to_string())
+            .bind(status.to_string())
+            .execute(&mut *conn)
+            .await?
+            .rows_affected();
+
+        Ok(updated > 0)
+    }
+
+    async fn update_watchers_status_by_job_name_and_status(
+        &self,
+        job_name: &JobName,
+        namespace: &Namespace,
+        status: JobDoneWatcherStatus,
+        new_status: JobDoneWatcherStatus
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let mut tx = conn.begin().await?;
+
+        let job_done_watcher_ids: Vec<String> =
+            sqlx::query_scalar(include_str!("../../queries/mysql/find_watcher_ids_by_job_name_and_status_for_update.sql"))
+                .bind(job_name.as_str())
+                .bind(status.to_string())
+                .bind(namespace.as_str())
+                .fetch_all(&mut *tx)
+                .await?;
+
+        if job_done_watcher_ids.is_empty() {
+            tx.commit().await?;
+            return Ok(Vec::new());
+        }
+
+        sqlx::query(include_str!("../../queries/mysql/update_watchers_status_by_job_name_and_status.sql"))
+            .bind(new_status.to_string())
+            .bind(job_name.as_str())
+            .bind(status.to_string())
+            .bind(namespace.as_str())
+            .execute(&mut *tx)
+            .await?;
+
+        let mut job_done_watcher_entities: Vec<JobDoneWatcherEntity> = Vec::with_capacity(job_done_watcher_ids.len());
+        for job_done_watcher_id in job_done_watcher_ids {
+            let job_done_watcher_entity: Option<JobDoneWatcherEntity> =
+                sqlx::query_as(include_str!("../../queries/mysql/find_watcher_by_id.sql"))
+                    .bind(job_done_watcher_id)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+
+            job_done_watcher_entities.extend(job_done_watcher_entity);
+        }
+
+        tx.commit().await?;
+
+        Ok(job_done_watcher_entities.into_iter().map(JobDoneWatcher::from).collect())
+    }
+
+    async fn update_job_done_trigger_webhook_status_and_called_at(
+        &self,
+        id: &Uuid,
+        job_done_trigger_webhook_id: &Uuid,
+        job_done_trigger_webhook_status: JobDoneTriggerWebhookStatus,
+        job_done_trigger_webhook_called_at: DateTime<Utc>
+    ) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/update_job_done_trigger_webhook_status_and_called_at.sql"))
+            .bind(job_done_trigger_webhook_status.to_string())
+            .bind(job_done_trigger_webhook_called_at)
+            .bind(job_done_trigger_webhook_id.to_string())
+            .bind(id.to_string())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn insert_job_done_trigger_webhook_attempt(&self, job_done_trigger_webhook_attempt: &JobDoneTriggerWebhookAttempt) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let request = job_done_trigger_webhook_attempt.request();
+        sqlx::query(include_str!("../../queries/mysql/insert_job_done_trigger_webhook_attempt.sql"))
+            .bind(job_done_trigger_webhook_attempt.id().to_string())
+            .bind(job_done_trigger_webhook_attempt.job_done_watcher_id().to_string())
+            .bind(job_done_trigger_webhook_attempt.job_done_trigger_webhook_id().to_string())
+            .bind(job_done_trigger_webhook_attempt.status().to_string())
+            .bind(job_done_trigger_webhook_attempt.response_status_code().map(i64::from))
+            .bind(job_done_trigger_webhook_attempt.error())
+            .bind(request.map(|request| request.method()))
+            .bind(request.map(|request| request.url()))
+            .bind(headers_to_json(request.map(|request| request.headers()))?)
+            .bind(request.map(|request| request.body()))
+            .bind(headers_to_json(job_done_trigger_webhook_attempt.response_headers())?)
+            .bind(job_done_trigger_webhook_attempt.response_body())
+            .bind(job_done_trigger_webhook_attempt.attempted_at())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_job_done_trigger_webhook_attempts(&self, job_done_watcher_id: &Uuid) -> anyhow::Result<Vec<JobDoneTriggerWebhookAttempt>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_done_trigger_webhook_attempt_entities: Vec<JobDoneTriggerWebhookAttemptEntity> =
+            sqlx::query_as(include_str!("../../queries/mysql/find_job_done_trigger_webhook_attempts.sql"))
+                .bind(job_done_watcher_id.to_string())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_done_trigger_webhook_attempt_entities.into_iter().map(JobDoneTriggerWebhookAttempt::from).collect())
+    }
+
+    async fn count_watchers_by_status(&self) -> anyhow::Result<Vec<(JobDoneWatcherStatus, u64)>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let watchers_by_status: Vec<(String, i64)> = sqlx::query_as(include_str!("../../queries/mysql/count_watchers_by_status.sql"))
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok(watchers_by_status.into_iter()
+            .map(|(status, count)| (JobDoneWatcherStatusEntity::from(status).into(), count as u64))
+            .collect())
+    }
+
+    async fn count_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>
+    ) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let count: i64 = sqlx::query_scalar(include_str!("../../queries/mysql/count_watchers_by_status_created_before.sql"))
+            .bind(status.to_string())
+            .bind(created_before)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    async fn delete_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>,
+        limit: u32
+    ) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let mut tx = conn.begin().await?;
+
+        let job_done_watcher_ids: Vec<String> =
+            sqlx::query_scalar(include_str!("../../queries/mysql/find_watcher_ids_by_status_created_before_for_update.sql"))
+                .bind(status.to_string())
+                .bind(created_before)
+                .bind(limit)
+                .fetch_all(&mut *tx)
+                .await?;
+
+        if job_done_watcher_ids.is_empty() {
+            tx.commit().await?;
+            return Ok(0);
+        }
+
+        let job_done_watcher_ids = job_done_watcher_ids.as_slice();
+        execute_with_id_list(&mut tx, include_str!("../../queries/mysql/delete_dead_letters_by_watcher_ids.sql"), job_done_watcher_ids).await?;
+        execute_with_id_list(&mut tx, include_str!("../../queries/mysql/delete_job_done_trigger_webhook_attempts_by_watcher_ids.sql"), job_done_watcher_ids).await?;
+        execute_with_id_list(&mut tx, include_str!("../../queries/mysql/delete_job_done_trigger_webhooks_by_watcher_ids.sql"), job_done_watcher_ids).await?;
+        let deleted = execute_with_id_list(&mut tx, include_str!("../../queries/mysql/delete_watchers_by_ids.sql"), job_done_watcher_ids).await?;
+
+        tx.commit().await?;
+
+        Ok(deleted)
+    }
+}
+
+// MySQL has no array binds: the ids are appended to the query, ending with `IN`, as a list of binds.
+async fn execute_with_id_list(conn: &mut MySqlConnection, query: &str, ids: &[String]) -> anyhow::Result<u64> {
+    let mut query_builder = sqlx::QueryBuilder::new(query);
+    query_builder.push(" (");
+    let mut separated = query_builder.separated(", ");
+    ids.iter().for_each(|id| { separated.push_bind(id); });
+    separated.push_unseparated(")");
+    Ok(query_builder.build().execute(conn).await?.rows_affected())
+}
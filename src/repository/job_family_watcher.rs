@@ -1,18 +1,18 @@
 use std::sync::{Arc, OnceLock};
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::models::entity::JobFamilyWatcherEntity;
-use crate::models::service::JobFamilyWatcher;
-use crate::repository::{SqliteDatabase, SqlxAcquire};
+use crate::models::entity::{JobFamilyDeliveryEntity, JobFamilyStateEntity, JobFamilyWatcherEntity};
+use crate::models::service::{JobFamilyDelivery, JobFamilyDeliveryFilter, JobFamilyState, JobFamilyWatcher, JobOutcome};
+use crate::repository::{InMemoryDatabase, MySqlDatabase, PostgresDatabase, SqliteDatabase, SqlxAcquire};
 
 static JOB_FAMILY_WATCHER_REPOSITORY: OnceLock<Arc<dyn JobFamilyWatcherRepository>> = OnceLock::new();
 
 pub fn set_job_family_watcher_repository(job_family_watcher_repository: impl JobFamilyWatcherRepository + 'static) {
-    if let Err(_) = JOB_FAMILY_WATCHER_REPOSITORY.set(Arc::new(job_family_watcher_repository)) {
+    if JOB_FAMILY_WATCHER_REPOSITORY.set(Arc::new(job_family_watcher_repository)).is_err() {
         panic!("You can't set Webhook Repository twice!");
     }
 }
@@ -23,30 +23,166 @@ pub fn get_job_family_watcher_repository() -> Arc<dyn JobFamilyWatcherRepository
 
 #[async_trait]
 pub trait JobFamilyWatcherRepository: Send + Sync {
-    async fn create_job_family_watcher(&self, job_family_watcher: JobFamilyWatcher) -> anyhow::Result<()>;
+    async fn save_job_family_watcher(&self, job_family_watcher: JobFamilyWatcher) -> anyhow::Result<()>;
     async fn find_all_job_family_watchers_by_job_family(&self, job_family: &str) -> anyhow::Result<Vec<JobFamilyWatcher>>;
+    async fn find_job_family_state(&self, job_family: &str) -> anyhow::Result<Option<JobFamilyState>>;
+    async fn replace_job_family_state(&self, previous_job_family_state: Option<&JobFamilyState>, job_family_state: &JobFamilyState) -> anyhow::Result<bool>;
+    async fn create_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()>;
+    async fn update_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()>;
+    async fn find_all_job_family_deliveries(&self, job_family_delivery_filter: &JobFamilyDeliveryFilter) -> anyhow::Result<Vec<JobFamilyDelivery>>;
+    async fn count_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>) -> anyhow::Result<u64>;
+    async fn delete_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>, limit: u32) -> anyhow::Result<u64>;
+}
+
+#[async_trait]
+impl JobFamilyWatcherRepository for InMemoryDatabase {
+    async fn save_job_family_watcher(&self, job_family_watcher: JobFamilyWatcher) -> anyhow::Result<()> {
+        let mut state = self.state.write().await;
+        let existing_id = state.job_family_watchers.values()
+            .find(|existing_job_family_watcher| existing_job_family_watcher.name() == job_family_watcher.name())
+            .map(JobFamilyWatcher::id);
+        let job_family_watcher = match existing_id {
+            Some(existing_id) => JobFamilyWatcher::new(
+                existing_id,
+                job_family_watcher.job_family(),
+                &job_family_watcher.url().to_string(),
+                job_family_watcher.request_body(),
+                job_family_watcher.description(),
+                job_family_watcher.on().clone(),
+                job_family_watcher.conditions().clone(),
+            )?.with_name(job_family_watcher.name()).with_tenant(job_family_watcher.tenant()),
+            None if state.job_family_watchers.contains_key(&job_family_watcher.id()) =>
+                return Err(anyhow!("Job Family Watcher with id {} already exists!", job_family_watcher.id())),
+            None => job_family_watcher,
+        };
+
+        state.job_family_watchers.insert(job_family_watcher.id(), job_family_watcher);
+        Ok(())
+    }
+
+    async fn find_all_job_family_watchers_by_job_family(&self, job_family: &str) -> anyhow::Result<Vec<JobFamilyWatcher>> {
+        Ok(self.state.read().await.job_family_watchers.values()
+            .filter(|job_family_watcher| job_family_watcher.job_family() == job_family)
+            .cloned()
+            .collect())
+    }
+
+    async fn find_job_family_state(&self, job_family: &str) -> anyhow::Result<Option<JobFamilyState>> {
+        Ok(self.state.read().await.job_family_states.get(job_family).cloned())
+    }
+
+    async fn replace_job_family_state(&self, previous_job_family_state: Option<&JobFamilyState>, job_family_state: &JobFamilyState) -> anyhow::Result<bool> {
+        let mut state = self.state.write().await;
+        let unchanged = match (state.job_family_states.get(job_family_state.job_family()), previous_job_family_state) {
+            (None, None) => true,
+            (Some(stored_job_family_state), Some(previous_job_family_state)) =>
+                stored_job_family_state.last_outcome() == previous_job_family_state.last_outcome()
+                    && stored_job_family_state.consecutive_failures() == previous_job_family_state.consecutive_failures(),
+            _ => false,
+        };
+
+        if unchanged {
+            state.job_family_states.insert(job_family_state.job_family().to_string(), job_family_state.clone());
+        }
+        Ok(unchanged)
+    }
+
+    async fn create_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()> {
+        let mut state = self.state.write().await;
+        if state.job_family_deliveries.contains_key(&job_family_delivery.id()) {
+            return Err(anyhow!("Job Family Delivery with id {} already exists!", job_family_delivery.id()));
+        }
+
+        if !state.job_family_watchers.contains_key(&job_family_delivery.job_family_watcher_id()) {
+            return Err(anyhow!("Job Family Watcher with id {} not found!", job_family_delivery.job_family_watcher_id()));
+        }
+
+        state.job_family_deliveries.insert(job_family_delivery.id(), job_family_delivery.clone());
+        Ok(())
+    }
+
+    async fn update_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()> {
+        if let Some(stored_job_family_delivery) = self.state.write().await.job_family_deliveries.get_mut(&job_family_delivery.id()) {
+            *stored_job_family_delivery = job_family_delivery.clone();
+        }
+
+        Ok(())
+    }
+
+    async fn find_all_job_family_deliveries(&self, job_family_delivery_filter: &JobFamilyDeliveryFilter) -> anyhow::Result<Vec<JobFamilyDelivery>> {
+        let state = self.state.read().await;
+
+        let mut job_family_deliveries: Vec<JobFamilyDelivery> = state.job_family_deliveries.values()
+            .filter(|job_family_delivery| job_family_delivery_filter.job_family()
+                .is_none_or(|job_family| job_family_delivery.job_family() == job_family))
+            .filter(|job_family_delivery| job_family_delivery_filter.from()
+                .is_none_or(|from| job_family_delivery.created_at() >= from))
+            .filter(|job_family_delivery| job_family_delivery_filter.to()
+                .is_none_or(|to| job_family_delivery.created_at() <= to))
+            .filter(|job_family_delivery| job_family_delivery_filter.tenant()
+                .is_none_or(|tenant| job_family_delivery.tenant() == Some(tenant)))
+            .cloned()
+            .collect();
+        job_family_deliveries.sort_by_key(|job_family_delivery| std::cmp::Reverse(job_family_delivery.created_at()));
+        Ok(job_family_deliveries)
+    }
+
+    async fn count_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>) -> anyhow::Result<u64> {
+        Ok(self.state.read().await.job_family_deliveries.values()
+            .filter(|job_family_delivery| job_family_delivery.created_at() < created_before)
+            .count() as u64)
+    }
+
+    async fn delete_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>, limit: u32) -> anyhow::Result<u64> {
+        let mut state = self.state.write().await;
+
+        let mut expired_job_family_deliveries: Vec<(DateTime<Utc>, Uuid)> = state.job_family_deliveries.values()
+            .filter(|job_family_delivery| job_family_delivery.created_at() < created_before)
+            .map(|job_family_delivery| (job_family_delivery.created_at(), job_family_delivery.id()))
+            .collect();
+        expired_job_family_deliveries.sort();
+        expired_job_family_deliveries.truncate(limit as usize);
+
+        for (_, id) in &expired_job_family_deliveries {
+            state.job_family_deliveries.remove(id);
+        }
+
+        Ok(expired_job_family_deliveries.len() as u64)
+    }
 }
 
 #[async_trait]
 impl JobFamilyWatcherRepository for SqliteDatabase {
-    async fn create_job_family_watcher(&self, job_family_watcher: JobFamilyWatcher) -> anyhow::Result<()> {
+    async fn save_job_family_watcher(&self, job_family_watcher: JobFamilyWatcher) -> anyhow::Result<()> {
         let mut conn = self.acquire()
             .await
             .with_context(|| "Unable to acquire a database connection".to_string())?;
 
-        let id = Uuid::new_v4().to_string();
+        let id = job_family_watcher.id().to_string();
+        let name = job_family_watcher.name();
         let job_family = job_family_watcher.job_family();
         let url = job_family_watcher.url().to_string();
         let request_body = job_family_watcher.request_body();
         let description = job_family_watcher.description();
+        let on_outcomes = join_job_outcomes(job_family_watcher.on());
+        let min_duration_seconds = job_family_watcher.conditions().min_duration_seconds().map(|value| value as i64);
+        let consecutive_failures = job_family_watcher.conditions().consecutive_failures().map(|value| value as i64);
+        let on_state_change = job_family_watcher.conditions().on_state_change();
         let created_at = Utc::now();
-        sqlx::query_file!("queries/sqlite/insert_job_family_watcher.sql",
+        let tenant = job_family_watcher.tenant();
+        sqlx::query_file!("queries/sqlite/upsert_job_family_watcher.sql",
             id,
+            name,
             job_family,
             url,
             request_body,
             description,
-            created_at
+            on_outcomes,
+            min_duration_seconds,
+            consecutive_failures,
+            on_state_change,
+            created_at,
+            tenant
         ).execute(&mut *conn).await?;
 
         Ok(())
@@ -66,4 +202,494 @@ impl JobFamilyWatcherRepository for SqliteDatabase {
 
         Ok(job_family_watcher_entities.into_iter().map(JobFamilyWatcher::from).collect())
     }
+
+    async fn find_job_family_state(&self, job_family: &str) -> anyhow::Result<Option<JobFamilyState>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_family_state_entity: Option<JobFamilyStateEntity> =
+            sqlx::query_file_as!(JobFamilyStateEntity, "queries/sqlite/find_job_family_state.sql", job_family)
+                .fetch_optional(&mut *conn)
+                .await?;
+
+        Ok(job_family_state_entity.map(JobFamilyState::from))
+    }
+
+    async fn replace_job_family_state(&self, previous_job_family_state: Option<&JobFamilyState>, job_family_state: &JobFamilyState) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_family = job_family_state.job_family();
+        let last_outcome = job_family_state.last_outcome().to_string();
+        let consecutive_failures = job_family_state.consecutive_failures() as i64;
+        let updated_at = job_family_state.updated_at();
+        let query_result = match previous_job_family_state {
+            Some(previous_job_family_state) => {
+                let previous_last_outcome = previous_job_family_state.last_outcome().to_string();
+                let previous_consecutive_failures = previous_job_family_state.consecutive_failures() as i64;
+                sqlx::query_file!("queries/sqlite/update_job_family_state.sql",
+                    last_outcome,
+                    consecutive_failures,
+                    updated_at,
+                    job_family,
+                    previous_last_outcome,
+                    previous_consecutive_failures
+                ).execute(&mut *conn).await?
+            },
+            None => sqlx::query_file!("queries/sqlite/insert_job_family_state.sql",
+                job_family,
+                last_outcome,
+                consecutive_failures,
+                updated_at
+            ).execute(&mut *conn).await?,
+        };
+
+        Ok(query_result.rows_affected() > 0)
+    }
+
+    async fn create_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let id = job_family_delivery.id().to_string();
+        let job_family_watcher_id = job_family_delivery.job_family_watcher_id().to_string();
+        let job_family = job_family_delivery.job_family();
+        let job_name = job_family_delivery.job_name().as_str();
+        let job_outcome = job_family_delivery.job_outcome().to_string();
+        let status = job_family_delivery.status().to_string();
+        let attempts = job_family_delivery.attempts() as i64;
+        let response_status_code = job_family_delivery.response_status_code().map(|code| code as i64);
+        let error = job_family_delivery.error();
+        let created_at = job_family_delivery.created_at();
+        let last_attempt_at = job_family_delivery.last_attempt_at();
+        let tenant = job_family_delivery.tenant();
+        sqlx::query_file!("queries/sqlite/insert_job_family_delivery.sql",
+            id,
+            job_family_watcher_id,
+            job_family,
+            job_name,
+            job_outcome,
+            status,
+            attempts,
+            response_status_code,
+            error,
+            created_at,
+            last_attempt_at,
+            tenant
+        ).execute(&mut *conn).await?;
+
+        Ok(())
+    }
+
+    async fn update_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let id = job_family_delivery.id().to_string();
+        let status = job_family_delivery.status().to_string();
+        let attempts = job_family_delivery.attempts() as i64;
+        let response_status_code = job_family_delivery.response_status_code().map(|code| code as i64);
+        let error = job_family_delivery.error();
+        let last_attempt_at = job_family_delivery.last_attempt_at();
+        sqlx::query_file!("queries/sqlite/update_job_family_delivery.sql",
+            id,
+            status,
+            attempts,
+            response_status_code,
+            error,
+            last_attempt_at
+        ).execute(&mut *conn).await?;
+
+        Ok(())
+    }
+
+    async fn find_all_job_family_deliveries(&self, job_family_delivery_filter: &JobFamilyDeliveryFilter) -> anyhow::Result<Vec<JobFamilyDelivery>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_family = job_family_delivery_filter.job_family();
+        let from = job_family_delivery_filter.from();
+        let to = job_family_delivery_filter.to();
+        let tenant = job_family_delivery_filter.tenant();
+        let job_family_delivery_entities: Vec<JobFamilyDeliveryEntity> =
+            sqlx::query_file_as!(JobFamilyDeliveryEntity,
+                "queries/sqlite/find_all_job_family_deliveries.sql",
+                job_family,
+                from,
+                to,
+                tenant
+            ).fetch_all(&mut *conn)
+            .await?;
+
+        Ok(job_family_delivery_entities.into_iter().map(JobFamilyDelivery::from).collect())
+    }
+
+    async fn count_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let count = sqlx::query_file_scalar!("queries/sqlite/count_job_family_deliveries_created_before.sql", created_before)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    async fn delete_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>, limit: u32) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_file!("queries/sqlite/delete_job_family_deliveries_created_before.sql", created_before, limit)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected())
+    }
+}
+
+#[async_trait]
+impl JobFamilyWatcherRepository for PostgresDatabase {
+    async fn save_job_family_watcher(&self, job_family_watcher: JobFamilyWatcher) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/upsert_job_family_watcher.sql"))
+            .bind(job_family_watcher.id().to_string())
+            .bind(job_family_watcher.name())
+            .bind(job_family_watcher.job_family())
+            .bind(job_family_watcher.url().to_string())
+            .bind(job_family_watcher.request_body())
+            .bind(job_family_watcher.description())
+            .bind(join_job_outcomes(job_family_watcher.on()))
+            .bind(job_family_watcher.conditions().min_duration_seconds().map(|value| value as i64))
+            .bind(job_family_watcher.conditions().consecutive_failures().map(|value| value as i64))
+            .bind(job_family_watcher.conditions().on_state_change())
+            .bind(Utc::now())
+            .bind(job_family_watcher.tenant())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_all_job_family_watchers_by_job_family(&self, job_family: &str) -> anyhow::Result<Vec<JobFamilyWatcher>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_family_watcher_entities: Vec<JobFamilyWatcherEntity> =
+            sqlx::query_as(include_str!("../../queries/postgres/find_all_job_family_watchers_by_job_family.sql"))
+                .bind(job_family)
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_family_watcher_entities.into_iter().map(JobFamilyWatcher::from).collect())
+    }
+
+    async fn find_job_family_state(&self, job_family: &str) -> anyhow::Result<Option<JobFamilyState>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_family_state_entity: Option<JobFamilyStateEntity> =
+            sqlx::query_as(include_str!("../../queries/postgres/find_job_family_state.sql"))
+                .bind(job_family)
+                .fetch_optional(&mut *conn)
+                .await?;
+
+        Ok(job_family_state_entity.map(JobFamilyState::from))
+    }
+
+    async fn replace_job_family_state(&self, previous_job_family_state: Option<&JobFamilyState>, job_family_state: &JobFamilyState) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let query = match previous_job_family_state {
+            Some(previous_job_family_state) => sqlx::query(include_str!("../../queries/postgres/update_job_family_state.sql"))
+                .bind(job_family_state.last_outcome().to_string())
+                .bind(job_family_state.consecutive_failures() as i64)
+                .bind(job_family_state.updated_at())
+                .bind(job_family_state.job_family())
+                .bind(previous_job_family_state.last_outcome().to_string())
+                .bind(previous_job_family_state.consecutive_failures() as i64),
+            None => sqlx::query(include_str!("../../queries/postgres/insert_job_family_state.sql"))
+                .bind(job_family_state.job_family())
+                .bind(job_family_state.last_outcome().to_string())
+                .bind(job_family_state.consecutive_failures() as i64)
+                .bind(job_family_state.updated_at()),
+        };
+
+        Ok(query.execute(&mut *conn)
+            .await?
+            .rows_affected() > 0)
+    }
+
+    async fn create_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/insert_job_family_delivery.sql"))
+            .bind(job_family_delivery.id().to_string())
+            .bind(job_family_delivery.job_family_watcher_id().to_string())
+            .bind(job_family_delivery.job_family())
+            .bind(job_family_delivery.job_name().as_str())
+            .bind(job_family_delivery.job_outcome().to_string())
+            .bind(job_family_delivery.status().to_string())
+            .bind(job_family_delivery.attempts() as i64)
+            .bind(job_family_delivery.response_status_code().map(|code| code as i64))
+            .bind(job_family_delivery.error())
+            .bind(job_family_delivery.created_at())
+            .bind(job_family_delivery.last_attempt_at())
+            .bind(job_family_delivery.tenant())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn update_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/update_job_family_delivery.sql"))
+            .bind(job_family_delivery.id().to_string())
+            .bind(job_family_delivery.status().to_string())
+            .bind(job_family_delivery.attempts() as i64)
+            .bind(job_family_delivery.response_status_code().map(|code| code as i64))
+            .bind(job_family_delivery.error())
+            .bind(job_family_delivery.last_attempt_at())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_all_job_family_deliveries(&self, job_family_delivery_filter: &JobFamilyDeliveryFilter) -> anyhow::Result<Vec<JobFamilyDelivery>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_family_delivery_entities: Vec<JobFamilyDeliveryEntity> =
+            sqlx::query_as(include_str!("../../queries/postgres/find_all_job_family_deliveries.sql"))
+                .bind(job_family_delivery_filter.job_family())
+                .bind(job_family_delivery_filter.from())
+                .bind(job_family_delivery_filter.to())
+                .bind(job_family_delivery_filter.tenant())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_family_delivery_entities.into_iter().map(JobFamilyDelivery::from).collect())
+    }
+
+    async fn count_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let count: i64 = sqlx::query_scalar(include_str!("../../queries/postgres/count_job_family_deliveries_created_before.sql"))
+            .bind(created_before)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    async fn delete_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>, limit: u32) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query(include_str!("../../queries/postgres/delete_job_family_deliveries_created_before.sql"))
+            .bind(created_before)
+            .bind(limit as i64)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected())
+    }
+}
+
+#[async_trait]
+impl JobFamilyWatcherRepository for MySqlDatabase {
+    async fn save_job_family_watcher(&self, job_family_watcher: JobFamilyWatcher) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/upsert_job_family_watcher.sql"))
+            .bind(job_family_watcher.id().to_string())
+            .bind(job_family_watcher.name())
+            .bind(job_family_watcher.job_family())
+            .bind(job_family_watcher.url().to_string())
+            .bind(job_family_watcher.request_body())
+            .bind(job_family_watcher.description())
+            .bind(join_job_outcomes(job_family_watcher.on()))
+            .bind(job_family_watcher.conditions().min_duration_seconds().map(|value| value as i64))
+            .bind(job_family_watcher.conditions().consecutive_failures().map(|value| value as i64))
+            .bind(job_family_watcher.conditions().on_state_change())
+            .bind(Utc::now())
+            .bind(job_family_watcher.tenant())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_all_job_family_watchers_by_job_family(&self, job_family: &str) -> anyhow::Result<Vec<JobFamilyWatcher>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_family_watcher_entities: Vec<JobFamilyWatcherEntity> =
+            sqlx::query_as(include_str!("../../queries/mysql/find_all_job_family_watchers_by_job_family.sql"))
+                .bind(job_family)
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_family_watcher_entities.into_iter().map(JobFamilyWatcher::from).collect())
+    }
+
+    async fn find_job_family_state(&self, job_family: &str) -> anyhow::Result<Option<JobFamilyState>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_family_state_entity: Option<JobFamilyStateEntity> =
+            sqlx::query_as(include_str!("../../queries/mysql/find_job_family_state.sql"))
+                .bind(job_family)
+                .fetch_optional(&mut *conn)
+                .await?;
+
+        Ok(job_family_state_entity.map(JobFamilyState::from))
+    }
+
+    async fn replace_job_family_state(&self, previous_job_family_state: Option<&JobFamilyState>, job_family_state: &JobFamilyState) -> anyhow::Result<bool> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let query = match previous_job_family_state {
+            Some(previous_job_family_state) => sqlx::query(include_str!("../../queries/mysql/update_job_family_state.sql"))
+                .bind(job_family_state.last_outcome().to_string())
+                .bind(job_family_state.consecutive_failures() as i64)
+                .bind(job_family_state.updated_at())
+                .bind(job_family_state.job_family())
+                .bind(previous_job_family_state.last_outcome().to_string())
+                .bind(previous_job_family_state.consecutive_failures() as i64),
+            None => sqlx::query(include_str!("../../queries/mysql/insert_job_family_state.sql"))
+                .bind(job_family_state.job_family())
+                .bind(job_family_state.last_outcome().to_string())
+                .bind(job_family_state.consecutive_failures() as i64)
+                .bind(job_family_state.updated_at()),
+        };
+
+        Ok(query.execute(&mut *conn)
+            .await?
+            .rows_affected() > 0)
+    }
+
+    async fn create_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/insert_job_family_delivery.sql"))
+            .bind(job_family_delivery.id().to_string())
+            .bind(job_family_delivery.job_family_watcher_id().to_string())
+            .bind(job_family_delivery.job_family())
+            .bind(job_family_delivery.job_name().as_str())
+            .bind(job_family_delivery.job_outcome().to_string())
+            .bind(job_family_delivery.status().to_string())
+            .bind(job_family_delivery.attempts() as i64)
+            .bind(job_family_delivery.response_status_code().map(|code| code as i64))
+            .bind(job_family_delivery.error())
+            .bind(job_family_delivery.created_at())
+            .bind(job_family_delivery.last_attempt_at())
+            .bind(job_family_delivery.tenant())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn update_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/update_job_family_delivery.sql"))
+            .bind(job_family_delivery.status().to_string())
+            .bind(job_family_delivery.attempts() as i64)
+            .bind(job_family_delivery.response_status_code().map(|code| code as i64))
+            .bind(job_family_delivery.error())
+            .bind(job_family_delivery.last_attempt_at())
+            .bind(job_family_delivery.id().to_string())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_all_job_family_deliveries(&self, job_family_delivery_filter: &JobFamilyDeliveryFilter) -> anyhow::Result<Vec<JobFamilyDelivery>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let job_family_delivery_entities: Vec<JobFamilyDeliveryEntity> =
+            sqlx::query_as(include_str!("../../queries/mysql/find_all_job_family_deliveries.sql"))
+                .bind(job_family_delivery_filter.job_family())
+                .bind(job_family_delivery_filter.job_family())
+                .bind(job_family_delivery_filter.from())
+                .bind(job_family_delivery_filter.from())
+                .bind(job_family_delivery_filter.to())
+                .bind(job_family_delivery_filter.to())
+                .bind(job_family_delivery_filter.tenant())
+                .bind(job_family_delivery_filter.tenant())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(job_family_delivery_entities.into_iter().map(JobFamilyDelivery::from).collect())
+    }
+
+    async fn count_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let count: i64 = sqlx::query_scalar(include_str!("../../queries/mysql/count_job_family_deliveries_created_before.sql"))
+            .bind(created_before)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    async fn delete_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>, limit: u32) -> anyhow::Result<u64> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query(include_str!("../../queries/mysql/delete_job_family_deliveries_created_before.sql"))
+            .bind(created_before)
+            .bind(limit)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected())
+    }
+}
+
+fn join_job_outcomes(job_outcomes: &[JobOutcome]) -> String {
+    job_outcomes.iter()
+        .map(JobOutcome::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
 }
@@ -0,0 +1,244 @@
+use std::future::Future;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::service::{ApiKey, DeadLetter, DeadLetterFilter, IdempotencyKey, JobDoneTriggerWebhookAttempt, JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherFilter, JobDoneWatcherStatus, JobFamilyDelivery, JobFamilyDeliveryFilter, JobFamilyState, JobFamilyWatcher, JobName, Namespace, PageRequest, Webhook, WebhookFilter};
+use crate::repository::{ApiKeyRepository, DeadLetterRepository, IdempotencyKeyRepository, JobDoneWatcherRepository, JobFamilyWatcherRepository, WebhookRepository};
+use crate::service;
+
+/// Times every query of the wrapped repository for the metrics.
+#[derive(Clone)]
+pub struct MeteredRepository<R> {
+    repository: R,
+}
+
+impl<R> MeteredRepository<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+}
+
+async fn metered<T>(method: &str, query: impl Future<Output = T>) -> T {
+    let started_at = Instant::now();
+    let output = query.await;
+    service::metrics::observe_repository_query(method, started_at.elapsed());
+    output
+}
+
+#[async_trait]
+impl<R: ApiKeyRepository> ApiKeyRepository for MeteredRepository<R> {
+    async fn create_api_key(&self, api_key: &ApiKey) -> anyhow::Result<()> {
+        metered("create_api_key", self.repository.create_api_key(api_key)).await
+    }
+
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> anyhow::Result<Option<ApiKey>> {
+        metered("find_api_key_by_hash", self.repository.find_api_key_by_hash(key_hash)).await
+    }
+
+    async fn find_all_api_keys(&self) -> anyhow::Result<Vec<ApiKey>> {
+        metered("find_all_api_keys", self.repository.find_all_api_keys()).await
+    }
+
+    async fn delete_api_key(&self, api_key_id: &Uuid) -> anyhow::Result<bool> {
+        metered("delete_api_key", self.repository.delete_api_key(api_key_id)).await
+    }
+}
+
+#[async_trait]
+impl<R: DeadLetterRepository> DeadLetterRepository for MeteredRepository<R> {
+    async fn save_dead_letter(&self, dead_letter: &DeadLetter) -> anyhow::Result<DeadLetter> {
+        metered("save_dead_letter", self.repository.save_dead_letter(dead_letter)).await
+    }
+
+    async fn find_all_dead_letters(&self, dead_letter_filter: &DeadLetterFilter, page_request: &PageRequest) -> anyhow::Result<Vec<DeadLetter>> {
+        metered("find_all_dead_letters", self.repository.find_all_dead_letters(dead_letter_filter, page_request)).await
+    }
+
+    async fn find_dead_letter_by_id(&self, id: &Uuid) -> anyhow::Result<Option<DeadLetter>> {
+        metered("find_dead_letter_by_id", self.repository.find_dead_letter_by_id(id)).await
+    }
+
+    async fn delete_dead_letter(&self, id: &Uuid) -> anyhow::Result<bool> {
+        metered("delete_dead_letter", self.repository.delete_dead_letter(id)).await
+    }
+
+    async fn delete_dead_letter_by_job_done_trigger_webhook_id(&self, job_done_trigger_webhook_id: &Uuid) -> anyhow::Result<()> {
+        metered("delete_dead_letter_by_job_done_trigger_webhook_id", self.repository.delete_dead_letter_by_job_done_trigger_webhook_id(job_done_trigger_webhook_id)).await
+    }
+}
+
+#[async_trait]
+impl<R: IdempotencyKeyRepository> IdempotencyKeyRepository for MeteredRepository<R> {
+    async fn find_idempotency_key(&self, scope: &str, key: &str) -> anyhow::Result<Option<IdempotencyKey>> {
+        metered("find_idempotency_key", self.repository.find_idempotency_key(scope, key)).await
+    }
+
+    async fn insert_idempotency_key(&self, idempotency_key: &IdempotencyKey, stale_claimed_before: DateTime<Utc>) -> anyhow::Result<bool> {
+        metered("insert_idempotency_key", self.repository.insert_idempotency_key(idempotency_key, stale_claimed_before)).await
+    }
+
+    async fn update_idempotency_key_resource_id(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>, resource_id: &Uuid) -> anyhow::Result<()> {
+        metered("update_idempotency_key_resource_id", self.repository.update_idempotency_key_resource_id(scope, key, claimed_at, resource_id)).await
+    }
+
+    async fn delete_idempotency_key(&self, scope: &str, key: &str, claimed_at: DateTime<Utc>) -> anyhow::Result<()> {
+        metered("delete_idempotency_key", self.repository.delete_idempotency_key(scope, key, claimed_at)).await
+    }
+
+    async fn count_expired_idempotency_keys(&self, now: DateTime<Utc>) -> anyhow::Result<u64> {
+        metered("count_expired_idempotency_keys", self.repository.count_expired_idempotency_keys(now)).await
+    }
+
+    async fn delete_expired_idempotency_keys(&self, now: DateTime<Utc>, limit: u32) -> anyhow::Result<u64> {
+        metered("delete_expired_idempotency_keys", self.repository.delete_expired_idempotency_keys(now, limit)).await
+    }
+}
+
+#[async_trait]
+impl<R: WebhookRepository> WebhookRepository for MeteredRepository<R> {
+    async fn find_all_webhooks(&self, webhook_filter: &WebhookFilter, page_request: &PageRequest) -> anyhow::Result<Vec<Webhook>> {
+        metered("find_all_webhooks", self.repository.find_all_webhooks(webhook_filter, page_request)).await
+    }
+
+    async fn find_webhook_by_id(&self, uuid: &Uuid) -> anyhow::Result<Option<Webhook>> {
+        metered("find_webhook_by_id", self.repository.find_webhook_by_id(uuid)).await
+    }
+
+    async fn create_webhook(&self, webhook: &Webhook) -> anyhow::Result<()> {
+        metered("create_webhook", self.repository.create_webhook(webhook)).await
+    }
+}
+
+#[async_trait]
+impl<R: JobDoneWatcherRepository> JobDoneWatcherRepository for MeteredRepository<R> {
+    async fn find_all_watchers_by_job_name_and_status(
+        &self,
+        job_name: &JobName,
+        status: JobDoneWatcherStatus
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
+        metered("find_all_watchers_by_job_name_and_status", self.repository.find_all_watchers_by_job_name_and_status(job_name, status)).await
+    }
+
+    async fn find_all_watchers(
+        &self,
+        job_done_watcher_filter: &JobDoneWatcherFilter,
+        page_request: &PageRequest
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
+        metered("find_all_watchers", self.repository.find_all_watchers(job_done_watcher_filter, page_request)).await
+    }
+
+    async fn find_watcher_by_id(&self, id: &Uuid) -> anyhow::Result<Option<JobDoneWatcher>> {
+        metered("find_watcher_by_id", self.repository.find_watcher_by_id(id)).await
+    }
+
+    async fn create_watcher(&self, job_done_watcher: &JobDoneWatcher) -> anyhow::Result<()> {
+        metered("create_watcher", self.repository.create_watcher(job_done_watcher)).await
+    }
+
+    async fn update_watcher_status(&self, id: &Uuid, job_done_watcher_status: JobDoneWatcherStatus) -> anyhow::Result<()> {
+        metered("update_watcher_status", self.repository.update_watcher_status(id, job_done_watcher_status)).await
+    }
+
+    async fn update_watcher_status_by_status(
+        &self,
+        id: &Uuid,
+        status: JobDoneWatcherStatus,
+        new_status: JobDoneWatcherStatus
+    ) -> anyhow::Result<bool> {
+        metered("update_watcher_status_by_status", self.repository.update_watcher_status_by_status(id, status, new_status)).await
+    }
+
+    async fn update_watchers_status_by_job_name_and_status(
+        &self,
+        job_name: &JobName,
+        namespace: &Namespace,
+        status: JobDoneWatcherStatus,
+        new_status: JobDoneWatcherStatus
+    ) -> anyhow::Result<Vec<JobDoneWatcher>> {
+        metered("update_watchers_status_by_job_name_and_status", self.repository.update_watchers_status_by_job_name_and_status(job_name, namespace, status, new_status)).await
+    }
+
+    async fn update_job_done_trigger_webhook_status_and_called_at(
+        &self,
+        id: &Uuid,
+        job_done_trigger_webhook_id: &Uuid,
+        job_done_trigger_webhook_status: JobDoneTriggerWebhookStatus,
+        job_done_trigger_webhook_called_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        metered(
+            "update_job_done_trigger_webhook_status_and_called_at",
+            self.repository.update_job_done_trigger_webhook_status_and_called_at(id, job_done_trigger_webhook_id, job_done_trigger_webhook_status, job_done_trigger_webhook_called_at),
+        ).await
+    }
+
+    async fn insert_job_done_trigger_webhook_attempt(&self, job_done_trigger_webhook_attempt: &JobDoneTriggerWebhookAttempt) -> anyhow::Result<()> {
+        metered("insert_job_done_trigger_webhook_attempt", self.repository.insert_job_done_trigger_webhook_attempt(job_done_trigger_webhook_attempt)).await
+    }
+
+    async fn find_job_done_trigger_webhook_attempts(&self, job_done_watcher_id: &Uuid) -> anyhow::Result<Vec<JobDoneTriggerWebhookAttempt>> {
+        metered("find_job_done_trigger_webhook_attempts", self.repository.find_job_done_trigger_webhook_attempts(job_done_watcher_id)).await
+    }
+
+    async fn count_watchers_by_status(&self) -> anyhow::Result<Vec<(JobDoneWatcherStatus, u64)>> {
+        metered("count_watchers_by_status", self.repository.count_watchers_by_status()).await
+    }
+
+    async fn count_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>
+    ) -> anyhow::Result<u64> {
+        metered("count_watchers_by_status_created_before", self.repository.count_watchers_by_status_created_before(status, created_before)).await
+    }
+
+    async fn delete_watchers_by_status_created_before(
+        &self,
+        status: JobDoneWatcherStatus,
+        created_before: DateTime<Utc>,
+        limit: u32
+    ) -> anyhow::Result<u64> {
+        metered("delete_watchers_by_status_created_before", self.repository.delete_watchers_by_status_created_before(status, created_before, limit)).await
+    }
+}
+
+#[async_trait]
+impl<R: JobFamilyWatcherRepository> JobFamilyWatcherRepository for MeteredRepository<R> {
+    async fn save_job_family_watcher(&self, job_family_watcher: JobFamilyWatcher) -> anyhow::Result<()> {
+        metered("save_job_family_watcher", self.repository.save_job_family_watcher(job_family_watcher)).await
+    }
+
+    async fn find_all_job_family_watchers_by_job_family(&self, job_family: &str) -> anyhow::Result<Vec<JobFamilyWatcher>> {
+        metered("find_all_job_family_watchers_by_job_family", self.repository.find_all_job_family_watchers_by_job_family(job_family)).await
+    }
+
+    async fn find_job_family_state(&self, job_family: &str) -> anyhow::Result<Option<JobFamilyState>> {
+        metered("find_job_family_state", self.repository.find_job_family_state(job_family)).await
+    }
+
+    async fn replace_job_family_state(&self, previous_job_family_state: Option<&JobFamilyState>, job_family_state: &JobFamilyState) -> anyhow::Result<bool> {
+        metered("replace_job_family_state", self.repository.replace_job_family_state(previous_job_family_state, job_family_state)).await
+    }
+
+    async fn create_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()> {
+        metered("create_job_family_delivery", self.repository.create_job_family_delivery(job_family_delivery)).await
+    }
+
+    async fn update_job_family_delivery(&self, job_family_delivery: &JobFamilyDelivery) -> anyhow::Result<()> {
+        metered("update_job_family_delivery", self.repository.update_job_family_delivery(job_family_delivery)).await
+    }
+
+    async fn find_all_job_family_deliveries(&self, job_family_delivery_filter: &JobFamilyDeliveryFilter) -> anyhow::Result<Vec<JobFamilyDelivery>> {
+        metered("find_all_job_family_deliveries", self.repository.find_all_job_family_deliveries(job_family_delivery_filter)).await
+    }
+
+    async fn count_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>) -> anyhow::Result<u64> {
+        metered("count_job_family_deliveries_created_before", self.repository.count_job_family_deliveries_created_before(created_before)).await
+    }
+
+    async fn delete_job_family_deliveries_created_before(&self, created_before: DateTime<Utc>, limit: u32) -> anyhow::Result<u64> {
+        metered("delete_job_family_deliveries_created_before", self.repository.delete_job_family_deliveries_created_before(created_before, limit)).await
+    }
+}
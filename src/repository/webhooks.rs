@@ -1,17 +1,16 @@
 use std::sync::{Arc, OnceLock};
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
-use moka::sync::Cache;
 use uuid::Uuid;
 
 use crate::models::entity::WebhookEntity;
-use crate::models::service::Webhook;
-use crate::repository::{SqliteDatabase, SqlxAcquire};
+use crate::models::service::{PageRequest, Webhook, WebhookFilter};
+use crate::repository::{paginate_in_memory, InMemoryDatabase, MySqlDatabase, PostgresDatabase, SqliteDatabase, SqlxAcquire};
 
 #[async_trait]
 pub trait WebhookRepository: Send + Sync {
-    async fn find_all_webhooks(&self) -> anyhow::Result<Vec<Webhook>>;
+    async fn find_all_webhooks(&self, webhook_filter: &WebhookFilter, page_request: &PageRequest) -> anyhow::Result<Vec<Webhook>>;
     async fn find_webhook_by_id(&self, uuid: &Uuid) -> anyhow::Result<Option<Webhook>>;
     async fn create_webhook(&self, webhook: &Webhook) -> anyhow::Result<()>;
 }
@@ -19,7 +18,7 @@ pub trait WebhookRepository: Send + Sync {
 pub static WEBHOOK_REPOSITORY: OnceLock<Arc<dyn WebhookRepository>> = OnceLock::new();
 
 pub fn set_webhook_repository(webhook_repository: impl WebhookRepository + 'static) {
-    if let Err(_) = WEBHOOK_REPOSITORY.set(Arc::new(webhook_repository)) {
+    if WEBHOOK_REPOSITORY.set(Arc::new(webhook_repository)).is_err() {
         panic!("You can't set Webhook Repository twice!");
     }
 }
@@ -28,45 +27,60 @@ pub fn get_webhook_repository() -> Arc<dyn WebhookRepository> {
     WEBHOOK_REPOSITORY.get().expect("Should be set!").clone()
 }
 
-pub struct InMemoryWebhookRepository {
-    webhook_by_id: Cache<String, Webhook>,
-}
-
-impl InMemoryWebhookRepository {
-    pub fn new() -> Self {
-        Self {
-            webhook_by_id: Cache::new(15),
-        }
-    }
-}
-
 #[async_trait]
-impl WebhookRepository for InMemoryWebhookRepository {
-    async fn find_all_webhooks(&self) -> anyhow::Result<Vec<Webhook>> {
-        Ok(self.webhook_by_id.iter()
-            .map(|(_, webhook)| webhook)
-            .collect())
+impl WebhookRepository for InMemoryDatabase {
+    async fn find_all_webhooks(&self, webhook_filter: &WebhookFilter, page_request: &PageRequest) -> anyhow::Result<Vec<Webhook>> {
+        let state = self.state.read().await;
+
+        let webhooks: Vec<Webhook> = state.webhooks.values()
+            .filter(|webhook| webhook_filter.from().is_none_or(|from| webhook.created_at() >= from))
+            .filter(|webhook| webhook_filter.to().is_none_or(|to| webhook.created_at() <= to))
+            .filter(|webhook| webhook_filter.tenant().is_none_or(|tenant| webhook.tenant() == Some(tenant)))
+            .cloned()
+            .collect();
+        Ok(paginate_in_memory(webhooks, page_request, |webhook| (webhook.created_at(), webhook.id())))
     }
 
     async fn find_webhook_by_id(&self, uuid: &Uuid) -> anyhow::Result<Option<Webhook>> {
-        Ok(self.webhook_by_id.get(&uuid.to_string()))
+        Ok(self.state.read().await.webhooks.get(uuid).cloned())
     }
 
     async fn create_webhook(&self, webhook: &Webhook) -> anyhow::Result<()> {
-        Ok(self.webhook_by_id.insert(webhook.id().to_string(), webhook.clone()))
+        let mut state = self.state.write().await;
+        if state.webhooks.contains_key(&webhook.id()) {
+            return Err(anyhow!("Webhook with id {} already exists!", webhook.id()));
+        }
+
+        state.webhooks.insert(webhook.id(), webhook.clone());
+        Ok(())
     }
 }
 
 #[async_trait]
 impl WebhookRepository for SqliteDatabase {
-    async fn find_all_webhooks(&self) -> anyhow::Result<Vec<Webhook>> {
+    async fn find_all_webhooks(&self, webhook_filter: &WebhookFilter, page_request: &PageRequest) -> anyhow::Result<Vec<Webhook>> {
         let mut conn = self.acquire()
             .await
             .with_context(|| "Unable to acquire a database connection".to_string())?;
 
-        let webhook_entities: Vec<WebhookEntity> = sqlx::query_file_as!(WebhookEntity, "queries/sqlite/find_all_webhooks.sql")
-            .fetch_all(&mut *conn)
-            .await?;
+        let from = webhook_filter.from();
+        let to = webhook_filter.to();
+        let after_created_at = page_request.after().map(|after| after.created_at());
+        let after_id = page_request.after().map(|after| after.id().to_string());
+        let descending = page_request.is_descending();
+        let limit = page_request.limit();
+        let tenant = webhook_filter.tenant();
+        let webhook_entities: Vec<WebhookEntity> = sqlx::query_file_as!(WebhookEntity,
+            "queries/sqlite/find_all_webhooks.sql",
+            from,
+            to,
+            after_created_at,
+            after_id,
+            descending,
+            limit,
+            tenant
+        ).fetch_all(&mut *conn)
+         .await?;
 
         Ok(webhook_entities.iter().map(Webhook::from).collect())
     }
@@ -86,24 +100,152 @@ impl WebhookRepository for SqliteDatabase {
     async fn create_webhook(&self, webhook: &Webhook) -> anyhow::Result<()> {
         let mut conn = self.acquire().await?;
 
-        let now = chrono::Utc::now();
+        let webhook_created_at = webhook.created_at();
         let webhook_id = webhook.id().to_string();
         let webhook_url = webhook.url().to_string();
         let webhook_request_body = webhook.request_body();
         let webhook_description = webhook.description();
+        let webhook_tenant = webhook.tenant();
+        let webhook_tls_profile = webhook.tls_profile();
+        let webhook_rate_limit_per_second = webhook.rate_limit_per_second();
         sqlx::query!(
             r#"
-                INSERT INTO webhooks ( id, url, request_body, description, created_at )
-                VALUES ( ?1, ?2, ?3, ?4, ?5 )
+                INSERT INTO webhooks ( id, url, request_body, description, created_at, tenant, tls_profile, rate_limit_per_second )
+                VALUES ( ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8 )
             "#,
             webhook_id,
             webhook_url,
             webhook_request_body,
             webhook_description,
-            now
+            webhook_created_at,
+            webhook_tenant,
+            webhook_tls_profile,
+            webhook_rate_limit_per_second
         ).execute(&mut *conn)
          .await?;
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl WebhookRepository for PostgresDatabase {
+    async fn find_all_webhooks(&self, webhook_filter: &WebhookFilter, page_request: &PageRequest) -> anyhow::Result<Vec<Webhook>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let webhook_entities: Vec<WebhookEntity> =
+            sqlx::query_as(include_str!("../../queries/postgres/find_all_webhooks.sql"))
+                .bind(webhook_filter.from())
+                .bind(webhook_filter.to())
+                .bind(page_request.after().map(|after| after.created_at()))
+                .bind(page_request.after().map(|after| after.id().to_string()))
+                .bind(page_request.is_descending())
+                .bind(page_request.limit() as i64)
+                .bind(webhook_filter.tenant())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(webhook_entities.iter().map(Webhook::from).collect())
+    }
+
+    async fn find_webhook_by_id(&self, uuid: &Uuid) -> anyhow::Result<Option<Webhook>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_as::<_, WebhookEntity>(include_str!("../../queries/postgres/find_webhook_by_id.sql"))
+            .bind(uuid.to_string())
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(Webhook::from))
+    }
+
+    async fn create_webhook(&self, webhook: &Webhook) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/postgres/insert_webhook.sql"))
+            .bind(webhook.id().to_string())
+            .bind(webhook.url().to_string())
+            .bind(webhook.request_body())
+            .bind(webhook.description())
+            .bind(webhook.created_at())
+            .bind(webhook.tenant())
+            .bind(webhook.tls_profile())
+            .bind(webhook.rate_limit_per_second())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for MySqlDatabase {
+    async fn find_all_webhooks(&self, webhook_filter: &WebhookFilter, page_request: &PageRequest) -> anyhow::Result<Vec<Webhook>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        let after_created_at = page_request.after().map(|after| after.created_at());
+        let after_id = page_request.after().map(|after| after.id().to_string());
+        let descending = page_request.is_descending();
+        let webhook_entities: Vec<WebhookEntity> =
+            sqlx::query_as(include_str!("../../queries/mysql/find_all_webhooks.sql"))
+                .bind(webhook_filter.from())
+                .bind(webhook_filter.from())
+                .bind(webhook_filter.to())
+                .bind(webhook_filter.to())
+                .bind(webhook_filter.tenant())
+                .bind(webhook_filter.tenant())
+                .bind(after_created_at)
+                .bind(descending)
+                .bind(after_created_at)
+                .bind(after_id.as_deref())
+                .bind(descending)
+                .bind(after_created_at)
+                .bind(after_id.as_deref())
+                .bind(descending)
+                .bind(descending)
+                .bind(page_request.limit())
+                .fetch_all(&mut *conn)
+                .await?;
+
+        Ok(webhook_entities.iter().map(Webhook::from).collect())
+    }
+
+    async fn find_webhook_by_id(&self, uuid: &Uuid) -> anyhow::Result<Option<Webhook>> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        Ok(sqlx::query_as::<_, WebhookEntity>(include_str!("../../queries/mysql/find_webhook_by_id.sql"))
+            .bind(uuid.to_string())
+            .fetch_optional(&mut *conn)
+            .await?
+            .map(Webhook::from))
+    }
+
+    async fn create_webhook(&self, webhook: &Webhook) -> anyhow::Result<()> {
+        let mut conn = self.acquire()
+            .await
+            .with_context(|| "Unable to acquire a database connection".to_string())?;
+
+        sqlx::query(include_str!("../../queries/mysql/insert_webhook.sql"))
+            .bind(webhook.id().to_string())
+            .bind(webhook.url().to_string())
+            .bind(webhook.request_body())
+            .bind(webhook.description())
+            .bind(webhook.created_at())
+            .bind(webhook.tenant())
+            .bind(webhook.tls_profile())
+            .bind(webhook.rate_limit_per_second())
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+}
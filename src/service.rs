@@ -1,4 +1,16 @@
 pub mod k8s_job_watcher;
 pub mod webhooks;
 pub mod job_done_watchers;
-pub mod job_family_watcher;
\ No newline at end of file
+pub mod job_family_watcher;
+pub mod delivery_pool;
+pub mod purge;
+pub mod idempotency;
+pub mod events;
+pub mod dead_letters;
+pub mod auth;
+pub mod tenants;
+pub mod egress;
+pub mod tls;
+pub mod delivery_client;
+pub mod destinations;
+pub mod metrics;
\ No newline at end of file
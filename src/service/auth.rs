@@ -0,0 +1,147 @@
+use std::sync::OnceLock;
+
+use chrono::Utc;
+use k8s_openapi::api::authentication::v1::{TokenReview, TokenReviewSpec};
+use kube::{Api, Client};
+use kube::api::PostParams;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::models::service::{API_KEY_PREFIX, ApiKey, ApiScope, AuthProvider, AuthSettings, AuthenticationError, CreateApiKeyRequest, CreatedApiKey, Principal};
+use crate::repository;
+
+const BOOTSTRAP_API_KEY_NAME: &str = "bootstrap";
+const SERVICE_ACCOUNT_USERNAME_PREFIX: &str = "system:serviceaccount:";
+
+static AUTH_SETTINGS: OnceLock<AuthSettings> = OnceLock::new();
+static KUBE_CLIENT: OnceLock<Client> = OnceLock::new();
+
+pub fn set_auth_settings(auth_settings: AuthSettings) {
+    if AUTH_SETTINGS.set(auth_settings).is_err() {
+        panic!("You can't set Auth Settings twice!");
+    }
+}
+
+pub fn set_kube_client(client: Client) {
+    if KUBE_CLIENT.set(client).is_err() {
+        panic!("You can't set Kube Client twice!");
+    }
+}
+
+/// Authentication is disabled until settings with at least one provider are set.
+pub fn is_auth_enabled() -> bool {
+    AUTH_SETTINGS.get().is_some()
+}
+
+fn hash_api_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key))
+}
+
+fn generate_api_key() -> String {
+    format!("{}{}{}", API_KEY_PREFIX, Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+pub async fn authenticate(token: &str) -> Result<Principal, AuthenticationError> {
+    let auth_settings = AUTH_SETTINGS.get().expect("Should be set!");
+
+    if token.starts_with(API_KEY_PREFIX) {
+        if !auth_settings.is_enabled(AuthProvider::ApiKey) {
+            return Err(AuthenticationError::InvalidCredentials);
+        }
+        return authenticate_api_key(token).await;
+    }
+
+    if auth_settings.is_enabled(AuthProvider::ServiceAccount) {
+        return authenticate_service_account(auth_settings, token).await;
+    }
+    Err(AuthenticationError::InvalidCredentials)
+}
+
+async fn authenticate_api_key(key: &str) -> Result<Principal, AuthenticationError> {
+    let api_key = repository::get_api_key_repository()
+        .find_api_key_by_hash(&hash_api_key(key)).await
+        .map_err(AuthenticationError::Unavailable)?
+        .ok_or(AuthenticationError::InvalidCredentials)?;
+
+    Ok(Principal::new(&format!("api-key:{}", api_key.name()), api_key.scopes().to_vec(), api_key.tenant()))
+}
+
+async fn authenticate_service_account(auth_settings: &AuthSettings, token: &str) -> Result<Principal, AuthenticationError> {
+    let client = KUBE_CLIENT.get().expect("Should be set!").clone();
+    let token_review = TokenReview {
+        spec: TokenReviewSpec {
+            token: Some(token.to_string()),
+            audiences: (!auth_settings.token_audiences().is_empty()).then(|| auth_settings.token_audiences().to_vec()),
+        },
+        ..Default::default()
+    };
+
+    let token_review = Api::<TokenReview>::all(client)
+        .create(&PostParams::default(), &token_review).await
+        .map_err(|error| AuthenticationError::Unavailable(error.into()))?;
+    let status = token_review.status.unwrap_or_default();
+    if !status.authenticated.unwrap_or(false) {
+        log::info!("Token review rejected the token: {}", status.error.unwrap_or_default());
+        return Err(AuthenticationError::InvalidCredentials);
+    }
+
+    let username = status.user.and_then(|user| user.username).unwrap_or_default();
+    let Some((namespace, name)) = username.strip_prefix(SERVICE_ACCOUNT_USERNAME_PREFIX).and_then(|service_account| service_account.split_once(':')) else {
+        log::info!("Token of {} doesn't belong to a service account", username);
+        return Err(AuthenticationError::InvalidCredentials);
+    };
+
+    // Authenticated but not allowed, so the request is forbidden rather than unauthorized.
+    let scopes = if auth_settings.allows_service_account(namespace, name) {
+        auth_settings.service_account_scopes().to_vec()
+    } else {
+        vec![]
+    };
+    Ok(Principal::new(&username, scopes, Some(namespace)))
+}
+
+pub async fn create_api_key(create_api_key_request: &CreateApiKeyRequest) -> anyhow::Result<CreatedApiKey> {
+    log::info!("Creating API key {}", create_api_key_request.name());
+
+    let key = generate_api_key();
+    let api_key = ApiKey::new(
+        Uuid::new_v4(),
+        create_api_key_request.name(),
+        &hash_api_key(&key),
+        create_api_key_request.scopes().to_vec(),
+        create_api_key_request.tenant(),
+        Utc::now(),
+    );
+    repository::get_api_key_repository().create_api_key(&api_key).await?;
+    Ok(CreatedApiKey::new(api_key, key))
+}
+
+pub async fn get_api_keys() -> anyhow::Result<Vec<ApiKey>> {
+    log::info!("Fetching API keys");
+
+    repository::get_api_key_repository().find_all_api_keys().await
+}
+
+pub async fn delete_api_key(api_key_id: &Uuid) -> anyhow::Result<bool> {
+    log::info!("Deleting API key {}", api_key_id);
+
+    repository::get_api_key_repository().delete_api_key(api_key_id).await
+}
+
+pub async fn ensure_bootstrap_api_key(key: &str) -> anyhow::Result<()> {
+    let api_key_repository = repository::get_api_key_repository();
+    let key_hash = hash_api_key(key);
+    if api_key_repository.find_api_key_by_hash(&key_hash).await?.is_some() {
+        return Ok(());
+    }
+
+    let api_key = ApiKey::new(Uuid::new_v4(), BOOTSTRAP_API_KEY_NAME, &key_hash, vec![ApiScope::Admin], None, Utc::now());
+    if let Err(error) = api_key_repository.create_api_key(&api_key).await {
+        // Another replica may have inserted it in the meantime.
+        if api_key_repository.find_api_key_by_hash(&key_hash).await?.is_none() {
+            return Err(error);
+        }
+    }
+    log::info!("Bootstrap API key registered");
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use anyhow::anyhow;
+use chrono::Utc;
+use k8s_openapi::serde_json::json;
+use uuid::Uuid;
+
+use crate::{repository, service};
+use crate::models::service::{DeadLetter, DeadLetterFilter, DeadLetterRedelivery, HttpUrl, JobDoneTriggerWebhookAttempt, JobDoneWatcher, JobName, Page, PageCursor, PageRequest, RedeliverJobDoneWatcherError};
+
+static DEAD_LETTER_WEBHOOK_URL: OnceLock<HttpUrl> = OnceLock::new();
+
+pub fn set_dead_letter_webhook_url(url: HttpUrl) {
+    if DEAD_LETTER_WEBHOOK_URL.set(url).is_err() {
+        panic!("You can't set Dead Letter Webhook URL twice!");
+    }
+}
+
+pub async fn record_dead_letter(job_done_watcher: &JobDoneWatcher, job_done_trigger_webhook_attempt: &JobDoneTriggerWebhookAttempt) {
+    let job_done_trigger_webhook_id = job_done_trigger_webhook_attempt.job_done_trigger_webhook_id();
+    match save_dead_letter(job_done_watcher, job_done_trigger_webhook_attempt).await {
+        Ok(dead_letter) => {
+            log::warn!("Job done trigger webhook {} moved to the dead letter queue as {}", job_done_trigger_webhook_id, dead_letter.id());
+            notify_dead_letter_webhook(&dead_letter);
+        },
+        Err(error) => log::error!("Failed to record dead letter for job done trigger webhook {}: {:?}", job_done_trigger_webhook_id, error),
+    }
+}
+
+async fn save_dead_letter(job_done_watcher: &JobDoneWatcher, job_done_trigger_webhook_attempt: &JobDoneTriggerWebhookAttempt) -> anyhow::Result<DeadLetter> {
+    let job_done_trigger_webhook_id = job_done_trigger_webhook_attempt.job_done_trigger_webhook_id();
+    let webhook_id = job_done_watcher.job_done_trigger_webhooks().iter()
+        .find(|job_done_trigger_webhook| job_done_trigger_webhook.id() == job_done_trigger_webhook_id)
+        .map(|job_done_trigger_webhook| job_done_trigger_webhook.webhook_id())
+        .ok_or_else(|| anyhow!("Job done trigger webhook {} not found", job_done_trigger_webhook_id))?;
+    let attempts = repository::get_job_done_watcher_repository()
+        .find_job_done_trigger_webhook_attempts(&job_done_watcher.id()).await?
+        .iter()
+        .filter(|attempt| attempt.job_done_trigger_webhook_id() == job_done_trigger_webhook_id)
+        .count();
+
+    let dead_letter = DeadLetter::new(
+        Uuid::new_v4(),
+        job_done_watcher.id(),
+        job_done_trigger_webhook_id,
+        webhook_id,
+        JobName::new(job_done_watcher.job_name())?,
+        job_done_watcher.namespace().cloned(),
+        job_done_trigger_webhook_attempt.request().cloned(),
+        job_done_trigger_webhook_attempt.response_status_code(),
+        job_done_trigger_webhook_attempt.error().map(str::to_string),
+        attempts as u32,
+        Utc::now(),
+    ).with_response(
+        job_done_trigger_webhook_attempt.response_headers().map(<[_]>::to_vec),
+        job_done_trigger_webhook_attempt.response_body().map(str::to_string),
+    );
+    repository::get_dead_letter_repository().save_dead_letter(&dead_letter).await
+}
+
+fn notify_dead_letter_webhook(dead_letter: &DeadLetter) {
+    let Some(url) = DEAD_LETTER_WEBHOOK_URL.get() else {
+        return;
+    };
+
+    let body = json!({
+        "id": dead_letter.id(),
+        "jobDoneWatcherId": dead_letter.job_done_watcher_id(),
+        "jobDoneTriggerWebhookId": dead_letter.job_done_trigger_webhook_id(),
+        "webhookId": dead_letter.webhook_id(),
+        "jobName": dead_letter.job_name().to_string(),
+        "namespace": dead_letter.namespace().map(|namespace| namespace.to_string()),
+        "responseStatusCode": dead_letter.response_status_code(),
+        "error": dead_letter.error(),
+        "attempts": dead_letter.attempts(),
+        "createdAt": dead_letter.created_at(),
+    });
+    let delivery_pool = service::delivery_pool::get_delivery_pool();
+    let request_timeout = delivery_pool.request_timeout();
+    let dead_letter_id = dead_letter.id();
+    delivery_pool.submit(async move {
+        let http_client = service::delivery_client::get_delivery_client().unrestricted_http_client();
+        match http_client.post(url.to_string()).json(&body).timeout(request_timeout).send().await {
+            Ok(response) => log::info!("Dead letter webhook called for {} with status: {}", dead_letter_id, response.status()),
+            Err(error) => log::warn!("Failed to call dead letter webhook for {}: {}", dead_letter_id, error),
+        }
+    });
+}
+
+pub async fn clear_dead_letter(job_done_trigger_webhook_id: &Uuid) {
+    let dead_letter_repository = repository::get_dead_letter_repository();
+    if let Err(error) = dead_letter_repository.delete_dead_letter_by_job_done_trigger_webhook_id(job_done_trigger_webhook_id).await {
+        log::error!("Failed to clear dead letter of job done trigger webhook {}: {:?}", job_done_trigger_webhook_id, error);
+    }
+}
+
+pub async fn get_dead_letters(dead_letter_filter: &DeadLetterFilter, page_request: &PageRequest) -> anyhow::Result<Page<DeadLetter>> {
+    log::info!("Fetching dead letters");
+
+    let dead_letter_repository = repository::get_dead_letter_repository();
+    let dead_letters = dead_letter_repository.find_all_dead_letters(dead_letter_filter, &page_request.with_lookahead()).await?;
+    Ok(Page::from_lookahead(dead_letters, page_request, |dead_letter| PageCursor::new(dead_letter.created_at(), dead_letter.id())))
+}
+
+/// Dead letters belong to the tenant of their watcher.
+async fn find_dead_letter_by_id(dead_letter_id: &Uuid, tenant: Option<&str>) -> anyhow::Result<Option<DeadLetter>> {
+    let Some(dead_letter) = repository::get_dead_letter_repository().find_dead_letter_by_id(dead_letter_id).await? else {
+        return Ok(None);
+    };
+    if tenant.is_some() && service::job_done_watchers::get_job_done_watcher_by_id(&dead_letter.job_done_watcher_id(), tenant).await?.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(dead_letter))
+}
+
+pub async fn get_dead_letter_by_id(dead_letter_id: &Uuid, tenant: Option<&str>) -> anyhow::Result<Option<(DeadLetter, Vec<JobDoneTriggerWebhookAttempt>)>> {
+    log::info!("Fetching dead letter by ID: {}", dead_letter_id);
+
+    let Some(dead_letter) = find_dead_letter_by_id(dead_letter_id, tenant).await? else {
+        return Ok(None);
+    };
+    let job_done_trigger_webhook_attempts = repository::get_job_done_watcher_repository()
+        .find_job_done_trigger_webhook_attempts(&dead_letter.job_done_watcher_id()).await?
+        .into_iter()
+        .filter(|attempt| attempt.job_done_trigger_webhook_id() == dead_letter.job_done_trigger_webhook_id())
+        .collect();
+    Ok(Some((dead_letter, job_done_trigger_webhook_attempts)))
+}
+
+pub async fn discard_dead_letter(dead_letter_id: &Uuid, tenant: Option<&str>) -> anyhow::Result<bool> {
+    log::info!("Discarding dead letter {}", dead_letter_id);
+
+    if find_dead_letter_by_id(dead_letter_id, tenant).await?.is_none() {
+        return Ok(false);
+    }
+    repository::get_dead_letter_repository().delete_dead_letter(dead_letter_id).await
+}
+
+/// Dead letters stay in the queue until their redelivery succeeds.
+pub async fn redeliver_dead_letters(dead_letter_ids: &[Uuid], tenant: Option<&str>) -> anyhow::Result<Vec<(Uuid, DeadLetterRedelivery)>> {
+    log::info!("Redelivering {} dead letters", dead_letter_ids.len());
+
+    let mut redeliveries: HashMap<Uuid, DeadLetterRedelivery> = HashMap::new();
+    let mut dead_letters_by_job_done_watcher: BTreeMap<Uuid, Vec<DeadLetter>> = BTreeMap::new();
+    for dead_letter_id in dead_letter_ids {
+        match find_dead_letter_by_id(dead_letter_id, tenant).await? {
+            Some(dead_letter) => dead_letters_by_job_done_watcher.entry(dead_letter.job_done_watcher_id()).or_default().push(dead_letter),
+            None => { redeliveries.insert(*dead_letter_id, DeadLetterRedelivery::NotFound); },
+        }
+    }
+
+    for (job_done_watcher_id, dead_letters) in dead_letters_by_job_done_watcher {
+        let job_done_trigger_webhook_ids = dead_letters.iter().map(DeadLetter::job_done_trigger_webhook_id).collect();
+        let redelivery = match service::job_done_watchers::redeliver_job_done_trigger_webhooks(&job_done_watcher_id, Some(job_done_trigger_webhook_ids), tenant).await {
+            Ok(_) => DeadLetterRedelivery::Accepted,
+            Err(RedeliverJobDoneWatcherError::Repository(error)) => return Err(error),
+            Err(error) => DeadLetterRedelivery::Rejected(error.to_string()),
+        };
+        for dead_letter in dead_letters {
+            redeliveries.insert(dead_letter.id(), redelivery.clone());
+        }
+    }
+
+    Ok(dead_letter_ids.iter()
+        .map(|dead_letter_id| (*dead_letter_id, redeliveries[dead_letter_id].clone()))
+        .collect())
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use anyhow::Context;
+use openssl::pkey::PKey;
+use reqwest::tls::{Certificate, Identity};
+use reqwest::{Client, ClientBuilder, NoProxy, Proxy, Request, Response};
+
+use crate::models::service::{ClientTlsProfile, DeliveryClientSettings};
+use crate::service;
+
+pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+static DELIVERY_CLIENT: OnceLock<DeliveryClient> = OnceLock::new();
+
+pub struct DeliveryClient {
+    http_client: Client,
+    tls_profile_http_clients: HashMap<String, Client>,
+    // For the URLs set by the operator, which the egress policy doesn't apply to.
+    unrestricted_http_client: Client,
+    proxied: bool,
+}
+
+impl DeliveryClient {
+    pub fn http_client(&self, tls_profile: Option<&str>) -> anyhow::Result<&Client> {
+        match tls_profile {
+            Some(tls_profile) => self.tls_profile_http_clients.get(tls_profile)
+                .ok_or_else(|| anyhow::anyhow!("Unknown TLS profile: {}", tls_profile)),
+            None => Ok(&self.http_client),
+        }
+    }
+
+    pub fn has_tls_profile(&self, tls_profile: &str) -> bool {
+        self.tls_profile_http_clients.contains_key(tls_profile)
+    }
+
+    pub fn unrestricted_http_client(&self) -> &Client {
+        &self.unrestricted_http_client
+    }
+
+    pub async fn execute(&self, request: Request, tls_profile: Option<&str>) -> anyhow::Result<Response> {
+        let http_client = self.http_client(tls_profile)?;
+        // A proxy resolves the name itself, bypassing the resolver of the client.
+        if self.proxied {
+            service::egress::check_url(request.url()).await?;
+        }
+        service::egress::execute(http_client, request).await
+    }
+}
+
+pub fn set_delivery_client(delivery_client_settings: &DeliveryClientSettings, default_tls_profile: &ClientTlsProfile, tls_profiles: &HashMap<String, ClientTlsProfile>) -> anyhow::Result<()> {
+    let http_client = build_http_client(delivery_client_settings, default_tls_profile, true)
+        .with_context(|| "Unable to build the default HTTP client".to_string())?;
+    let tls_profile_http_clients = tls_profiles.iter()
+        .map(|(name, tls_profile)| build_http_client(delivery_client_settings, tls_profile, true)
+            .with_context(|| format!("Unable to build the HTTP client of TLS profile {}", name))
+            .map(|http_client| (name.clone(), http_client)))
+        .collect::<anyhow::Result<HashMap<_, _>>>()?;
+    let unrestricted_http_client = build_http_client(delivery_client_settings, default_tls_profile, false)
+        .with_context(|| "Unable to build the unrestricted HTTP client".to_string())?;
+
+    let delivery_client = DeliveryClient {
+        http_client,
+        tls_profile_http_clients,
+        unrestricted_http_client,
+        proxied: delivery_client_settings.http_proxy().is_some() || delivery_client_settings.https_proxy().is_some(),
+    };
+    if DELIVERY_CLIENT.set(delivery_client).is_err() {
+        panic!("You can't set Delivery Client twice!");
+    }
+    Ok(())
+}
+
+pub fn get_delivery_client() -> &'static DeliveryClient {
+    DELIVERY_CLIENT.get().expect("Should be set!")
+}
+
+fn build_http_client(delivery_client_settings: &DeliveryClientSettings, tls_profile: &ClientTlsProfile, restricted: bool) -> anyhow::Result<Client> {
+    let mut client_builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(delivery_client_settings.connect_timeout())
+        .pool_max_idle_per_host(delivery_client_settings.max_idle_connections_per_host())
+        // Proxies come from the settings only.
+        .no_proxy();
+    if let Some(read_timeout) = delivery_client_settings.read_timeout() {
+        client_builder = client_builder.read_timeout(read_timeout);
+    }
+    client_builder = with_proxies(client_builder, delivery_client_settings)?;
+    if restricted {
+        client_builder = service::egress::restrict_client(client_builder);
+    }
+
+    if let Some(ca_file) = tls_profile.ca_file() {
+        for certificate in Certificate::from_pem_bundle(&read_file(ca_file)?)? {
+            client_builder = client_builder.add_root_certificate(certificate);
+        }
+    }
+    if let (Some(client_cert_file), Some(client_key_file)) = (tls_profile.client_cert_file(), tls_profile.client_key_file()) {
+        // The native TLS backend only reads PKCS#8 keys.
+        let client_key = PKey::private_key_from_pem(&read_file(client_key_file)?)?.private_key_to_pem_pkcs8()?;
+        client_builder = client_builder.identity(Identity::from_pkcs8_pem(&read_file(client_cert_file)?, &client_key)?);
+    }
+    if let Some(min_version) = tls_profile.min_version() {
+        client_builder = client_builder.min_tls_version(min_version);
+    }
+    Ok(client_builder.build()?)
+}
+
+fn with_proxies(mut client_builder: ClientBuilder, delivery_client_settings: &DeliveryClientSettings) -> anyhow::Result<ClientBuilder> {
+    let no_proxy = delivery_client_settings.no_proxy().and_then(NoProxy::from_string);
+    if let Some(http_proxy) = delivery_client_settings.http_proxy() {
+        client_builder = client_builder.proxy(Proxy::http(http_proxy)?.no_proxy(no_proxy.clone()));
+    }
+    if let Some(https_proxy) = delivery_client_settings.https_proxy() {
+        client_builder = client_builder.proxy(Proxy::https(https_proxy)?.no_proxy(no_proxy));
+    }
+    Ok(client_builder)
+}
+
+fn read_file(path: &Path) -> anyhow::Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("Unable to read {}", path.display()))
+}
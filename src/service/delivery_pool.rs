@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+static DELIVERY_POOL: OnceLock<DeliveryPool> = OnceLock::new();
+
+tokio::task_local! {
+    static DELIVERY_SLOT: RefCell<Option<OwnedSemaphorePermit>>;
+}
+
+pub struct DeliveryPool {
+    sender: UnboundedSender<BoxFuture<'static, ()>>,
+    slots: Arc<Semaphore>,
+    request_timeout: Duration,
+    max_attempts: u32,
+    retry_backoff: Duration,
+}
+
+impl DeliveryPool {
+    pub fn submit(&self, delivery: impl Future<Output = ()> + Send + 'static) {
+        if self.sender.send(delivery.boxed()).is_err() {
+            log::error!("Delivery pool is not running, delivery dropped!");
+        }
+    }
+
+    /// Hands the slot of the running delivery over to the queued ones while it waits, e.g. for a destination.
+    pub async fn release_slot_while<F: Future>(&self, future: F) -> F::Output {
+        let Some(slot) = DELIVERY_SLOT.try_with(|slot| slot.borrow_mut().take()).ok().flatten() else {
+            return future.await;
+        };
+        drop(slot);
+
+        let output = future.await;
+        let slot = self.slots.clone().acquire_owned().await.expect("Delivery slots are never closed");
+        let _ = DELIVERY_SLOT.try_with(|current_slot| *current_slot.borrow_mut() = Some(slot));
+        output
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        self.request_timeout
+    }
+
+    pub fn request_timeout_for(&self, timeout_seconds: u32) -> Duration {
+        match timeout_seconds {
+            0 => self.request_timeout,
+            timeout_seconds => Duration::from_secs(timeout_seconds as u64),
+        }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// The backoff doubles after every failed attempt.
+    pub fn retry_backoff_after(&self, attempt: u32) -> Duration {
+        self.retry_backoff.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
+pub fn spawn_delivery_pool(concurrency: usize, request_timeout: Duration, max_attempts: u32, retry_backoff: Duration) {
+    log::info!("Starting delivery pool (concurrency: {}, request timeout: {:?}, max attempts: {}, retry backoff: {:?})", concurrency, request_timeout, max_attempts, retry_backoff);
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let slots = Arc::new(Semaphore::new(concurrency));
+    if DELIVERY_POOL.set(DeliveryPool { sender, slots: slots.clone(), request_timeout, max_attempts, retry_backoff }).is_err() {
+        panic!("You can't set Delivery Pool twice!");
+    }
+
+    actix_web::rt::spawn(run_deliveries(receiver, slots));
+}
+
+pub fn get_delivery_pool() -> &'static DeliveryPool {
+    DELIVERY_POOL.get().expect("Should be set!")
+}
+
+// The semaphore is fair, deliveries start in the order they were submitted.
+async fn run_deliveries(mut receiver: UnboundedReceiver<BoxFuture<'static, ()>>, slots: Arc<Semaphore>) {
+    while let Some(delivery) = receiver.recv().await {
+        let slots = slots.clone();
+        actix_web::rt::spawn(async move {
+            let slot = slots.acquire_owned().await.expect("Delivery slots are never closed");
+            DELIVERY_SLOT.scope(RefCell::new(Some(slot)), delivery).await;
+        });
+    }
+
+    log::warn!("Delivery pool stopped.");
+}
\ No newline at end of file
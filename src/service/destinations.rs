@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::pin::pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use reqwest::Response;
+use tokio::sync::Notify;
+use url::Url;
+use uuid::Uuid;
+
+use crate::models::service::{CircuitState, Destination, DestinationSettings, Webhook};
+use crate::service;
+
+// Deliveries waiting for a probe also check again on their own, in case it is lost.
+const PROBE_WAIT: Duration = Duration::from_secs(1);
+
+static DESTINATIONS: OnceLock<Destinations> = OnceLock::new();
+
+/// Circuit breakers and rate limits of the destinations called by the replica.
+pub struct Destinations {
+    destination_settings: DestinationSettings,
+    hosts: Mutex<HashMap<String, HostState>>,
+    webhooks: Mutex<HashMap<Uuid, TokenBucket>>,
+}
+
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: f64) -> Self {
+        Self { rate, burst, tokens: burst, refilled_at: Instant::now() }
+    }
+
+    // Tokens are taken in advance, so that waiting deliveries are served in order.
+    fn reserve(&mut self) -> Duration {
+        let now = Instant::now();
+        self.tokens = (self.tokens + now.duration_since(self.refilled_at).as_secs_f64() * self.rate).min(self.burst);
+        self.refilled_at = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+
+    // A full bucket unused for a while is no different from a new one.
+    fn is_idle(&self, now: Instant, idle_for: Duration) -> bool {
+        let elapsed = now.duration_since(self.refilled_at);
+        elapsed >= idle_for && self.tokens + elapsed.as_secs_f64() * self.rate >= self.burst
+    }
+}
+
+struct HostState {
+    token_bucket: Option<TokenBucket>,
+    circuit_state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<DateTime<Utc>>,
+    retry_at: Option<Instant>,
+    probing: bool,
+    queued_deliveries: usize,
+    admitted_deliveries: usize,
+    used_at: Instant,
+    state_changed: Arc<Notify>,
+}
+
+enum Admission {
+    Admitted { probe: bool },
+    Wait(Duration),
+}
+
+impl HostState {
+    fn new(destination_settings: &DestinationSettings) -> Self {
+        Self {
+            token_bucket: destination_settings.rate_limit_per_second()
+                .map(|rate| TokenBucket::new(rate, destination_settings.rate_limit_burst() as f64)),
+            circuit_state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+            retry_at: None,
+            probing: false,
+            queued_deliveries: 0,
+            admitted_deliveries: 0,
+            used_at: Instant::now(),
+            state_changed: Arc::new(Notify::new()),
+        }
+    }
+
+    fn is_idle(&self, now: Instant, idle_for: Duration) -> bool {
+        self.circuit_state == CircuitState::Closed
+            && self.queued_deliveries == 0
+            && self.admitted_deliveries == 0
+            && now.duration_since(self.used_at) >= idle_for
+            && self.token_bucket.as_ref().is_none_or(|token_bucket| token_bucket.is_idle(now, idle_for))
+    }
+
+    fn admit(&mut self) -> Admission {
+        let now = Instant::now();
+        match self.circuit_state {
+            CircuitState::Closed => Admission::Admitted { probe: false },
+            CircuitState::Open if self.retry_at.is_some_and(|retry_at| retry_at > now) => {
+                let retry_at = self.retry_at.expect("Should be not empty");
+                Admission::Wait(retry_at - now)
+            },
+            _ if !self.probing => {
+                self.circuit_state = CircuitState::HalfOpen;
+                self.probing = true;
+                Admission::Admitted { probe: true }
+            },
+            _ => Admission::Wait(PROBE_WAIT),
+        }
+    }
+}
+
+/// Admission of a delivery by its destination, to be given the outcome of the call.
+pub struct DestinationPermit<'a> {
+    destinations: &'a Destinations,
+    host: String,
+    probe: bool,
+    recorded: bool,
+}
+
+impl DestinationPermit<'_> {
+    pub fn record(mut self, response: &anyhow::Result<Response>) {
+        self.recorded = true;
+        // Any non-2xx fails a delivery, but only a 5xx or a 429 says the receiver is unhealthy or overloaded.
+        let success = response.as_ref()
+            .is_ok_and(|response| !response.status().is_server_error() && response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS);
+        self.destinations.record(&self.host, self.probe, success);
+    }
+}
+
+impl Drop for DestinationPermit<'_> {
+    fn drop(&mut self) {
+        self.destinations.release(&self.host, self.probe && !self.recorded);
+    }
+}
+
+impl Destinations {
+    pub fn new(destination_settings: DestinationSettings) -> Self {
+        Self {
+            destination_settings,
+            hosts: Mutex::new(HashMap::new()),
+            webhooks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Waits until a delivery to the URL is allowed by the circuit breaker and the rate limits of its host and webhook,
+    /// leaving the slot of the delivery pool to other deliveries in the meantime.
+    pub async fn acquire(&self, url: &Url, webhook: Option<&Webhook>) -> DestinationPermit<'_> {
+        let host = destination_name(url);
+
+        let state_changed = self.hosts.lock().expect("Destinations lock poisoned!")
+            .entry(host.clone())
+            .or_insert_with(|| HostState::new(&self.destination_settings))
+            .state_changed
+            .clone();
+        let mut queued = false;
+        let mut rate_limited = false;
+        loop {
+            // Registered before the state is read, so that no change can be missed.
+            let mut state_changed = pin!(state_changed.notified());
+            state_changed.as_mut().enable();
+            let (admission, host_wait) = {
+                let mut hosts = self.hosts.lock().expect("Destinations lock poisoned!");
+                let host_state = hosts.get_mut(&host).expect("Should be set!");
+                let admission = host_state.admit();
+                host_state.used_at = Instant::now();
+                if let Admission::Admitted { .. } = admission {
+                    host_state.admitted_deliveries += 1;
+                }
+                match admission {
+                    Admission::Admitted { .. } if queued => {
+                        host_state.queued_deliveries -= 1;
+                        queued = false;
+                    },
+                    Admission::Wait(_) if !queued => {
+                        host_state.queued_deliveries += 1;
+                        queued = true;
+                    },
+                    _ => {},
+                }
+                // Tokens are only taken once the circuit lets the delivery through.
+                let host_wait = match (&admission, &mut host_state.token_bucket) {
+                    (Admission::Admitted { .. }, Some(token_bucket)) if !rate_limited => token_bucket.reserve(),
+                    _ => Duration::ZERO,
+                };
+                (admission, host_wait)
+            };
+
+            match admission {
+                Admission::Wait(wait) => {
+                    log::debug!("Delivery to {} queued for {:?}", host, wait);
+                    let delivery_pool = service::delivery_pool::get_delivery_pool();
+                    let _ = delivery_pool.release_slot_while(actix_web::rt::time::timeout(wait, state_changed)).await;
+                },
+                Admission::Admitted { probe } => {
+                    let destination_permit = DestinationPermit { destinations: self, host: host.clone(), probe, recorded: false };
+                    if rate_limited {
+                        return destination_permit;
+                    }
+                    rate_limited = true;
+                    let wait = host_wait.max(self.reserve_webhook_token(webhook));
+                    if wait.is_zero() {
+                        return destination_permit;
+                    }
+                    log::debug!("Delivery to {} rate limited for {:?}", host, wait);
+                    let delivery_pool = service::delivery_pool::get_delivery_pool();
+                    delivery_pool.release_slot_while(actix_web::rt::time::sleep(wait)).await;
+                    // The circuit may have opened in the meantime.
+                    if probe || self.is_closed(&host) {
+                        return destination_permit;
+                    }
+                },
+            }
+        }
+    }
+
+    fn reserve_webhook_token(&self, webhook: Option<&Webhook>) -> Duration {
+        let Some((webhook_id, rate)) = webhook.and_then(|webhook| webhook.rate_limit_per_second().map(|rate| (webhook.id(), rate))) else {
+            return Duration::ZERO;
+        };
+        let mut webhooks = self.webhooks.lock().expect("Destinations lock poisoned!");
+        webhooks.entry(webhook_id)
+            .or_insert_with(|| TokenBucket::new(rate, rate.ceil().max(1.0)))
+            .reserve()
+    }
+
+    /// Forgets the hosts and webhooks, deleted ones included, unused for the idle timeout at `now` with nothing pending on them.
+    pub fn evict_idle(&self, now: Instant) {
+        let idle_for = self.destination_settings.idle_timeout();
+
+        let mut hosts = self.hosts.lock().expect("Destinations lock poisoned!");
+        let host_count = hosts.len();
+        hosts.retain(|_, host_state| !host_state.is_idle(now, idle_for));
+        let evicted_hosts = host_count - hosts.len();
+        drop(hosts);
+
+        let mut webhooks = self.webhooks.lock().expect("Destinations lock poisoned!");
+        let webhook_count = webhooks.len();
+        webhooks.retain(|_, token_bucket| !token_bucket.is_idle(now, idle_for));
+        let evicted_webhooks = webhook_count - webhooks.len();
+
+        log::debug!("Evicted {} idle destinations and {} idle webhook rate limits", evicted_hosts, evicted_webhooks);
+    }
+
+    pub fn state(&self) -> Vec<Destination> {
+        let hosts = self.hosts.lock().expect("Destinations lock poisoned!");
+        let mut destinations: Vec<Destination> = hosts.iter()
+            .map(|(host, host_state)| Destination::new(
+                host,
+                host_state.circuit_state,
+                host_state.consecutive_failures,
+                host_state.opened_at,
+                host_state.queued_deliveries,
+            ))
+            .collect();
+        destinations.sort_by(|first, second| first.name().cmp(second.name()));
+        destinations
+    }
+
+    fn record(&self, host: &str, probe: bool, success: bool) {
+        let mut hosts = self.hosts.lock().expect("Destinations lock poisoned!");
+        let Some(host_state) = hosts.get_mut(host) else {
+            return;
+        };
+
+        if success {
+            if host_state.circuit_state != CircuitState::Closed {
+                log::info!("Circuit of destination {} closed", host);
+            }
+            host_state.circuit_state = CircuitState::Closed;
+            host_state.consecutive_failures = 0;
+            host_state.opened_at = None;
+            host_state.retry_at = None;
+            host_state.probing = false;
+            host_state.state_changed.notify_waiters();
+            return;
+        }
+
+        host_state.consecutive_failures += 1;
+        let threshold_reached = self.destination_settings.circuit_breaker_failures()
+            .is_some_and(|circuit_breaker_failures| host_state.consecutive_failures >= circuit_breaker_failures);
+        if probe || (host_state.circuit_state == CircuitState::Closed && threshold_reached) {
+            let open_duration = self.destination_settings.circuit_breaker_open_duration();
+            log::warn!("Circuit of destination {} opened after {} consecutive failures, next probe in {:?}", host, host_state.consecutive_failures, open_duration);
+            host_state.circuit_state = CircuitState::Open;
+            host_state.opened_at.get_or_insert_with(Utc::now);
+            host_state.retry_at = Some(Instant::now() + open_duration);
+            host_state.probing = false;
+            host_state.state_changed.notify_waiters();
+        }
+    }
+
+    fn is_closed(&self, host: &str) -> bool {
+        let hosts = self.hosts.lock().expect("Destinations lock poisoned!");
+        hosts.get(host).is_some_and(|host_state| host_state.circuit_state == CircuitState::Closed)
+    }
+
+    fn release(&self, host: &str, release_probe: bool) {
+        let mut hosts = self.hosts.lock().expect("Destinations lock poisoned!");
+        if let Some(host_state) = hosts.get_mut(host) {
+            host_state.admitted_deliveries -= 1;
+            host_state.used_at = Instant::now();
+            if release_probe {
+                host_state.probing = false;
+                host_state.state_changed.notify_waiters();
+            }
+        }
+    }
+}
+
+pub fn set_destination_settings(destination_settings: DestinationSettings) {
+    if DESTINATIONS.set(Destinations::new(destination_settings)).is_err() {
+        panic!("You can't set Destination Settings twice!");
+    }
+}
+
+fn get_destinations() -> &'static Destinations {
+    DESTINATIONS.get().expect("Should be set!")
+}
+
+pub async fn acquire(url: &Url, webhook: Option<&Webhook>) -> DestinationPermit<'static> {
+    get_destinations().acquire(url, webhook).await
+}
+
+pub fn spawn_idle_destinations_eviction_task() {
+    let idle_timeout = get_destinations().destination_settings.idle_timeout();
+    log::info!("Starting idle destinations eviction task (idle timeout: {:?})", idle_timeout);
+    actix_web::rt::spawn(async move {
+        let mut ticker = actix_web::rt::time::interval(idle_timeout);
+        loop {
+            ticker.tick().await;
+            get_destinations().evict_idle(Instant::now());
+        }
+    });
+}
+
+pub fn get_destinations_state() -> Vec<Destination> {
+    get_destinations().state()
+}
+
+fn destination_name(url: &Url) -> String {
+    format!("{}:{}", url.host_str().unwrap_or_default(), url.port_or_known_default().unwrap_or_default())
+}
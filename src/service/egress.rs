@@ -0,0 +1,97 @@
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::{Arc, OnceLock};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use reqwest::{Client, ClientBuilder, Request, Response};
+use url::Url;
+
+use crate::models::service::{EgressError, EgressPolicy};
+
+const MAX_REDIRECTS: usize = 10;
+
+static EGRESS_POLICY: OnceLock<Arc<EgressPolicy>> = OnceLock::new();
+
+pub fn set_egress_policy(egress_policy: EgressPolicy) {
+    if EGRESS_POLICY.set(Arc::new(egress_policy)).is_err() {
+        panic!("You can't set Egress Policy twice!");
+    }
+}
+
+fn get_egress_policy() -> &'static Arc<EgressPolicy> {
+    EGRESS_POLICY.get().expect("Should be set!")
+}
+
+/// Restricts the connections and redirects of a client to the egress policy.
+pub fn restrict_client(client_builder: ClientBuilder) -> ClientBuilder {
+    let egress_policy = get_egress_policy();
+    let redirect_egress_policy = egress_policy.clone();
+    // Redirects to a name go through the resolver, only address literals have to be checked here.
+    let redirect_policy = Policy::custom(move |attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            return attempt.error("too many redirects");
+        }
+        match redirect_egress_policy.check_url(attempt.url()) {
+            Ok(()) => attempt.follow(),
+            Err(error) => attempt.error(error),
+        }
+    });
+    client_builder
+        .dns_resolver(Arc::new(EgressResolver(egress_policy.clone())))
+        .redirect(redirect_policy)
+}
+
+/// Checked when a destination is registered; a name that doesn't resolve yet is checked again on delivery.
+pub async fn check_url(url: &Url) -> Result<(), EgressError> {
+    let egress_policy = get_egress_policy();
+    egress_policy.check_url(url)?;
+
+    if let Some(url::Host::Domain(host)) = url.host() {
+        match resolve(egress_policy, host).await {
+            Err(EgressError::Unresolvable(host, error)) => log::info!("Egress check of {} skipped, it doesn't resolve: {}", host, error),
+            result => result.map(|_| ())?,
+        }
+    }
+    Ok(())
+}
+
+/// Executes a request with a restricted client, reporting the egress policy rather than a connection error on rejection.
+pub async fn execute(http_client: &Client, request: Request) -> anyhow::Result<Response> {
+    get_egress_policy().check_url(request.url())?;
+
+    http_client.execute(request).await.map_err(|error| {
+        let mut source = error.source();
+        while let Some(cause) = source {
+            if let Some(egress_error) = cause.downcast_ref::<EgressError>() {
+                return anyhow::anyhow!("{}", egress_error);
+            }
+            source = cause.source();
+        }
+        error.into()
+    })
+}
+
+// Connections only use the checked addresses, so a second lookup can't rebind the name.
+async fn resolve(egress_policy: &EgressPolicy, host: &str) -> Result<Vec<SocketAddr>, EgressError> {
+    egress_policy.check_host(host)?;
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await
+        .map_err(|error| EgressError::Unresolvable(host.to_string(), error.to_string()))?
+        .collect();
+    for address in &addresses {
+        egress_policy.check_address(address.ip())?;
+    }
+    Ok(addresses)
+}
+
+struct EgressResolver(Arc<EgressPolicy>);
+
+impl Resolve for EgressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let egress_policy = self.0.clone();
+        Box::pin(async move {
+            let addresses = resolve(&egress_policy, name.as_str()).await?;
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
@@ -0,0 +1,22 @@
+use std::sync::OnceLock;
+
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+use crate::models::service::Event;
+
+const EVENT_BUS_CAPACITY: usize = 1024;
+
+static EVENT_BUS: OnceLock<Sender<Event>> = OnceLock::new();
+
+fn get_event_bus() -> &'static Sender<Event> {
+    EVENT_BUS.get_or_init(|| broadcast::channel(EVENT_BUS_CAPACITY).0)
+}
+
+pub fn publish_event(event: Event) {
+    // Fails only when nobody is subscribed.
+    let _ = get_event_bus().send(event);
+}
+
+pub fn subscribe_events() -> Receiver<Event> {
+    get_event_bus().subscribe()
+}
\ No newline at end of file
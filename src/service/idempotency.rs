@@ -0,0 +1,73 @@
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::models::service::{IdempotencyClaim, IdempotencyError, IdempotencyKey};
+use crate::repository;
+
+static IDEMPOTENCY_KEY_TTL: OnceLock<Duration> = OnceLock::new();
+static IDEMPOTENCY_KEY_IN_PROGRESS_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+pub fn set_idempotency_key_ttl(ttl: Duration) {
+    if IDEMPOTENCY_KEY_TTL.set(ttl).is_err() {
+        panic!("You can't set Idempotency Key TTL twice!");
+    }
+}
+
+fn get_idempotency_key_ttl() -> Duration {
+    *IDEMPOTENCY_KEY_TTL.get().expect("Should be set!")
+}
+
+pub fn set_idempotency_key_in_progress_timeout(in_progress_timeout: Duration) {
+    if IDEMPOTENCY_KEY_IN_PROGRESS_TIMEOUT.set(in_progress_timeout).is_err() {
+        panic!("You can't set Idempotency Key In Progress Timeout twice!");
+    }
+}
+
+fn get_idempotency_key_in_progress_timeout() -> Duration {
+    *IDEMPOTENCY_KEY_IN_PROGRESS_TIMEOUT.get().expect("Should be set!")
+}
+
+pub async fn claim_idempotency_key(scope: &str, tenant: Option<&str>, key: &str, fingerprint: &str) -> Result<IdempotencyClaim, IdempotencyError> {
+    let idempotency_key_repository = repository::get_idempotency_key_repository();
+    let scope = &IdempotencyKey::tenant_scope(scope, tenant);
+
+    // Kept to the microsecond, as the databases do, so that the claim is recognized when completed or released.
+    let now = Utc::now().trunc_subsecs(6);
+    let idempotency_key = IdempotencyKey::new(scope, key, fingerprint, None, now, now + get_idempotency_key_ttl());
+    // A request still in progress after the timeout has crashed or been cancelled, its claim is taken over.
+    if idempotency_key_repository.insert_idempotency_key(&idempotency_key, now - get_idempotency_key_in_progress_timeout()).await? {
+        log::info!("Idempotency key {} claimed for {}", key, scope);
+        return Ok(IdempotencyClaim::Claimed(now));
+    }
+
+    match idempotency_key_repository.find_idempotency_key(scope, key).await? {
+        Some(existing) if existing.fingerprint() != fingerprint => Err(IdempotencyError::FingerprintMismatch),
+        Some(existing) => match existing.resource_id() {
+            Some(resource_id) => {
+                log::info!("Idempotency key {} already used for {}: {}", key, scope, resource_id);
+                Ok(IdempotencyClaim::Completed(resource_id))
+            },
+            None => Err(IdempotencyError::InProgress),
+        },
+        // Released by the request holding it between the insert and the lookup.
+        None => Err(IdempotencyError::InProgress),
+    }
+}
+
+pub async fn complete_idempotency_key(scope: &str, tenant: Option<&str>, key: &str, claimed_at: DateTime<Utc>, resource_id: &Uuid) {
+    let idempotency_key_repository = repository::get_idempotency_key_repository();
+    let scope = &IdempotencyKey::tenant_scope(scope, tenant);
+    if let Err(error) = idempotency_key_repository.update_idempotency_key_resource_id(scope, key, claimed_at, resource_id).await {
+        log::error!("Failed to complete idempotency key {} for {}: {:?}", key, scope, error);
+    }
+}
+
+pub async fn release_idempotency_key(scope: &str, tenant: Option<&str>, key: &str, claimed_at: DateTime<Utc>) {
+    let idempotency_key_repository = repository::get_idempotency_key_repository();
+    let scope = &IdempotencyKey::tenant_scope(scope, tenant);
+    if let Err(error) = idempotency_key_repository.delete_idempotency_key(scope, key, claimed_at).await {
+        log::error!("Failed to release idempotency key {} for {}: {:?}", key, scope, error);
+    }
+}
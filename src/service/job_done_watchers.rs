@@ -1,17 +1,42 @@
 use std::time::Duration;
 
 use chrono::Utc;
-use futures_util::{stream, StreamExt};
 use futures_util::future::join_all;
-use reqwest::Client;
+use reqwest::StatusCode;
+use tokio::sync::broadcast::error::RecvError;
 use uuid::Uuid;
 
 use crate::{repository, service};
-use crate::models::service::{CreateJobDoneWatcherRequest, JobDoneTriggerWebhook, JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherStatus, JobName};
+use crate::models::service::{CreateJobDoneWatcherError, CreateJobDoneWatcherRequest, DeliveryKind, Event, JobDoneTriggerWebhook, JobDoneTriggerWebhookAttempt, JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherFilter, JobDoneWatcherStatus, JobName, Namespace, Page, PageCursor, PageRequest, RedeliverJobDoneWatcherError, SortOrder, WaitTimeout};
 
-pub async fn create_job_done_watcher(create_job_done_watcher_request: CreateJobDoneWatcherRequest) -> anyhow::Result<JobDoneWatcher> {
+pub async fn create_job_done_watcher(
+    create_job_done_watcher_request: CreateJobDoneWatcherRequest,
+    tenant: Option<&str>
+) -> Result<JobDoneWatcher, CreateJobDoneWatcherError> {
     log::info!("Creating JobDoneWatcher for job: {}", create_job_done_watcher_request.job_name());
 
+    if let Some(tenant) = tenant {
+        // Without a namespace the watcher would match the jobs of every namespace.
+        let namespace = create_job_done_watcher_request.namespace().ok_or(CreateJobDoneWatcherError::MissingNamespace)?;
+        if !service::tenants::can_watch_namespace(Some(tenant), namespace) {
+            log::warn!("Rejecting JobDoneWatcher of tenant {} in namespace {}", tenant, namespace);
+            return Err(CreateJobDoneWatcherError::NamespaceNotAllowed(namespace.clone()));
+        }
+    }
+
+    let webhook_repository = repository::get_webhook_repository();
+    let mut webhooks_not_found = Vec::new();
+    for (index, job_done_trigger_webhook) in create_job_done_watcher_request.job_done_trigger_webhooks().iter().enumerate() {
+        let webhook = webhook_repository.find_webhook_by_id(&job_done_trigger_webhook.webhook_id()).await?;
+        if !webhook.is_some_and(|webhook| service::tenants::is_visible(webhook.tenant(), tenant)) {
+            webhooks_not_found.push((index, job_done_trigger_webhook.webhook_id()));
+        }
+    }
+    if !webhooks_not_found.is_empty() {
+        log::warn!("Rejecting JobDoneWatcher referencing unknown webhooks: {:?}", webhooks_not_found);
+        return Err(CreateJobDoneWatcherError::WebhooksNotFound(webhooks_not_found));
+    }
+
     let job_done_trigger_webhooks: Vec<_> = create_job_done_watcher_request.job_done_trigger_webhooks()
         .iter()
         .map(|job_done_trigger_webhook| JobDoneTriggerWebhook::new(
@@ -25,11 +50,12 @@ pub async fn create_job_done_watcher(create_job_done_watcher_request: CreateJobD
     let job_done_watcher = JobDoneWatcher::new(
         Uuid::new_v4(),
         create_job_done_watcher_request.job_name().clone(),
+        create_job_done_watcher_request.namespace().cloned(),
         create_job_done_watcher_request.timeout_seconds(),
         job_done_trigger_webhooks,
         JobDoneWatcherStatus::Pending,
         Utc::now(),
-    );
+    ).with_tenant(tenant);
 
     if job_done_watcher.timeout_seconds() > 0 {
         start_timer_job_done_watcher(&job_done_watcher.id(), job_done_watcher.timeout_seconds() as u64);
@@ -40,53 +66,124 @@ pub async fn create_job_done_watcher(create_job_done_watcher_request: CreateJobD
     job_done_watcher_repository.create_watcher(&job_done_watcher).await
         .map(|_| {
             log::info!("Successfully created JobDoneWatcher with ID: {}", job_done_watcher.id());
+            service::events::publish_event(Event::JobDoneWatcher(job_done_watcher.clone()));
             job_done_watcher
         })
         .map_err(|error| {
             log::error!("Failed to create JobDoneWatcher: {}", error);
-            anyhow::anyhow!("Failed to create job_done_watcher: {}", error)
+            anyhow::anyhow!("Failed to create job_done_watcher: {}", error).into()
         })
 }
 
 fn start_timer_job_done_watcher(job_done_watcher_id: &Uuid, timeout_secs: u64) {
     log::info!("Starting timeout of {} seconds for JobDoneWatcher with ID: {}", timeout_secs, job_done_watcher_id);
 
-    let job_done_watcher_id = job_done_watcher_id.clone();
+    let job_done_watcher_id = *job_done_watcher_id;
     actix_web::rt::spawn(async move {
         actix_web::rt::time::sleep(Duration::from_secs(timeout_secs)).await;
         log::info!("Timeout reached for JobDoneWatcher ID: {}", job_done_watcher_id);
+        time_out_job_done_watcher(&job_done_watcher_id).await;
+    });
+}
 
-        let job_done_watcher_repository = repository::get_job_done_watcher_repository();
-        let timeout_result = job_done_watcher_repository.update_watcher_status(&job_done_watcher_id, JobDoneWatcherStatus::Timeout).await;
+/// Moves a JobDoneWatcher to Timeout, unless its Job finished first and it already left Pending.
+pub async fn time_out_job_done_watcher(job_done_watcher_id: &Uuid) {
+    let job_done_watcher_repository = repository::get_job_done_watcher_repository();
+    let timeout_result = job_done_watcher_repository.update_watcher_status_by_status(
+        job_done_watcher_id,
+        JobDoneWatcherStatus::Pending,
+        JobDoneWatcherStatus::Timeout
+    ).await;
+    match timeout_result {
+        Ok(true) => {},
+        Ok(false) => {
+            log::info!("JobDoneWatcher {} was no longer pending, Timeout ignored", job_done_watcher_id);
+            return;
+        },
+        Err(error) => {
+            log::error!("Failed to update JobDoneWatcher {} to Timeout: {:#?}", job_done_watcher_id, error);
+            return;
+        },
+    }
 
-        match timeout_result {
-            Ok(_) => log::info!("JobDoneWatcher {} updated to Timeout status", job_done_watcher_id),
-            Err(error) => log::error!("Failed to update JobDoneWatcher {} to Timeout: {:#?}", job_done_watcher_id, error),
-        };
-    });
+    log::info!("JobDoneWatcher {} updated to Timeout status", job_done_watcher_id);
+    match job_done_watcher_repository.find_watcher_by_id(job_done_watcher_id).await {
+        Ok(Some(job_done_watcher)) => service::events::publish_event(Event::JobDoneWatcher(job_done_watcher)),
+        Ok(None) => {},
+        Err(error) => log::error!("Failed to fetch JobDoneWatcher {} after Timeout: {:#?}", job_done_watcher_id, error),
+    };
 }
 
-pub async fn get_job_done_watchers() -> anyhow::Result<Vec<JobDoneWatcher>> {
-    log::info!("Fetching all JobDoneWatchers");
+pub async fn get_job_done_watchers(
+    job_done_watcher_filter: &JobDoneWatcherFilter,
+    page_request: &PageRequest
+) -> anyhow::Result<Page<JobDoneWatcher>> {
+    log::info!("Fetching JobDoneWatchers");
 
     let job_done_watcher_repository = repository::get_job_done_watcher_repository();
-    job_done_watcher_repository.find_all_watchers().await
+    let job_done_watchers = job_done_watcher_repository.find_all_watchers(job_done_watcher_filter, &page_request.with_lookahead()).await?;
+    Ok(Page::from_lookahead(job_done_watchers, page_request, |job_done_watcher| PageCursor::new(job_done_watcher.created_at(), job_done_watcher.id())))
 }
 
-pub async fn get_job_done_watcher_by_id(job_done_watcher_id: &Uuid) -> anyhow::Result<Option<JobDoneWatcher>> {
+pub async fn get_job_done_watcher_by_id(job_done_watcher_id: &Uuid, tenant: Option<&str>) -> anyhow::Result<Option<JobDoneWatcher>> {
     log::info!("Fetching JobDoneWatcher by ID: {}", job_done_watcher_id);
 
     let job_done_watcher_repository = repository::get_job_done_watcher_repository();
-    job_done_watcher_repository.find_watcher_by_id(job_done_watcher_id).await
+    Ok(job_done_watcher_repository.find_watcher_by_id(job_done_watcher_id).await?
+        .filter(|job_done_watcher| service::tenants::is_visible(job_done_watcher.tenant(), tenant)))
 }
 
-pub async fn notify_job_done_watchers(job_name: &JobName) {
-    log::info!("Notifying JobDoneWatchers for job: {}", job_name);
+pub async fn find_latest_job_done_watcher(job_name: &JobName, namespace: Option<&Namespace>, tenant: Option<&str>) -> anyhow::Result<Option<JobDoneWatcher>> {
+    log::info!("Fetching latest JobDoneWatcher for job: {}", job_name);
+
+    let job_done_watcher_filter = JobDoneWatcherFilter::new(Some(job_name.clone()), None, namespace.cloned(), None, None, None)
+        .with_tenant(tenant);
+    let page_request = PageRequest::new(SortOrder::Descending, None, Some(1))?;
+    let job_done_watcher_repository = repository::get_job_done_watcher_repository();
+    Ok(job_done_watcher_repository.find_all_watchers(&job_done_watcher_filter, &page_request).await?.pop())
+}
+
+pub async fn wait_for_job_done_watcher(job_done_watcher_id: &Uuid, wait_timeout: WaitTimeout, tenant: Option<&str>) -> anyhow::Result<Option<JobDoneWatcher>> {
+    log::info!("Waiting up to {:?} for JobDoneWatcher {} to finish", wait_timeout.duration(), job_done_watcher_id);
+
+    // Subscribe before reading the current state, so no transition in between is missed.
+    let mut receiver = service::events::subscribe_events();
+    match get_job_done_watcher_by_id(job_done_watcher_id, tenant).await? {
+        Some(job_done_watcher) if !job_done_watcher.status().is_finished() => {},
+        job_done_watcher => return Ok(job_done_watcher),
+    }
+
+    let finished_job_done_watcher = actix_web::rt::time::timeout(wait_timeout.duration(), async {
+        loop {
+            match receiver.recv().await {
+                Ok(Event::JobDoneWatcher(job_done_watcher))
+                    if job_done_watcher.id() == *job_done_watcher_id && job_done_watcher.status().is_finished() => return Some(job_done_watcher),
+                Ok(_) => continue,
+                // Some events were missed, the current state tells whether ours was one of them.
+                Err(RecvError::Lagged(_)) => match get_job_done_watcher_by_id(job_done_watcher_id, tenant).await {
+                    Ok(Some(job_done_watcher)) if job_done_watcher.status().is_finished() => return Some(job_done_watcher),
+                    _ => continue,
+                },
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }).await;
+
+    match finished_job_done_watcher {
+        Ok(Some(job_done_watcher)) => Ok(Some(job_done_watcher)),
+        // Timed out: the watcher may have been finished by another replica.
+        _ => get_job_done_watcher_by_id(job_done_watcher_id, tenant).await,
+    }
+}
+
+pub async fn notify_job_done_watchers(job_name: &JobName, namespace: &Namespace) {
+    log::info!("Notifying JobDoneWatchers for job: {} (namespace: {})", job_name, namespace);
 
     let job_done_watcher_repository = repository::get_job_done_watcher_repository();
     let job_done_watchers =
         match job_done_watcher_repository.update_watchers_status_by_job_name_and_status(
             job_name,
+            namespace,
             JobDoneWatcherStatus::Pending,
             JobDoneWatcherStatus::Processing
         ).await {
@@ -99,56 +196,143 @@ pub async fn notify_job_done_watchers(job_name: &JobName) {
 
     log::info!("Updated status for {} JobDoneWatchers for job: {}", job_done_watchers.len(), job_name);
 
-    stream::iter(job_done_watchers.into_iter())
-        .map(|job_done_watcher| call_job_done_trigger_webhooks(job_done_watcher))
-        .buffer_unordered(10)
-        .collect::<Vec<anyhow::Result<JobDoneWatcher>>>()
-        .await
-        .iter()
-        .for_each(|result| {
-            match result {
+    let delivery_pool = service::delivery_pool::get_delivery_pool();
+    for job_done_watcher in job_done_watchers {
+        service::events::publish_event(Event::JobDoneWatcher(job_done_watcher.clone()));
+        let job_done_trigger_webhook_ids = job_done_watcher.job_done_trigger_webhooks().iter().map(JobDoneTriggerWebhook::id).collect();
+        delivery_pool.submit(async move {
+            match call_job_done_trigger_webhooks(job_done_watcher, job_done_trigger_webhook_ids).await {
                 Ok(job_done_watcher) => log::info!("JobDoneWatcher {} successfully notified!", job_done_watcher.id()),
                 Err(error) => log::error!("Failed to notify JobDoneWatcher: {:#?}", error),
             }
         });
+    }
+}
+
+pub async fn redeliver_job_done_trigger_webhooks(
+    job_done_watcher_id: &Uuid,
+    job_done_trigger_webhook_ids: Option<Vec<Uuid>>,
+    tenant: Option<&str>
+) -> Result<JobDoneWatcher, RedeliverJobDoneWatcherError> {
+    log::info!("Redelivering JobDoneWatcher {}", job_done_watcher_id);
+
+    let job_done_watcher_repository = repository::get_job_done_watcher_repository();
+    let mut job_done_watcher = job_done_watcher_repository.find_watcher_by_id(job_done_watcher_id).await?
+        .filter(|job_done_watcher| service::tenants::is_visible(job_done_watcher.tenant(), tenant))
+        .ok_or(RedeliverJobDoneWatcherError::NotFound)?;
+    let status = job_done_watcher.status();
+    if !status.is_redeliverable() {
+        return Err(RedeliverJobDoneWatcherError::InvalidStatus(status));
+    }
+
+    let job_done_trigger_webhook_ids = match job_done_trigger_webhook_ids {
+        Some(job_done_trigger_webhook_ids) => {
+            let job_done_trigger_webhooks = job_done_watcher.job_done_trigger_webhooks();
+            let not_found: Vec<Uuid> = job_done_trigger_webhook_ids.iter()
+                .filter(|id| !job_done_trigger_webhooks.iter().any(|job_done_trigger_webhook| job_done_trigger_webhook.id() == **id))
+                .copied()
+                .collect();
+            if !not_found.is_empty() {
+                return Err(RedeliverJobDoneWatcherError::TriggersNotFound(not_found));
+            }
+            let not_called: Vec<Uuid> = job_done_trigger_webhooks.iter()
+                .filter(|job_done_trigger_webhook| job_done_trigger_webhook_ids.contains(&job_done_trigger_webhook.id()))
+                .filter(|job_done_trigger_webhook| !job_done_trigger_webhook.status().is_redeliverable())
+                .map(JobDoneTriggerWebhook::id)
+                .collect();
+            if !not_called.is_empty() {
+                return Err(RedeliverJobDoneWatcherError::TriggersNotCalled(not_called));
+            }
+            job_done_trigger_webhook_ids
+        },
+        None => job_done_watcher.job_done_trigger_webhooks().iter()
+            .filter(|job_done_trigger_webhook| *job_done_trigger_webhook.status() == JobDoneTriggerWebhookStatus::Failed)
+            .map(JobDoneTriggerWebhook::id)
+            .collect(),
+    };
+    if job_done_trigger_webhook_ids.is_empty() {
+        return Err(RedeliverJobDoneWatcherError::NothingToRedeliver);
+    }
+
+    if !job_done_watcher_repository.update_watcher_status_by_status(job_done_watcher_id, status, JobDoneWatcherStatus::Processing).await? {
+        return Err(RedeliverJobDoneWatcherError::Conflict);
+    }
+    job_done_watcher.set_status(JobDoneWatcherStatus::Processing);
+    log::info!("JobDoneWatcher {} back to Processing, redelivering {} webhooks", job_done_watcher_id, job_done_trigger_webhook_ids.len());
+    service::events::publish_event(Event::JobDoneWatcher(job_done_watcher.clone()));
+
+    let redelivered_job_done_watcher = job_done_watcher.clone();
+    service::delivery_pool::get_delivery_pool().submit(async move {
+        match call_job_done_trigger_webhooks(redelivered_job_done_watcher, job_done_trigger_webhook_ids).await {
+            Ok(job_done_watcher) => log::info!("JobDoneWatcher {} successfully redelivered!", job_done_watcher.id()),
+            Err(error) => log::error!("Failed to redeliver JobDoneWatcher: {:#?}", error),
+        }
+    });
+    Ok(job_done_watcher)
 }
 
-async fn call_job_done_trigger_webhooks(mut job_done_watcher: JobDoneWatcher) -> anyhow::Result<JobDoneWatcher> {
+pub async fn get_job_done_trigger_webhook_attempts(job_done_watcher_id: &Uuid, tenant: Option<&str>) -> anyhow::Result<Option<Vec<JobDoneTriggerWebhookAttempt>>> {
+    log::info!("Fetching attempts of JobDoneWatcher {}", job_done_watcher_id);
+
+    if get_job_done_watcher_by_id(job_done_watcher_id, tenant).await?.is_none() {
+        return Ok(None);
+    }
+    let job_done_watcher_repository = repository::get_job_done_watcher_repository();
+    Ok(Some(job_done_watcher_repository.find_job_done_trigger_webhook_attempts(job_done_watcher_id).await?))
+}
+
+async fn call_job_done_trigger_webhooks(mut job_done_watcher: JobDoneWatcher, job_done_trigger_webhook_ids: Vec<Uuid>) -> anyhow::Result<JobDoneWatcher> {
     log::info!("Calling webhooks for JobDoneWatcher {}", job_done_watcher.id());
 
     let job_done_watcher_id = job_done_watcher.id();
     let job_done_trigger_webhooks = job_done_watcher.job_done_trigger_webhooks_mut();
-    let total_webhooks = job_done_trigger_webhooks.len();
 
     let call_webhook_tasks: Vec<_> = job_done_trigger_webhooks
         .iter_mut()
+        .filter(|webhook| job_done_trigger_webhook_ids.contains(&webhook.id()))
         .map(|webhook| async {
-            let result = call_job_done_trigger_webhook(webhook).await;
-            let job_done_watcher_repository = repository::get_job_done_watcher_repository();
-            job_done_watcher_repository.update_job_done_trigger_webhook_status_and_called_at(
-                &job_done_watcher_id,
-                &webhook.id(),
-                webhook.status().clone(),
-                webhook.called_at().expect("Should be not empty"),
-            ).await?;
-            result
-        })
-        .collect();
+            let delivery_pool = service::delivery_pool::get_delivery_pool();
+            let mut attempt_number = 1;
+            loop {
+                let attempt = call_job_done_trigger_webhook(&job_done_watcher_id, webhook).await?;
+                let job_done_watcher_repository = repository::get_job_done_watcher_repository();
+                job_done_watcher_repository.update_job_done_trigger_webhook_status_and_called_at(
+                    &job_done_watcher_id,
+                    &webhook.id(),
+                    *webhook.status(),
+                    webhook.called_at().expect("Should be not empty"),
+                ).await?;
+                job_done_watcher_repository.insert_job_done_trigger_webhook_attempt(&attempt).await?;
 
-    let call_webhook_results: Vec<_> = join_all(call_webhook_tasks).await;
-
-    let webhooks_sent_successfully: Vec<_> = call_webhook_results
-        .into_iter()
-        .filter(|result| match result {
-            Ok(sent) => *sent,
-            Err(error) => {
-                log::warn!("Webhook failed: {:#?}", error);
-                false
+                // Without a request, the webhook is missing or can't be rendered, and a receiver rejecting the request
+                // with a 4xx other than 429 would reject it again: retrying won't help.
+                let retryable = attempt.status() == JobDoneTriggerWebhookStatus::Failed
+                    && attempt.request().is_some()
+                    && attempt.response_status_code().is_none_or(is_retryable_status_code);
+                if !retryable || attempt_number >= delivery_pool.max_attempts() {
+                    return Ok::<_, anyhow::Error>(attempt);
+                }
+                let retry_backoff = delivery_pool.retry_backoff_after(attempt_number);
+                log::info!("Retrying job done trigger webhook {} in {:?}", webhook.id(), retry_backoff);
+                delivery_pool.release_slot_while(actix_web::rt::time::sleep(retry_backoff)).await;
+                attempt_number += 1;
             }
         })
         .collect();
 
-    let total_webhooks_sent_successfully = webhooks_sent_successfully.len();
+    for call_webhook_result in join_all(call_webhook_tasks).await {
+        match call_webhook_result {
+            Ok(attempt) if attempt.status() == JobDoneTriggerWebhookStatus::Failed => service::dead_letters::record_dead_letter(&job_done_watcher, &attempt).await,
+            Ok(attempt) => service::dead_letters::clear_dead_letter(&attempt.job_done_trigger_webhook_id()).await,
+            Err(error) => log::warn!("Webhook failed: {:#?}", error),
+        }
+    }
+
+    // Redelivered or not, every trigger counts toward the status of the watcher.
+    let total_webhooks = job_done_watcher.job_done_trigger_webhooks().len();
+    let total_webhooks_sent_successfully = job_done_watcher.job_done_trigger_webhooks().iter()
+        .filter(|webhook| *webhook.status() == JobDoneTriggerWebhookStatus::Called)
+        .count();
     let total_webhooks_failed = total_webhooks - total_webhooks_sent_successfully;
     let job_done_watcher_status = evaluate_job_done_watcher_status(
         total_webhooks,
@@ -160,40 +344,82 @@ async fn call_job_done_trigger_webhooks(mut job_done_watcher: JobDoneWatcher) ->
     let job_done_watcher_repository = repository::get_job_done_watcher_repository();
     job_done_watcher_repository.update_watcher_status(&job_done_watcher.id(), job_done_watcher_status).await?;
     log::info!("JobDoneWatcher {} status updated to {:?}", job_done_watcher.id(), job_done_watcher_status);
+    service::events::publish_event(Event::JobDoneWatcher(job_done_watcher.clone()));
     Ok(job_done_watcher)
 }
 
-async fn call_job_done_trigger_webhook(job_done_trigger_webhook: &mut JobDoneTriggerWebhook) -> anyhow::Result<bool> {
+async fn call_job_done_trigger_webhook(job_done_watcher_id: &Uuid, job_done_trigger_webhook: &mut JobDoneTriggerWebhook) -> anyhow::Result<JobDoneTriggerWebhookAttempt> {
     let webhook_id = job_done_trigger_webhook.webhook_id();
     log::info!("Calling webhook with ID: {}", webhook_id);
 
-    match service::webhooks::get_webhook_by_id(&webhook_id).await? {
+    let attempted_at = Utc::now();
+    let webhook = service::webhooks::get_webhook_by_id(&webhook_id, None).await?;
+    // A redelivery keeps the time of the first call.
+    if job_done_trigger_webhook.called_at().is_none() {
+        job_done_trigger_webhook.set_called_at(attempted_at);
+    } else {
+        service::metrics::record_delivery_retry(DeliveryKind::JobDoneTrigger);
+    }
+
+    let mut request = None;
+    let mut response_headers = None;
+    let mut response_body = None;
+    let (response_status_code, error) = match webhook {
         Some(webhook) => {
-            job_done_trigger_webhook.set_called_at(Utc::now());
-            let http_client = Client::new();
-            match http_client
-                .post(webhook.url().to_string())
-                .body(webhook.request_body().to_string())
-                .send().await
-            {
-                Ok(_) => {
-                    job_done_trigger_webhook.set_status(JobDoneTriggerWebhookStatus::Called);
-                    log::info!("Successfully called webhook with ID: {}", webhook_id);
-                    Ok(true)
+            let request_timeout = service::delivery_pool::get_delivery_pool().request_timeout_for(job_done_trigger_webhook.timeout_seconds());
+            let response = match service::webhooks::build_webhook_request(&webhook, request_timeout) {
+                Ok(webhook_request) => {
+                    request = Some(service::webhooks::to_rendered_webhook_request(&webhook_request));
+                    service::webhooks::send_webhook_request(&webhook, webhook_request, DeliveryKind::JobDoneTrigger).await
+                },
+                Err(error) => Err(error),
+            };
+            match response {
+                Ok(response) => {
+                    let response_status = response.status();
+                    response_headers = Some(service::webhooks::to_header_pairs(response.headers()));
+                    response_body = Some(service::webhooks::read_response_body(response).await);
+                    if response_status.is_success() {
+                        job_done_trigger_webhook.set_status(JobDoneTriggerWebhookStatus::Called);
+                        log::info!("Successfully called webhook with ID: {}", webhook_id);
+                        (Some(response_status.as_u16()), None)
+                    } else {
+                        job_done_trigger_webhook.set_status(JobDoneTriggerWebhookStatus::Failed);
+                        log::error!("Webhook with ID {} answered with status {}", webhook_id, response_status);
+                        (Some(response_status.as_u16()), Some(format!("Webhook answered with status {}", response_status)))
+                    }
                 },
                 Err(err) => {
                     job_done_trigger_webhook.set_status(JobDoneTriggerWebhookStatus::Failed);
                     log::error!("Failed to call webhook with ID {}: {}", webhook_id, err);
-                    Ok(false)
+                    (None, Some(err.to_string()))
                 }
             }
         },
         None => {
             log::warn!("Webhook with ID {} doesn't exist", webhook_id);
             job_done_trigger_webhook.set_status(JobDoneTriggerWebhookStatus::Failed);
-            Ok(false)
+            (None, Some(format!("Webhook {} doesn't exist", webhook_id)))
         }
-    }
+    };
+    let delivered = *job_done_trigger_webhook.status() == JobDoneTriggerWebhookStatus::Called;
+    service::metrics::record_delivery(DeliveryKind::JobDoneTrigger, &webhook_id, delivered, response_status_code);
+
+    Ok(JobDoneTriggerWebhookAttempt::new(
+        Uuid::new_v4(),
+        *job_done_watcher_id,
+        job_done_trigger_webhook.id(),
+        *job_done_trigger_webhook.status(),
+        response_status_code,
+        error,
+        attempted_at,
+    ).with_request(request)
+     .with_response(response_headers, response_body))
+}
+
+fn is_retryable_status_code(status_code: u16) -> bool {
+    StatusCode::from_u16(status_code)
+        .is_ok_and(|status_code| status_code.is_server_error() || status_code == StatusCode::TOO_MANY_REQUESTS)
 }
 
 fn evaluate_job_done_watcher_status(total_webhooks: usize, success_count: usize, failure_count: usize) -> JobDoneWatcherStatus {
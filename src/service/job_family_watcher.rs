@@ -1,20 +1,40 @@
+use std::time::{Duration as StdDuration, Instant};
+
+use chrono::{Duration, Utc};
 use futures_util::{stream, StreamExt};
-use reqwest::Client;
+use reqwest::StatusCode;
+use uuid::Uuid;
+
+use crate::models::service::{DeliveryKind, Event, HttpUrl, JobFamilyDelivery, JobFamilyDeliveryFilter, JobFamilyDeliveryStatus, JobFamilyState, JobFamilyWatcher, JobName, JobOutcome, Namespace};
+use crate::{repository, service};
 
-use crate::models::service::{HttpUrl, JobFamilyWatcher};
-use crate::repository;
+const MAX_JOB_FAMILY_STATE_ATTEMPTS: usize = 10;
 
+pub async fn save_job_family_watcher(job_family_watcher: JobFamilyWatcher) -> anyhow::Result<()> {
+    log::info!("Saving job family watcher {} (job family {})", job_family_watcher.name(), job_family_watcher.job_family());
 
-pub async fn create_job_family_watcher(job_family_watcher: JobFamilyWatcher) -> anyhow::Result<()> {
-    log::info!("Creating job family watcher (job family {})", job_family_watcher.job_family());
+    // Its deliveries are rejected anyway, the watcher is kept so that fixing the policy is enough.
+    if let Err(error) = service::egress::check_url(job_family_watcher.url()).await {
+        log::warn!("Job family watcher {} won't be able to deliver: {}", job_family_watcher.id(), error);
+    }
 
     let job_family_watcher_repository = repository::get_job_family_watcher_repository();
-    job_family_watcher_repository.create_job_family_watcher(job_family_watcher).await?;
+    job_family_watcher_repository.save_job_family_watcher(job_family_watcher).await?;
     Ok(())
 }
 
-pub async fn notify_job_family_watchers(job_family: &str) {
-    log::info!("Notifying job family watchers for job family: {}", job_family);
+pub async fn notify_job_family_watchers(
+    job_family: &str,
+    job_name: &JobName,
+    namespace: &Namespace,
+    job_outcome: JobOutcome,
+    job_duration: Option<Duration>
+) {
+    log::info!("Notifying job family watchers for job family: {} (job: {}, outcome: {})", job_family, job_name, job_outcome);
+
+    let Some((previous_job_family_state, job_family_state)) = advance_job_family_state(job_family, job_outcome).await else {
+        return;
+    };
 
     let job_family_watcher_repository = repository::get_job_family_watcher_repository();
     let job_family_watchers =
@@ -28,31 +48,182 @@ pub async fn notify_job_family_watchers(job_family: &str) {
 
     log::info!("Found {} job family watchers for job family: {}", job_family_watchers.len(), job_family);
 
-    stream::iter(job_family_watchers.into_iter())
+    let job_family_watchers: Vec<_> = job_family_watchers
+        .into_iter()
+        .filter(|job_family_watcher| {
+            if !service::tenants::can_watch_namespace(job_family_watcher.tenant(), namespace) {
+                log::info!("Job family watcher {} can't watch namespace {}, skipping.", job_family_watcher.id(), namespace);
+                return false;
+            }
+
+            let should_notify = should_notify_job_family_watcher(
+                job_family_watcher,
+                job_outcome,
+                job_duration,
+                previous_job_family_state.as_ref(),
+                &job_family_state,
+            );
+
+            if !should_notify {
+                log::info!("Conditions of job family watcher {} not met, skipping.", job_family_watcher.id());
+            }
+
+            should_notify
+        })
+        .collect();
+
+    stream::iter(job_family_watchers)
         .for_each(|job_family_watcher| async move {
-            call_webhook(
-                job_family_watcher.url(),
-                job_family_watcher.request_body(),
-                job_family
-            ).await;
+            submit_job_family_delivery(job_family_watcher, job_name, job_outcome).await;
         }).await;
 }
 
-async fn call_webhook(url: &HttpUrl, request_body: &str, job_family: &str) {
+pub async fn get_job_family_deliveries(job_family_delivery_filter: &JobFamilyDeliveryFilter) -> anyhow::Result<Vec<JobFamilyDelivery>> {
+    log::info!("Fetching job family deliveries (filter: {:?})", job_family_delivery_filter);
+
+    let job_family_watcher_repository = repository::get_job_family_watcher_repository();
+    job_family_watcher_repository.find_all_job_family_deliveries(job_family_delivery_filter).await
+}
+
+/// Moves the state of a job family past `job_outcome`, starting over whenever another replica got there first.
+async fn advance_job_family_state(job_family: &str, job_outcome: JobOutcome) -> Option<(Option<JobFamilyState>, JobFamilyState)> {
+    let job_family_watcher_repository = repository::get_job_family_watcher_repository();
+    for _ in 0..MAX_JOB_FAMILY_STATE_ATTEMPTS {
+        let previous_job_family_state =
+            match job_family_watcher_repository.find_job_family_state(job_family).await {
+                Ok(job_family_state) => job_family_state,
+                Err(err) => {
+                    log::error!("Failed to retrieve state of job family '{}': {:?}", job_family, err);
+                    return None;
+                }
+            };
+
+        let job_family_state = next_job_family_state(job_family, previous_job_family_state.as_ref(), job_outcome);
+        match job_family_watcher_repository.replace_job_family_state(previous_job_family_state.as_ref(), &job_family_state).await {
+            Ok(true) => return Some((previous_job_family_state, job_family_state)),
+            Ok(false) => log::info!("State of job family '{}' changed concurrently, retrying.", job_family),
+            Err(err) => {
+                log::error!("Failed to save state of job family '{}': {:?}", job_family, err);
+                return None;
+            }
+        }
+    }
+
+    log::error!("Gave up saving state of job family '{}' after {} attempts", job_family, MAX_JOB_FAMILY_STATE_ATTEMPTS);
+    None
+}
+
+fn next_job_family_state(job_family: &str, previous_job_family_state: Option<&JobFamilyState>, job_outcome: JobOutcome) -> JobFamilyState {
+    let consecutive_failures = match job_outcome {
+        JobOutcome::Succeeded => 0,
+        JobOutcome::Failed => previous_job_family_state.map_or(0, JobFamilyState::consecutive_failures) + 1,
+    };
+
+    JobFamilyState::new(job_family, job_outcome, consecutive_failures, Utc::now())
+}
+
+fn should_notify_job_family_watcher(
+    job_family_watcher: &JobFamilyWatcher,
+    job_outcome: JobOutcome,
+    job_duration: Option<Duration>,
+    previous_job_family_state: Option<&JobFamilyState>,
+    job_family_state: &JobFamilyState,
+) -> bool {
+    if !job_family_watcher.on().contains(&job_outcome) {
+        return false;
+    }
+
+    let conditions = job_family_watcher.conditions();
+    if let Some(min_duration_seconds) = conditions.min_duration_seconds() {
+        match job_duration {
+            Some(job_duration) if job_duration.num_seconds() >= min_duration_seconds as i64 => {},
+            _ => return false,
+        }
+    }
+
+    let failure_threshold = conditions.consecutive_failures().unwrap_or(1).max(1);
+    match job_outcome {
+        JobOutcome::Failed if conditions.on_state_change() => job_family_state.consecutive_failures() == failure_threshold,
+        JobOutcome::Failed => job_family_state.consecutive_failures() >= failure_threshold,
+        JobOutcome::Succeeded if conditions.on_state_change() => previous_job_family_state
+            .is_some_and(|previous_job_family_state| previous_job_family_state.consecutive_failures() >= failure_threshold),
+        JobOutcome::Succeeded => true,
+    }
+}
+
+async fn submit_job_family_delivery(job_family_watcher: JobFamilyWatcher, job_name: &JobName, job_outcome: JobOutcome) {
+    let job_family_watcher_repository = repository::get_job_family_watcher_repository();
+    let job_family_delivery = JobFamilyDelivery::new(
+        Uuid::new_v4(),
+        job_family_watcher.id(),
+        job_family_watcher.job_family(),
+        job_name.clone(),
+        job_outcome,
+        Utc::now(),
+    ).with_tenant(job_family_watcher.tenant());
+
+    if let Err(err) = job_family_watcher_repository.create_job_family_delivery(&job_family_delivery).await {
+        log::error!("Failed to record delivery for job family watcher {}: {:?}", job_family_watcher.id(), err);
+    }
+    service::events::publish_event(Event::JobFamilyDelivery(job_family_delivery.clone()));
+
+    let delivery_pool = service::delivery_pool::get_delivery_pool();
+    let request_timeout = delivery_pool.request_timeout();
+    delivery_pool.submit(deliver_job_family_webhook(job_family_watcher, job_family_delivery, request_timeout));
+}
+
+async fn deliver_job_family_webhook(job_family_watcher: JobFamilyWatcher, mut job_family_delivery: JobFamilyDelivery, request_timeout: StdDuration) {
+    job_family_delivery.set_attempts(job_family_delivery.attempts() + 1);
+    job_family_delivery.set_last_attempt_at(Utc::now());
+    if job_family_delivery.attempts() > 1 {
+        service::metrics::record_delivery_retry(DeliveryKind::JobFamily);
+    }
+    match call_webhook(job_family_watcher.url(), job_family_watcher.request_body(), job_family_watcher.job_family(), request_timeout).await {
+        Ok(response_status_code) => {
+            job_family_delivery.set_response_status_code(Some(response_status_code.as_u16()));
+            job_family_delivery.set_status(if response_status_code.is_success() {
+                JobFamilyDeliveryStatus::Delivered
+            } else {
+                JobFamilyDeliveryStatus::Failed
+            });
+        },
+        Err(err) => {
+            job_family_delivery.set_error(Some(err.to_string()));
+            job_family_delivery.set_status(JobFamilyDeliveryStatus::Failed);
+        }
+    }
+    let delivered = job_family_delivery.status() == JobFamilyDeliveryStatus::Delivered;
+    service::metrics::record_delivery(DeliveryKind::JobFamily, &job_family_watcher.id(), delivered, job_family_delivery.response_status_code());
+
+    let job_family_watcher_repository = repository::get_job_family_watcher_repository();
+    if let Err(err) = job_family_watcher_repository.update_job_family_delivery(&job_family_delivery).await {
+        log::error!("Failed to update delivery {} for job family watcher {}: {:?}", job_family_delivery.id(), job_family_watcher.id(), err);
+    }
+    service::events::publish_event(Event::JobFamilyDelivery(job_family_delivery));
+}
+
+async fn call_webhook(url: &HttpUrl, request_body: &str, job_family: &str, request_timeout: StdDuration) -> anyhow::Result<StatusCode> {
     log::info!("Calling webhook for job family '{}' at URL: {}", job_family, url);
 
-    let http_client = Client::new();
-    match http_client
+    let delivery_client = service::delivery_client::get_delivery_client();
+    let request = delivery_client.http_client(None)?
         .post(url.to_string())
         .body(request_body.to_string())
-        .send().await
-    {
+        .timeout(request_timeout)
+        .build()?;
+    let destination_permit = service::destinations::acquire(url, None).await;
+    let started_at = Instant::now();
+    let response = delivery_client.execute(request, None).await;
+    service::metrics::observe_delivery_duration(DeliveryKind::JobFamily, started_at.elapsed());
+    destination_permit.record(&response);
+    match response {
         Ok(response) => {
             log::info!("Successfully called webhook at {} with status: {}", url, response.status());
-            log::info!("Successfully called webhook");
+            Ok(response.status())
         },
         Err(err) => {
             log::warn!("Failed to call webhook for job family '{}': {}, URL: {}", job_family, err, url);
+            Err(err)
         }
     }
 }
\ No newline at end of file
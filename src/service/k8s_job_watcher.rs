@@ -1,17 +1,21 @@
 use std::collections::BTreeMap;
 
-use futures_util::{pin_mut, TryStreamExt};
+use chrono::Duration;
+use futures_util::{pin_mut, StreamExt};
 use k8s_openapi::api::batch::v1::{Job, JobStatus};
 use k8s_openapi::serde_json::json;
 use kube::{Api, Client, ResourceExt};
 use kube::api::{Patch, PatchParams};
 use kube::runtime::{watcher, WatchStreamExt};
 use kube::runtime::reflector::Lookup;
-use crate::models::service::JobName;
+use crate::models::service::{JobName, JobOutcome, Namespace};
 
 use crate::service;
 
-const K8S_WEBHOOKS_CALLED_LABEL: &'static str = "app.k8s.job.webhooks/webhooks-called";
+const K8S_WEBHOOKS_CALLED_LABEL: &str = "app.k8s.job.webhooks/webhooks-called";
+const JOB_CONDITION_STATUS_TRUE: &str = "True";
+const JOB_CONDITION_TYPE_COMPLETE: &str = "Complete";
+const JOB_CONDITION_TYPE_FAILED: &str = "Failed";
 
 pub fn spawn_k8s_job_watcher() {
     actix_web::rt::spawn(watch_jobs());
@@ -21,12 +25,23 @@ pub async fn watch_jobs() {
     log::info!("Starting K8S watch jobs...");
 
     let client = Client::try_default().await.unwrap();
-    let jobs: Api<Job> = Api::default_namespaced(client);
-    let stream = watcher(jobs.clone(), watcher::Config::default()).default_backoff().applied_objects();
+    // Watchers, and the tenants owning them, may watch Jobs of any namespace.
+    let jobs: Api<Job> = Api::all(client.clone());
+    let stream = watcher(jobs, watcher::Config::default()).default_backoff().applied_objects();
     pin_mut!(stream);
 
     log::info!("K8S job watcher initialized successfully.");
-    while let Some(job) = stream.try_next().await.unwrap() {
+    while let Some(job) = stream.next().await {
+        let job = match job {
+            Ok(job) => job,
+            Err(err) => {
+                // The watcher lists the jobs again once its backoff has elapsed.
+                log::warn!("K8S job watch stream failed, restarting: {:?}", err);
+                service::metrics::record_watch_restart();
+                continue;
+            }
+        };
+        service::metrics::record_kubernetes_event();
         log::debug!("Received job update: {:?}", job.name());
 
         if is_already_scanned_job(job.labels()) {
@@ -34,22 +49,10 @@ pub async fn watch_jobs() {
             continue;
         }
 
-        if let Some((job_name, job_status)) = job.name().zip(job.clone().status) {
-            let job_name = JobName::new(job_name.as_ref()).expect("Creating JobName from job name k8s");
-            log::debug!("Processing job: {}", job_name);
-
-            if !is_successfully_completed_job(job_status) {
-                log::info!("Job {} not successfully completed, skipping.", job_name);
-                continue;
-            }
-
-            log::info!("Job {} successfully completed, notifying watchers...", job_name);
-            service::job_done_watchers::notify_job_done_watchers(&job_name).await;
-
-            notify_job_family_watchers(&job).await;
-
+        if let Some((job_name, namespace)) = process_job(&job).await {
             log::info!("Adding label to indicate webhooks have been called for job: {}", job_name);
 
+            let jobs: Api<Job> = Api::namespaced(client.clone(), namespace.as_str());
             if let Err(err) = add_webhooks_called_label(&jobs, &job_name).await {
                 log::warn!("Failed to add webhooks-called label to job {}: {:?}", job_name, err);
             } else {
@@ -59,33 +62,65 @@ pub async fn watch_jobs() {
     }
 }
 
+/// Notifies the watchers of a finished Job, returning its name and namespace; a Job not finished yet is skipped.
+pub async fn process_job(job: &Job) -> Option<(JobName, Namespace)> {
+    let (job_name, job_status) = job.name().zip(job.status.as_ref())?;
+    let job_name = JobName::new(job_name.as_ref()).expect("Creating JobName from job name k8s");
+    let namespace = Namespace::new(&ResourceExt::namespace(job).unwrap_or_default()).expect("Creating Namespace from job namespace k8s");
+    log::debug!("Processing job: {} (namespace: {})", job_name, namespace);
+
+    let Some(job_outcome) = job_outcome(job_status) else {
+        log::info!("Job {} not finished yet, skipping.", job_name);
+        return None;
+    };
+    service::metrics::record_job_finished(job_outcome);
+
+    if job_outcome == JobOutcome::Succeeded {
+        log::info!("Job {} successfully completed, notifying watchers...", job_name);
+        service::job_done_watchers::notify_job_done_watchers(&job_name, &namespace).await;
+    } else {
+        log::info!("Job {} failed.", job_name);
+    }
+
+    notify_job_family_watchers(job, &job_name, &namespace, job_outcome, job_duration(job_status)).await;
+    Some((job_name, namespace))
+}
+
 fn is_already_scanned_job(job_labels: &BTreeMap<String, String>) -> bool {
     job_labels
         .get(K8S_WEBHOOKS_CALLED_LABEL)
-        .map_or(false, |scanned_label| scanned_label == "true")
+        .is_some_and(|scanned_label| scanned_label == "true")
 }
 
-fn is_successfully_completed_job(job_status: JobStatus) -> bool {
-    let conditions = job_status.conditions;
-
-    const JOB_CONDITION_STATUS_TRUE: &'static str = "True";
-    const JOB_CONDITION_TYPE_COMPLETE: &'static str = "Complete";
-    conditions
-        .and_then(|job_conditions| job_conditions.last().cloned())
-        .map(|last_job_condition|
-            last_job_condition.status == JOB_CONDITION_STATUS_TRUE &&
-                last_job_condition.type_  == JOB_CONDITION_TYPE_COMPLETE)
-        .unwrap_or(false)
+fn job_outcome(job_status: &JobStatus) -> Option<JobOutcome> {
+    job_status.conditions
+        .as_ref()
+        .and_then(|job_conditions| job_conditions.last())
+        .filter(|last_job_condition| last_job_condition.status == JOB_CONDITION_STATUS_TRUE)
+        .and_then(|last_job_condition| match last_job_condition.type_.as_str() {
+            JOB_CONDITION_TYPE_COMPLETE => Some(JobOutcome::Succeeded),
+            JOB_CONDITION_TYPE_FAILED => Some(JobOutcome::Failed),
+            _ => None,
+        })
 }
 
-async fn notify_job_family_watchers(job: &Job) {
-    let job_name = job.name().expect("Should be present!");
+fn job_duration(job_status: &JobStatus) -> Option<Duration> {
+    let start_time = job_status.start_time.as_ref()?;
+    let end_time = job_status.completion_time.as_ref()
+        .or_else(|| job_status.conditions
+            .as_ref()
+            .and_then(|job_conditions| job_conditions.last())
+            .and_then(|last_job_condition| last_job_condition.last_transition_time.as_ref()))?;
+
+    Some(end_time.0 - start_time.0)
+}
 
-    if let Some(job_owner_reference) = job.owner_references().get(0) {
+async fn notify_job_family_watchers(job: &Job, job_name: &JobName, namespace: &Namespace, job_outcome: JobOutcome, job_duration: Option<Duration>) {
+    if let Some(job_owner_reference) = job.owner_references().first() {
         if job_owner_reference.kind == "CronJob" {
             let cronjob = job_owner_reference.name.clone();
-            log::info!("Job '{}' belongs to a CronJob ({}), successfully completed. Notifying job family watchers...", job_name, cronjob);
-            service::job_family_watcher::notify_job_family_watchers(&cronjob).await;
+            log::info!("Job '{}' belongs to a CronJob ({}), {}. Notifying job family watchers...", job_name, cronjob, job_outcome);
+            service::job_family_watcher::notify_job_family_watchers(&cronjob, job_name, namespace, job_outcome, job_duration).await;
         }
     }
 }
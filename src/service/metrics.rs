@@ -0,0 +1,103 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder};
+use uuid::Uuid;
+
+use crate::models::service::{DeliveryKind, JobOutcome};
+use crate::repository;
+
+pub const CONTENT_TYPE: &str = prometheus::TEXT_FORMAT;
+const NAMESPACE: &str = "k8s_job_webhooks";
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+struct Metrics {
+    registry: Registry,
+    kubernetes_events: IntCounter,
+    jobs_finished: IntCounterVec,
+    watch_restarts: IntCounter,
+    job_done_watchers: IntGaugeVec,
+    deliveries: IntCounterVec,
+    delivery_duration: HistogramVec,
+    delivery_retries: IntCounterVec,
+    repository_query_duration: HistogramVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let metrics = Self {
+            registry: Registry::new(),
+            kubernetes_events: IntCounter::with_opts(Opts::new("kubernetes_events_total", "Job events received from the Kubernetes watch stream").namespace(NAMESPACE))
+                .expect("Valid metric"),
+            jobs_finished: IntCounterVec::new(Opts::new("jobs_finished_total", "Finished Jobs by outcome").namespace(NAMESPACE), &["outcome"])
+                .expect("Valid metric"),
+            watch_restarts: IntCounter::with_opts(Opts::new("watch_restarts_total", "Restarts of the Kubernetes watch stream after an error").namespace(NAMESPACE))
+                .expect("Valid metric"),
+            job_done_watchers: IntGaugeVec::new(Opts::new("job_done_watchers", "Job done watchers by status").namespace(NAMESPACE), &["status"])
+                .expect("Valid metric"),
+            deliveries: IntCounterVec::new(Opts::new("deliveries_total", "Webhook deliveries by kind, webhook, outcome and response status code").namespace(NAMESPACE), &["kind", "webhook", "outcome", "status_code"])
+                .expect("Valid metric"),
+            delivery_duration: HistogramVec::new(HistogramOpts::new("delivery_duration_seconds", "Duration of the webhook calls, rate limits excluded").namespace(NAMESPACE), &["kind"])
+                .expect("Valid metric"),
+            delivery_retries: IntCounterVec::new(Opts::new("delivery_retries_total", "Webhook deliveries made again after a previous attempt").namespace(NAMESPACE), &["kind"])
+                .expect("Valid metric"),
+            repository_query_duration: HistogramVec::new(HistogramOpts::new("repository_query_duration_seconds", "Duration of the database queries by repository method").namespace(NAMESPACE), &["method"])
+                .expect("Valid metric"),
+        };
+
+        metrics.registry.register(Box::new(metrics.kubernetes_events.clone())).expect("Metric registered once");
+        metrics.registry.register(Box::new(metrics.jobs_finished.clone())).expect("Metric registered once");
+        metrics.registry.register(Box::new(metrics.watch_restarts.clone())).expect("Metric registered once");
+        metrics.registry.register(Box::new(metrics.job_done_watchers.clone())).expect("Metric registered once");
+        metrics.registry.register(Box::new(metrics.deliveries.clone())).expect("Metric registered once");
+        metrics.registry.register(Box::new(metrics.delivery_duration.clone())).expect("Metric registered once");
+        metrics.registry.register(Box::new(metrics.delivery_retries.clone())).expect("Metric registered once");
+        metrics.registry.register(Box::new(metrics.repository_query_duration.clone())).expect("Metric registered once");
+        metrics
+    }
+}
+
+pub fn record_kubernetes_event() {
+    METRICS.kubernetes_events.inc();
+}
+
+pub fn record_job_finished(job_outcome: JobOutcome) {
+    METRICS.jobs_finished.with_label_values(&[&job_outcome.to_string()]).inc();
+}
+
+pub fn record_watch_restart() {
+    METRICS.watch_restarts.inc();
+}
+
+pub fn record_delivery(delivery_kind: DeliveryKind, webhook_id: &Uuid, delivered: bool, response_status_code: Option<u16>) {
+    let outcome = if delivered { "delivered" } else { "failed" };
+    let status_code = response_status_code.map(|status_code| status_code.to_string()).unwrap_or_default();
+    METRICS.deliveries.with_label_values(&[&delivery_kind.to_string(), &webhook_id.to_string(), outcome, &status_code]).inc();
+}
+
+pub fn observe_delivery_duration(delivery_kind: DeliveryKind, duration: Duration) {
+    METRICS.delivery_duration.with_label_values(&[&delivery_kind.to_string()]).observe(duration.as_secs_f64());
+}
+
+pub fn record_delivery_retry(delivery_kind: DeliveryKind) {
+    METRICS.delivery_retries.with_label_values(&[&delivery_kind.to_string()]).inc();
+}
+
+pub fn observe_repository_query(method: &str, duration: Duration) {
+    METRICS.repository_query_duration.with_label_values(&[method]).observe(duration.as_secs_f64());
+}
+
+/// Renders the metrics in the Prometheus text format, counting the watchers by status first.
+pub async fn render_metrics() -> anyhow::Result<String> {
+    let watchers_by_status = repository::get_job_done_watcher_repository().count_watchers_by_status().await?;
+    // Statuses without watchers anymore are dropped rather than kept at their last count.
+    METRICS.job_done_watchers.reset();
+    for (status, count) in watchers_by_status {
+        METRICS.job_done_watchers.with_label_values(&[&status.to_string()]).set(count as i64);
+    }
+
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&METRICS.registry.gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
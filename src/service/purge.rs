@@ -0,0 +1,106 @@
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::models::service::{PurgeReport, RetentionPolicy};
+use crate::repository;
+
+static PURGER: OnceLock<Purger> = OnceLock::new();
+
+pub struct Purger {
+    retention_policy: RetentionPolicy,
+    batch_size: u32,
+}
+
+impl Purger {
+    /// Deletes batches until one comes back short, i.e. nothing is left to delete.
+    async fn purge_in_batches<F, Fut>(&self, label: &str, mut delete_batch: F) -> anyhow::Result<u64>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = anyhow::Result<u64>>,
+    {
+        let mut deleted = 0;
+        loop {
+            let batch = delete_batch(self.batch_size).await?;
+            deleted += batch;
+            if batch < self.batch_size as u64 {
+                log::debug!("Purged {} {}", deleted, label);
+                return Ok(deleted);
+            }
+        }
+    }
+}
+
+pub fn spawn_purge_task(retention_policy: RetentionPolicy, interval: Duration, batch_size: u32) {
+    if retention_policy.is_empty() {
+        log::info!("No retention configured, only expired idempotency keys are purged.");
+    }
+    if PURGER.set(Purger { retention_policy, batch_size }).is_err() {
+        panic!("You can't set Purger twice!");
+    }
+
+    log::info!("Starting purge task (interval: {:?}, batch size: {})", interval, batch_size);
+    actix_web::rt::spawn(async move {
+        let mut ticker = actix_web::rt::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(error) = purge(false).await {
+                log::error!("Purge failed: {:?}", error);
+            }
+        }
+    });
+}
+
+fn get_purger() -> &'static Purger {
+    PURGER.get().expect("Should be set!")
+}
+
+pub async fn purge(dry_run: bool) -> anyhow::Result<PurgeReport> {
+    let purger = get_purger();
+    let now = Utc::now();
+    let mut purge_report = PurgeReport::new(dry_run);
+
+    let job_done_watcher_repository = repository::get_job_done_watcher_repository();
+    for (status, retention) in purger.retention_policy.job_done_watcher_retentions() {
+        let created_before = now - *retention;
+        let count = if dry_run {
+            job_done_watcher_repository.count_watchers_by_status_created_before(*status, created_before).await?
+        } else {
+            purger.purge_in_batches("job done watchers", |batch_size| {
+                job_done_watcher_repository.delete_watchers_by_status_created_before(*status, created_before, batch_size)
+            }).await?
+        };
+        purge_report.add_job_done_watchers(*status, count);
+    }
+
+    if let Some(retention) = purger.retention_policy.job_family_delivery_retention() {
+        let created_before = now - retention;
+        let job_family_watcher_repository = repository::get_job_family_watcher_repository();
+        let count = if dry_run {
+            job_family_watcher_repository.count_job_family_deliveries_created_before(created_before).await?
+        } else {
+            purger.purge_in_batches("job family deliveries", |batch_size| {
+                job_family_watcher_repository.delete_job_family_deliveries_created_before(created_before, batch_size)
+            }).await?
+        };
+        purge_report.set_job_family_deliveries(count);
+    }
+
+    let idempotency_key_repository = repository::get_idempotency_key_repository();
+    let count = if dry_run {
+        idempotency_key_repository.count_expired_idempotency_keys(now).await?
+    } else {
+        purger.purge_in_batches("idempotency keys", |batch_size| {
+            idempotency_key_repository.delete_expired_idempotency_keys(now, batch_size)
+        }).await?
+    };
+    purge_report.set_idempotency_keys(count);
+
+    if !dry_run {
+        log::info!("Purge done: {:?}", purge_report);
+    }
+
+    Ok(purge_report)
+}
@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::models::service::Namespace;
+
+static TENANT_NAMESPACES: OnceLock<HashMap<String, Vec<String>>> = OnceLock::new();
+
+pub fn set_tenant_namespaces(tenant_namespaces: HashMap<String, Vec<String>>) {
+    if TENANT_NAMESPACES.set(tenant_namespaces).is_err() {
+        panic!("You can't set Tenant Namespaces twice!");
+    }
+}
+
+/// A resource is visible to its owner, and to everyone when the caller isn't scoped to a tenant.
+pub fn is_visible(owner: Option<&str>, tenant: Option<&str>) -> bool {
+    tenant.is_none_or(|tenant| owner == Some(tenant))
+}
+
+/// Tenants without configured namespaces may only watch the namespace named after them.
+pub fn can_watch_namespace(tenant: Option<&str>, namespace: &Namespace) -> bool {
+    let Some(tenant) = tenant else {
+        return true;
+    };
+    match TENANT_NAMESPACES.get().and_then(|tenant_namespaces| tenant_namespaces.get(tenant)) {
+        Some(namespaces) => namespaces.iter().any(|allowed_namespace| allowed_namespace == namespace.as_str()),
+        None => tenant == namespace.as_str(),
+    }
+}
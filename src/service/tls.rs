@@ -0,0 +1,80 @@
+use std::fs;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+use openssl::ssl::{SniError, SslAcceptor, SslAcceptorBuilder, SslContext, SslFiletype, SslMethod, SslVerifyMode};
+use openssl::x509::X509Name;
+
+use crate::models::service::TlsSettings;
+
+static SSL_CONTEXT: OnceLock<RwLock<SslContext>> = OnceLock::new();
+
+/// Every handshake switches to the latest loaded context, so that rotated certificates apply to new connections.
+pub fn build_ssl_acceptor(tls_settings: &TlsSettings) -> anyhow::Result<SslAcceptorBuilder> {
+    let ssl_context = build_ssl_acceptor_builder(tls_settings)?.build().into_context();
+    if SSL_CONTEXT.set(RwLock::new(ssl_context)).is_err() {
+        panic!("You can't set SSL Context twice!");
+    }
+
+    let mut ssl_acceptor_builder = build_ssl_acceptor_builder(tls_settings)?;
+    // Called on every ClientHello, with or without a server name.
+    ssl_acceptor_builder.set_servername_callback(|ssl, _| {
+        let ssl_context = SSL_CONTEXT.get().expect("Should be set!").read().expect("SSL Context lock poisoned!");
+        ssl.set_ssl_context(&ssl_context).map_err(|_| SniError::ALERT_FATAL)
+    });
+    Ok(ssl_acceptor_builder)
+}
+
+fn build_ssl_acceptor_builder(tls_settings: &TlsSettings) -> anyhow::Result<SslAcceptorBuilder> {
+    let mut ssl_acceptor_builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server())?;
+    ssl_acceptor_builder.set_certificate_chain_file(tls_settings.cert_file())?;
+    ssl_acceptor_builder.set_private_key_file(tls_settings.key_file(), SslFiletype::PEM)?;
+    ssl_acceptor_builder.check_private_key()?;
+
+    if let Some(client_ca_file) = tls_settings.client_ca_file() {
+        ssl_acceptor_builder.set_ca_file(client_ca_file)?;
+        ssl_acceptor_builder.set_client_ca_list(X509Name::load_client_ca_file(client_ca_file)?);
+        ssl_acceptor_builder.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+    }
+    Ok(ssl_acceptor_builder)
+}
+
+pub fn spawn_tls_reload_task(tls_settings: TlsSettings, interval: Duration) {
+    log::info!("Starting TLS reload task (interval: {:?})", interval);
+
+    actix_web::rt::spawn(async move {
+        let mut ticker = actix_web::rt::time::interval(interval);
+        let mut loaded_files = read_tls_files(&tls_settings).ok();
+        loop {
+            ticker.tick().await;
+            let files = match read_tls_files(&tls_settings) {
+                Ok(files) => files,
+                Err(error) => {
+                    log::warn!("Failed to read TLS files, keeping the loaded certificate: {}", error);
+                    continue;
+                },
+            };
+            if loaded_files.as_ref() == Some(&files) {
+                continue;
+            }
+
+            // A certificate and key replaced one after the other are retried once the second file changes.
+            loaded_files = Some(files);
+            match build_ssl_acceptor_builder(&tls_settings) {
+                Ok(ssl_acceptor_builder) => {
+                    *SSL_CONTEXT.get().expect("Should be set!").write().expect("SSL Context lock poisoned!") = ssl_acceptor_builder.build().into_context();
+                    log::info!("TLS certificate reloaded from {}", tls_settings.cert_file().display());
+                },
+                Err(error) => log::warn!("Failed to reload TLS certificate, keeping the loaded one: {}", error),
+            }
+        }
+    });
+}
+
+fn read_tls_files(tls_settings: &TlsSettings) -> std::io::Result<Vec<Vec<u8>>> {
+    [Some(tls_settings.cert_file()), Some(tls_settings.key_file()), tls_settings.client_ca_file()]
+        .into_iter()
+        .flatten()
+        .map(fs::read)
+        .collect()
+}
@@ -1,19 +1,38 @@
+use std::time::{Duration, Instant};
+
 use chrono::Utc;
+use reqwest::{Request, Response};
+use reqwest::header::HeaderMap;
 use uuid::Uuid;
 
-use crate::models::service::{CreateWebhookRequest, Webhook};
-use crate::repository;
+use crate::models::service::{CreateWebhookError, CreateWebhookRequest, DeliveryKind, Page, PageCursor, PageRequest, RenderedWebhookRequest, TestWebhookRequest, TestWebhookResponse, TestWebhookResult, Webhook, WebhookFilter};
+use crate::{repository, service};
+
+const MAX_RECORDED_RESPONSE_BODY_BYTES: usize = 64 * 1024;
 
-pub async fn create_webhook(create_webhook_request: CreateWebhookRequest) -> anyhow::Result<Webhook> {
+pub async fn create_webhook(create_webhook_request: CreateWebhookRequest, tenant: Option<&str>) -> Result<Webhook, CreateWebhookError> {
     log::info!("Creating a new webhook with URL: {}", create_webhook_request.url());
 
+    if let Err(error) = service::egress::check_url(create_webhook_request.url()).await {
+        log::warn!("Rejecting webhook with URL {}: {}", create_webhook_request.url(), error);
+        return Err(error.into());
+    }
+    if let Some(tls_profile) = create_webhook_request.tls_profile() {
+        if !service::delivery_client::get_delivery_client().has_tls_profile(tls_profile) {
+            log::warn!("Rejecting webhook with unknown TLS profile: {}", tls_profile);
+            return Err(CreateWebhookError::UnknownTlsProfile(tls_profile.to_string()));
+        }
+    }
+
     let webhook = Webhook::new(
         Uuid::new_v4(),
         create_webhook_request.url().clone(),
         create_webhook_request.request_body(),
         create_webhook_request.description(),
         Utc::now(),
-    );
+    ).with_tenant(tenant)
+     .with_tls_profile(create_webhook_request.tls_profile())
+     .with_rate_limit_per_second(create_webhook_request.rate_limit_per_second());
 
     let webhook_repository = repository::get_webhook_repository();
     match webhook_repository.create_webhook(&webhook).await {
@@ -23,20 +42,20 @@ pub async fn create_webhook(create_webhook_request: CreateWebhookRequest) -> any
         }
         Err(error) => {
             log::error!("Failed to create webhook: {:?}", error);
-            Err(error)
+            Err(error.into())
         }
     }
 }
 
-pub async fn get_webhooks() -> anyhow::Result<Vec<Webhook>> {
-    log::info!("Fetching all webhooks");
+pub async fn get_webhooks(webhook_filter: &WebhookFilter, page_request: &PageRequest) -> anyhow::Result<Page<Webhook>> {
+    log::info!("Fetching webhooks");
 
     let webhook_repository = repository::get_webhook_repository();
 
-    match webhook_repository.find_all_webhooks().await {
+    match webhook_repository.find_all_webhooks(webhook_filter, &page_request.with_lookahead()).await {
         Ok(webhooks) => {
             log::info!("Successfully retrieved {} webhooks", webhooks.len());
-            Ok(webhooks)
+            Ok(Page::from_lookahead(webhooks, page_request, |webhook| PageCursor::new(webhook.created_at(), webhook.id())))
         }
         Err(error) => {
             log::error!("Failed to fetch webhooks: {:?}", error);
@@ -45,17 +64,17 @@ pub async fn get_webhooks() -> anyhow::Result<Vec<Webhook>> {
     }
 }
 
-pub async fn get_webhook_by_id(webhook_id: &Uuid) -> anyhow::Result<Option<Webhook>> {
+pub async fn get_webhook_by_id(webhook_id: &Uuid, tenant: Option<&str>) -> anyhow::Result<Option<Webhook>> {
     log::info!("Fetching webhook with ID: {}", webhook_id);
 
     let webhook_repository = repository::get_webhook_repository();
 
     match webhook_repository.find_webhook_by_id(webhook_id).await {
-        Ok(Some(webhook)) => {
+        Ok(Some(webhook)) if service::tenants::is_visible(webhook.tenant(), tenant) => {
             log::info!("Successfully retrieved webhook with ID: {}", webhook.id());
             Ok(Some(webhook))
         }
-        Ok(None) => {
+        Ok(_) => {
             log::warn!("Webhook with ID {} not found", webhook_id);
             Ok(None)
         }
@@ -64,4 +83,91 @@ pub async fn get_webhook_by_id(webhook_id: &Uuid) -> anyhow::Result<Option<Webho
             Err(error)
         }
     }
+}
+
+/// Sends a request built by `build_webhook_request`, throttled by the destination of the webhook.
+pub async fn send_webhook_request(webhook: &Webhook, request: Request, delivery_kind: DeliveryKind) -> anyhow::Result<Response> {
+    let destination_permit = service::destinations::acquire(webhook.url(), Some(webhook)).await;
+    let started_at = Instant::now();
+    let response = service::delivery_client::get_delivery_client().execute(request, webhook.tls_profile()).await;
+    service::metrics::observe_delivery_duration(delivery_kind, started_at.elapsed());
+    destination_permit.record(&response);
+    response
+}
+
+pub fn build_webhook_request(webhook: &Webhook, request_timeout: Duration) -> anyhow::Result<Request> {
+    Ok(service::delivery_client::get_delivery_client().http_client(webhook.tls_profile())?
+        .post(webhook.url().to_string())
+        .body(webhook.request_body().to_string())
+        .timeout(request_timeout)
+        .build()?)
+}
+
+pub async fn test_webhook(webhook_id: &Uuid, test_webhook_request: &TestWebhookRequest, tenant: Option<&str>) -> anyhow::Result<Option<TestWebhookResult>> {
+    let Some(webhook) = get_webhook_by_id(webhook_id, tenant).await? else {
+        return Ok(None);
+    };
+    log::info!("Testing webhook {} for job {} (send: {})", webhook_id, test_webhook_request.job_name(), test_webhook_request.send());
+
+    let mut test_webhook_result = TestWebhookResult::new(test_webhook_request);
+    let request_timeout = service::delivery_pool::get_delivery_pool().request_timeout_for(test_webhook_request.timeout_seconds());
+    let request = match build_webhook_request(&webhook, request_timeout) {
+        Ok(request) => request,
+        Err(error) => {
+            test_webhook_result.set_error(error.to_string());
+            return Ok(Some(test_webhook_result));
+        },
+    };
+
+    test_webhook_result.set_request(to_rendered_webhook_request(&request));
+    if !test_webhook_request.send() {
+        return Ok(Some(test_webhook_result));
+    }
+
+    let started_at = Instant::now();
+    let response = send_webhook_request(&webhook, request, DeliveryKind::Test).await;
+    test_webhook_result.set_latency(started_at.elapsed());
+    match response {
+        Ok(response) => {
+            log::info!("Test of webhook {} answered with status: {}", webhook_id, response.status());
+            test_webhook_result.set_response(TestWebhookResponse::new(response.status().as_u16(), to_header_pairs(response.headers())));
+        },
+        Err(error) => {
+            log::warn!("Test of webhook {} failed: {}", webhook_id, error);
+            test_webhook_result.set_error(error.to_string());
+        },
+    }
+    Ok(Some(test_webhook_result))
+}
+
+/// Reads the body of a response to record it, truncated past `MAX_RECORDED_RESPONSE_BODY_BYTES`.
+pub async fn read_response_body(mut response: Response) -> String {
+    let mut body = Vec::new();
+    while body.len() < MAX_RECORDED_RESPONSE_BODY_BYTES {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            Ok(None) => break,
+            Err(error) => {
+                log::warn!("Failed to read the body of the response from {}: {}", response.url(), error);
+                break;
+            },
+        }
+    }
+    body.truncate(MAX_RECORDED_RESPONSE_BODY_BYTES);
+    String::from_utf8_lossy(&body).to_string()
+}
+
+pub fn to_rendered_webhook_request(request: &Request) -> RenderedWebhookRequest {
+    RenderedWebhookRequest::new(
+        request.method().as_str(),
+        request.url().as_str(),
+        to_header_pairs(request.headers()),
+        &String::from_utf8_lossy(request.body().and_then(|body| body.as_bytes()).unwrap_or_default()),
+    )
+}
+
+pub fn to_header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers.iter()
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).to_string()))
+        .collect()
 }
\ No newline at end of file
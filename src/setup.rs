@@ -1,16 +1,43 @@
+use std::collections::HashMap;
 use std::env;
 use std::fs::read_to_string;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use actix_web::{App, HttpServer, web};
+use actix_web::middleware;
 use actix_web::middleware::Logger;
 use futures_util::stream;
 use futures_util::StreamExt;
+use ipnet::IpNet;
+use sqlx::{Acquire, Database};
+use sqlx::migrate::{Migrate, Migrator};
 use yaml_rust2::YamlLoader;
 
 use crate::{controller, repository, service};
-use crate::controller::IdempotencyMap;
-use crate::models::service::JobFamilyWatcher;
-use crate::repository::SqlxAcquire;
+use crate::models::service::{API_KEY_PREFIX, ApiScope, AuthProvider, AuthSettings, ClientTlsProfile, DeliveryClientSettings, DestinationSettings, EgressPolicy, HttpUrl, JobDoneWatcherStatus, JobFamilyWatcher, Namespace, RetentionPolicy, TlsSettings};
+use crate::repository::{ApiKeyRepository, DeadLetterRepository, IdempotencyKeyRepository, JobDoneWatcherRepository, JobFamilyWatcherRepository, SqlxAcquire, SqlxMigrationsTable, WebhookRepository};
+
+static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("migrations/sqlite");
+static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("migrations/postgres");
+static MYSQL_MIGRATOR: Migrator = sqlx::migrate!("migrations/mysql");
+
+const DEFAULT_DELIVERY_CONCURRENCY: u64 = 10;
+const DEFAULT_DELIVERY_TIMEOUT_SECONDS: u64 = 30;
+const DEFAULT_DELIVERY_MAX_ATTEMPTS: u64 = 3;
+const DEFAULT_DELIVERY_RETRY_BACKOFF_SECONDS: u64 = 5;
+const DEFAULT_DELIVERY_CONNECT_TIMEOUT_SECONDS: u64 = 10;
+const DEFAULT_DELIVERY_MAX_IDLE_CONNECTIONS_PER_HOST: u64 = 10;
+const DEFAULT_DESTINATION_CIRCUIT_BREAKER_OPEN_SECONDS: u64 = 30;
+const DEFAULT_DESTINATION_IDLE_TIMEOUT_SECONDS: u64 = 600;
+const DEFAULT_PURGE_INTERVAL_SECONDS: u64 = 3600;
+const DEFAULT_PURGE_BATCH_SIZE: u64 = 500;
+const DEFAULT_IDEMPOTENCY_KEY_TTL_SECONDS: u64 = 86400;
+const DEFAULT_IDEMPOTENCY_KEY_IN_PROGRESS_TIMEOUT_SECONDS: u64 = 60;
+const DEFAULT_TLS_RELOAD_INTERVAL_SECONDS: u64 = 60;
+// Loopback, link-local (cloud metadata endpoints) and unspecified addresses.
+pub const DEFAULT_EGRESS_DENIED_CIDRS: [&str; 6] = ["127.0.0.0/8", "169.254.0.0/16", "0.0.0.0/8", "::1/128", "fe80::/10", "::/128"];
 
 pub fn init_logging() -> anyhow::Result<()> {
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
@@ -18,6 +45,26 @@ pub fn init_logging() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunMode {
+    Server,
+    MigrateOnly,
+    CheckMigrations,
+}
+
+pub fn parse_run_mode() -> anyhow::Result<RunMode> {
+    let mut run_mode = RunMode::Server;
+    for arg in env::args().skip(1) {
+        run_mode = match arg.as_str() {
+            "--migrate-only" => RunMode::MigrateOnly,
+            "--check-migrations" => RunMode::CheckMigrations,
+            _ => return Err(anyhow::anyhow!("Unknown argument: {}", arg)),
+        };
+    }
+
+    Ok(run_mode)
+}
+
 pub async fn init_database() -> anyhow::Result<()> {
     log::info!("Init database...");
 
@@ -25,33 +72,57 @@ pub async fn init_database() -> anyhow::Result<()> {
     let database = database_url.split(':').next().unwrap_or("");
 
     match database {
+        "memory" => {
+            log::warn!("Using in-memory database: data will be lost on restart.");
+            set_repositories(repository::InMemoryDatabase::new());
+            Ok(())
+        },
         "sqlite" => {
+            let repository = connect_sqlite(&database_url).await?;
+            migrate(&repository, &SQLITE_MIGRATOR, false).await?;
+            set_repositories(repository);
+            Ok(())
+        },
+        "postgres" | "postgresql" => {
+            let repository = repository::PostgresDatabase::connect(&database_url).await?;
+            migrate(&repository, &POSTGRES_MIGRATOR, false).await?;
+            set_repositories(repository);
+            Ok(())
+        },
+        "mysql" | "mariadb" => {
+            let repository = repository::MySqlDatabase::connect(&database_url).await?;
+            migrate(&repository, &MYSQL_MIGRATOR, false).await?;
+            set_repositories(repository);
+            Ok(())
+        },
+        _ => Err(anyhow::anyhow!("Unsupported database: {}", database))
+    }
+}
 
-            let repository = if is_in_memory_sqlite(&database_url) {
-                let repository = repository::SqliteDatabase::connect_in_memory(&database_url).await?;
-                log::info!("Running migrations for in-memory SQLite database...");
-
-                let mut conn = repository.acquire().await?;
-                sqlx::migrate!("migrations/sqlite")
-                    .run(&mut *conn)
-                    .await?;
-
-                log::info!("Migrations completed successfully.");
-                repository
-            } else {
-                repository::SqliteDatabase::connect(&database_url).await?
-            };
-
-            repository::set_webhook_repository(repository.clone());
-            repository::set_job_done_watcher_repository(repository.clone());
-            repository::set_job_family_watcher_repository(repository);
+pub async fn migrate_database(check_only: bool) -> anyhow::Result<()> {
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let database = database_url.split(':').next().unwrap_or("");
 
+    match database {
+        "memory" => {
+            log::info!("In-memory database has no migrations.");
             Ok(())
         },
+        "sqlite" => migrate(&connect_sqlite(&database_url).await?, &SQLITE_MIGRATOR, check_only).await,
+        "postgres" | "postgresql" => migrate(&repository::PostgresDatabase::connect(&database_url).await?, &POSTGRES_MIGRATOR, check_only).await,
+        "mysql" | "mariadb" => migrate(&repository::MySqlDatabase::connect(&database_url).await?, &MYSQL_MIGRATOR, check_only).await,
         _ => Err(anyhow::anyhow!("Unsupported database: {}", database))
     }
 }
 
+async fn connect_sqlite(database_url: &str) -> anyhow::Result<repository::SqliteDatabase> {
+    if is_in_memory_sqlite(database_url) {
+        repository::SqliteDatabase::connect_in_memory(database_url).await
+    } else {
+        repository::SqliteDatabase::connect(database_url).await
+    }
+}
+
 fn is_in_memory_sqlite(url: &str) -> bool {
     const URL_IN_MEMORY: [&str; 4] = [
         "sqlite::memory:",
@@ -63,6 +134,73 @@ fn is_in_memory_sqlite(url: &str) -> bool {
     URL_IN_MEMORY.contains(&url)
 }
 
+fn set_repositories<R>(repository: R)
+where
+    R: ApiKeyRepository + DeadLetterRepository + IdempotencyKeyRepository + WebhookRepository + JobDoneWatcherRepository + JobFamilyWatcherRepository + Clone + 'static
+{
+    let repository = repository::MeteredRepository::new(repository);
+    repository::set_api_key_repository(repository.clone());
+    repository::set_dead_letter_repository(repository.clone());
+    repository::set_idempotency_key_repository(repository.clone());
+    repository::set_webhook_repository(repository.clone());
+    repository::set_job_done_watcher_repository(repository.clone());
+    repository::set_job_family_watcher_repository(repository);
+}
+
+async fn migrate<R>(repository: &R, migrator: &Migrator, check_only: bool) -> anyhow::Result<()>
+where
+    R: SqlxAcquire + SqlxMigrationsTable + Sync,
+    <R::DB as Database>::Connection: Migrate,
+    for<'c> &'c mut <R::DB as Database>::Connection: Acquire<'c, Connection = &'c mut <R::DB as Database>::Connection>,
+{
+    if check_only {
+        log::info!("Checking database migrations...");
+        // A check leaves the database untouched, even without the migrations table.
+        if !repository.has_migrations_table().await? {
+            return Err(anyhow::anyhow!("Database is not migrated"));
+        }
+        let mut conn = repository.acquire().await?;
+        return check_migrations(&mut *conn, migrator).await;
+    }
+
+    let mut conn = repository.acquire().await?;
+
+    log::info!("Running database migrations...");
+    migrator.run(&mut *conn).await?;
+    log::info!("Migrations completed successfully.");
+    Ok(())
+}
+
+async fn check_migrations(conn: &mut impl Migrate, migrator: &Migrator) -> anyhow::Result<()> {
+    if let Some(version) = conn.dirty_version().await? {
+        return Err(anyhow::anyhow!("Migration {} is only partially applied", version));
+    }
+
+    let applied_migrations: HashMap<i64, _> = conn.list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|applied_migration| (applied_migration.version, applied_migration.checksum))
+        .collect();
+
+    let mut pending_migrations = Vec::new();
+    for migration in migrator.iter().filter(|migration| migration.migration_type.is_up_migration()) {
+        match applied_migrations.get(&migration.version) {
+            Some(checksum) if *checksum != migration.checksum => {
+                return Err(anyhow::anyhow!("Migration {} was modified after being applied", migration.version));
+            },
+            Some(_) => {},
+            None => pending_migrations.push(migration.version),
+        }
+    }
+
+    if !pending_migrations.is_empty() {
+        return Err(anyhow::anyhow!("Pending migrations: {:?}", pending_migrations));
+    }
+
+    log::info!("All migrations are applied.");
+    Ok(())
+}
+
 pub async fn parse_job_family_watchers_config_file() -> anyhow::Result<()> {
     if let Ok(job_family_watchers_config_file) = env::var("JOB_FAMILY_WATCHERS_CONFIG_FILE") {
         log::info!("Attempting to read job family watchers config file: {}", job_family_watchers_config_file);
@@ -86,21 +224,25 @@ pub async fn parse_job_family_watchers_config_file() -> anyhow::Result<()> {
                     log::error!("Failed to convert object to JobFamilyWatcher. Error: {}", err);
                     anyhow::anyhow!("Failed to convert object to JobFamilyWatcher: {}", err)
                 })?;
+                if job_family_watchers.iter().any(|other| other.name() == job_family_watcher.name()) {
+                    log::error!("Job family watcher name '{}' is used twice, name watchers of the same job family apart.", job_family_watcher.name());
+                    return Err(anyhow::anyhow!("Duplicate job family watcher name: {}", job_family_watcher.name()));
+                }
                 job_family_watchers.push(job_family_watcher);
             }
         }
 
         if !job_family_watchers.is_empty() {
-            log::info!("Creating job family watchers in the service... TOT: {}", job_family_watchers.len());
+            log::info!("Saving job family watchers in the service... TOT: {}", job_family_watchers.len());
         } else {
-            log::info!("No job family watchers to create.");
+            log::info!("No job family watchers to save.");
         }
 
-        stream::iter(job_family_watchers.into_iter())
+        stream::iter(job_family_watchers)
             .for_each(|job_family_watcher| {
                 async move {
-                    if let Err(err) = service::job_family_watcher::create_job_family_watcher(job_family_watcher.clone()).await {
-                        log::error!("Failed to create job family watcher: {:?}. Error: {}", job_family_watcher, err);
+                    if let Err(err) = service::job_family_watcher::save_job_family_watcher(job_family_watcher.clone()).await {
+                        log::error!("Failed to save job family watcher: {:?}. Error: {}", job_family_watcher, err);
                     }
                 }
             })
@@ -112,23 +254,387 @@ pub async fn parse_job_family_watchers_config_file() -> anyhow::Result<()> {
     Ok(())
 }
 
+pub fn init_delivery_pool() -> anyhow::Result<()> {
+    log::info!("Init delivery pool...");
+
+    let concurrency = parse_env_var("DELIVERY_CONCURRENCY", DEFAULT_DELIVERY_CONCURRENCY)?;
+    let request_timeout_seconds = parse_env_var("DELIVERY_TIMEOUT_SECONDS", DEFAULT_DELIVERY_TIMEOUT_SECONDS)?;
+    let max_attempts = parse_env_var("DELIVERY_MAX_ATTEMPTS", DEFAULT_DELIVERY_MAX_ATTEMPTS)?;
+    let retry_backoff_seconds = parse_env_var("DELIVERY_RETRY_BACKOFF_SECONDS", DEFAULT_DELIVERY_RETRY_BACKOFF_SECONDS)?;
+    if concurrency == 0 {
+        return Err(anyhow::anyhow!("DELIVERY_CONCURRENCY must be greater than 0"));
+    }
+    if max_attempts == 0 || max_attempts > u32::MAX as u64 {
+        return Err(anyhow::anyhow!("DELIVERY_MAX_ATTEMPTS must be between 1 and {}", u32::MAX));
+    }
+
+    service::delivery_pool::spawn_delivery_pool(
+        concurrency as usize,
+        Duration::from_secs(request_timeout_seconds),
+        max_attempts as u32,
+        Duration::from_secs(retry_backoff_seconds),
+    );
+    Ok(())
+}
+
+pub fn init_destinations() -> anyhow::Result<()> {
+    log::info!("Init destinations...");
+
+    let rate_limit_per_second = match env::var("DESTINATION_RATE_LIMIT_PER_SECOND") {
+        Ok(value) => Some(value.parse::<f64>()
+            .ok()
+            .filter(|rate_limit_per_second| rate_limit_per_second.is_finite() && *rate_limit_per_second > 0.0)
+            .ok_or_else(|| anyhow::anyhow!("Invalid value for DESTINATION_RATE_LIMIT_PER_SECOND: {} (a positive number is expected)", value))?),
+        Err(_) => None,
+    };
+    let rate_limit_burst = parse_optional_env_var("DESTINATION_RATE_LIMIT_BURST")?
+        .unwrap_or_else(|| rate_limit_per_second.map_or(1, |rate_limit_per_second| rate_limit_per_second.ceil() as u64));
+    if rate_limit_burst == 0 || rate_limit_burst > u32::MAX as u64 {
+        return Err(anyhow::anyhow!("DESTINATION_RATE_LIMIT_BURST must be between 1 and {}", u32::MAX));
+    }
+    let circuit_breaker_failures = parse_optional_env_var("DESTINATION_CIRCUIT_BREAKER_FAILURES")?;
+    if circuit_breaker_failures.is_some_and(|failures| failures == 0 || failures > u32::MAX as u64) {
+        return Err(anyhow::anyhow!("DESTINATION_CIRCUIT_BREAKER_FAILURES must be between 1 and {}", u32::MAX));
+    }
+    let circuit_breaker_open_seconds = parse_env_var("DESTINATION_CIRCUIT_BREAKER_OPEN_SECONDS", DEFAULT_DESTINATION_CIRCUIT_BREAKER_OPEN_SECONDS)?;
+    if circuit_breaker_open_seconds == 0 {
+        return Err(anyhow::anyhow!("DESTINATION_CIRCUIT_BREAKER_OPEN_SECONDS must be greater than 0"));
+    }
+    let idle_timeout_seconds = parse_env_var("DESTINATION_IDLE_TIMEOUT_SECONDS", DEFAULT_DESTINATION_IDLE_TIMEOUT_SECONDS)?;
+    if idle_timeout_seconds == 0 {
+        return Err(anyhow::anyhow!("DESTINATION_IDLE_TIMEOUT_SECONDS must be greater than 0"));
+    }
+
+    let destination_settings = DestinationSettings::new(
+        rate_limit_per_second,
+        rate_limit_burst as u32,
+        circuit_breaker_failures.map(|failures| failures as u32),
+        Duration::from_secs(circuit_breaker_open_seconds),
+        Duration::from_secs(idle_timeout_seconds),
+    );
+    log::info!("Destination settings: {:?}", destination_settings);
+    service::destinations::set_destination_settings(destination_settings);
+    service::destinations::spawn_idle_destinations_eviction_task();
+    Ok(())
+}
+
+pub fn init_purge() -> anyhow::Result<()> {
+    log::info!("Init purge...");
+
+    let mut job_done_watcher_retentions = Vec::new();
+    for (status, name) in [
+        (JobDoneWatcherStatus::Completed, "RETENTION_COMPLETED_SECONDS"),
+        (JobDoneWatcherStatus::PartiallyCompleted, "RETENTION_PARTIALLY_COMPLETED_SECONDS"),
+        (JobDoneWatcherStatus::Failed, "RETENTION_FAILED_SECONDS"),
+        (JobDoneWatcherStatus::Timeout, "RETENTION_TIMEOUT_SECONDS"),
+        (JobDoneWatcherStatus::Cancelled, "RETENTION_CANCELLED_SECONDS"),
+    ] {
+        if let Some(seconds) = parse_optional_env_var(name)? {
+            job_done_watcher_retentions.push((status, chrono::Duration::seconds(seconds as i64)));
+        }
+    }
+    let job_family_delivery_retention = parse_optional_env_var("RETENTION_JOB_FAMILY_DELIVERIES_SECONDS")?
+        .map(|seconds| chrono::Duration::seconds(seconds as i64));
+
+    let interval_seconds = parse_env_var("PURGE_INTERVAL_SECONDS", DEFAULT_PURGE_INTERVAL_SECONDS)?;
+    let batch_size = parse_env_var("PURGE_BATCH_SIZE", DEFAULT_PURGE_BATCH_SIZE)?;
+    if interval_seconds == 0 {
+        return Err(anyhow::anyhow!("PURGE_INTERVAL_SECONDS must be greater than 0"));
+    }
+    if batch_size == 0 || batch_size > u32::MAX as u64 {
+        return Err(anyhow::anyhow!("PURGE_BATCH_SIZE must be between 1 and {}", u32::MAX));
+    }
+
+    service::purge::spawn_purge_task(
+        RetentionPolicy::new(job_done_watcher_retentions, job_family_delivery_retention),
+        Duration::from_secs(interval_seconds),
+        batch_size as u32
+    );
+    Ok(())
+}
+
+pub fn init_idempotency() -> anyhow::Result<()> {
+    log::info!("Init idempotency...");
+
+    let ttl_seconds = parse_env_var("IDEMPOTENCY_KEY_TTL_SECONDS", DEFAULT_IDEMPOTENCY_KEY_TTL_SECONDS)?;
+    if ttl_seconds == 0 || ttl_seconds > i64::MAX as u64 / 1000 {
+        return Err(anyhow::anyhow!("IDEMPOTENCY_KEY_TTL_SECONDS must be between 1 and {}", i64::MAX as u64 / 1000));
+    }
+
+    let in_progress_timeout_seconds = parse_env_var("IDEMPOTENCY_KEY_IN_PROGRESS_TIMEOUT_SECONDS", DEFAULT_IDEMPOTENCY_KEY_IN_PROGRESS_TIMEOUT_SECONDS)?;
+    if in_progress_timeout_seconds == 0 || in_progress_timeout_seconds > i64::MAX as u64 / 1000 {
+        return Err(anyhow::anyhow!("IDEMPOTENCY_KEY_IN_PROGRESS_TIMEOUT_SECONDS must be between 1 and {}", i64::MAX as u64 / 1000));
+    }
+
+    service::idempotency::set_idempotency_key_ttl(chrono::Duration::seconds(ttl_seconds as i64));
+    service::idempotency::set_idempotency_key_in_progress_timeout(chrono::Duration::seconds(in_progress_timeout_seconds as i64));
+    Ok(())
+}
+
+pub fn init_dead_letters() -> anyhow::Result<()> {
+    log::info!("Init dead letters...");
+
+    match env::var("DEAD_LETTER_WEBHOOK_URL") {
+        Ok(value) => {
+            let url = HttpUrl::new(&value)
+                .map_err(|err| anyhow::anyhow!("Invalid value for DEAD_LETTER_WEBHOOK_URL: {} ({})", value, err))?;
+            service::dead_letters::set_dead_letter_webhook_url(url);
+        },
+        Err(_) => log::info!("Environment variable DEAD_LETTER_WEBHOOK_URL is not set, dead letters won't be notified."),
+    }
+
+    Ok(())
+}
+
+pub async fn init_auth() -> anyhow::Result<()> {
+    log::info!("Init auth...");
+
+    let providers = parse_list_env_var("AUTH_PROVIDERS").iter()
+        .map(|provider| AuthProvider::try_from(provider.as_str()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if providers.is_empty() {
+        log::warn!("Environment variable AUTH_PROVIDERS is not set, the REST API is not authenticated.");
+        return Ok(());
+    }
+
+    let service_account_scopes = match env::var("AUTH_SERVICE_ACCOUNT_SCOPES") {
+        Ok(value) => ApiScope::parse_list(&value)?,
+        Err(_) => vec![ApiScope::Read, ApiScope::Write],
+    };
+    let auth_settings = AuthSettings::new(
+        providers,
+        service_account_scopes,
+        parse_list_env_var("AUTH_SERVICE_ACCOUNTS"),
+        parse_list_env_var("AUTH_TOKEN_AUDIENCES"),
+    );
+    if auth_settings.is_enabled(AuthProvider::ServiceAccount) {
+        service::auth::set_kube_client(kube::Client::try_default().await?);
+    }
+    if let Ok(bootstrap_api_key) = env::var("AUTH_BOOTSTRAP_API_KEY") {
+        if !bootstrap_api_key.starts_with(API_KEY_PREFIX) {
+            return Err(anyhow::anyhow!("AUTH_BOOTSTRAP_API_KEY must start with {}", API_KEY_PREFIX));
+        }
+        service::auth::ensure_bootstrap_api_key(&bootstrap_api_key).await?;
+    }
+
+    service::auth::set_auth_settings(auth_settings);
+    Ok(())
+}
+
+pub fn init_tenants() -> anyhow::Result<()> {
+    log::info!("Init tenants...");
+
+    // Format: tenant=namespace|namespace,tenant=namespace
+    let mut tenant_namespaces = HashMap::new();
+    for entry in parse_list_env_var("TENANT_NAMESPACES") {
+        let (tenant, namespaces) = entry.split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid value for TENANT_NAMESPACES: {}", entry))?;
+        let namespaces = namespaces.split('|')
+            .map(|namespace| Namespace::new(namespace.trim()).map(|namespace| namespace.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        tenant_namespaces.insert(tenant.trim().to_string(), namespaces);
+    }
+
+    service::tenants::set_tenant_namespaces(tenant_namespaces);
+    Ok(())
+}
+
+pub fn init_egress() -> anyhow::Result<()> {
+    log::info!("Init egress policy...");
+
+    let denied_cidrs = match env::var("EGRESS_DENIED_CIDRS") {
+        Ok(_) => parse_cidrs_env_var("EGRESS_DENIED_CIDRS")?,
+        Err(_) => DEFAULT_EGRESS_DENIED_CIDRS.iter().map(|cidr| cidr.parse()).collect::<Result<_, _>>()?,
+    };
+    let egress_policy = EgressPolicy::new(
+        parse_cidrs_env_var("EGRESS_ALLOWED_CIDRS")?,
+        denied_cidrs,
+        parse_list_env_var("EGRESS_ALLOWED_HOSTS"),
+        parse_list_env_var("EGRESS_DENIED_HOSTS"),
+    );
+    log::info!("Egress policy: {:?}", egress_policy);
+
+    service::egress::set_egress_policy(egress_policy);
+    Ok(())
+}
+
+pub fn init_delivery_client() -> anyhow::Result<()> {
+    log::info!("Init delivery client...");
+
+    let connect_timeout_seconds = parse_env_var("DELIVERY_CONNECT_TIMEOUT_SECONDS", DEFAULT_DELIVERY_CONNECT_TIMEOUT_SECONDS)?;
+    if connect_timeout_seconds == 0 {
+        return Err(anyhow::anyhow!("DELIVERY_CONNECT_TIMEOUT_SECONDS must be greater than 0"));
+    }
+    let read_timeout_seconds = parse_optional_env_var("DELIVERY_READ_TIMEOUT_SECONDS")?;
+    if read_timeout_seconds == Some(0) {
+        return Err(anyhow::anyhow!("DELIVERY_READ_TIMEOUT_SECONDS must be greater than 0"));
+    }
+    let max_idle_connections_per_host = parse_env_var("DELIVERY_MAX_IDLE_CONNECTIONS_PER_HOST", DEFAULT_DELIVERY_MAX_IDLE_CONNECTIONS_PER_HOST)?;
+    let http_proxy = parse_proxy_env_var("HTTP_PROXY");
+    let https_proxy = parse_proxy_env_var("HTTPS_PROXY");
+    let no_proxy = parse_proxy_env_var("NO_PROXY");
+    let delivery_client_settings = DeliveryClientSettings::new(
+        Duration::from_secs(connect_timeout_seconds),
+        read_timeout_seconds.map(Duration::from_secs),
+        max_idle_connections_per_host as usize,
+    ).with_proxies(http_proxy.as_deref(), https_proxy.as_deref(), no_proxy.as_deref());
+    // Proxy URLs may hold credentials, they are not logged.
+    log::info!("Delivery client: {} (connect timeout: {:?}, read timeout: {:?}, max idle connections per host: {}, HTTP proxy: {}, HTTPS proxy: {})",
+        service::delivery_client::USER_AGENT,
+        delivery_client_settings.connect_timeout(),
+        delivery_client_settings.read_timeout(),
+        delivery_client_settings.max_idle_connections_per_host(),
+        http_proxy.is_some(),
+        https_proxy.is_some());
+
+    let default_tls_profile = ClientTlsProfile::new(
+        env::var("WEBHOOK_TLS_CA_FILE").ok().map(PathBuf::from),
+        env::var("WEBHOOK_TLS_CLIENT_CERT_FILE").ok().map(PathBuf::from),
+        env::var("WEBHOOK_TLS_CLIENT_KEY_FILE").ok().map(PathBuf::from),
+        env::var("WEBHOOK_TLS_MIN_VERSION").ok().as_deref(),
+    ).map_err(|err| anyhow::anyhow!("Invalid default webhook TLS profile: {}", err))?;
+    let tls_profiles = parse_tls_profiles_config_file()?;
+
+    service::delivery_client::set_delivery_client(&delivery_client_settings, &default_tls_profile, &tls_profiles)
+}
+
+// The lowercase variant is the most widespread one.
+fn parse_proxy_env_var(name: &str) -> Option<String> {
+    env::var(name.to_lowercase()).or_else(|_| env::var(name)).ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+fn parse_tls_profiles_config_file() -> anyhow::Result<HashMap<String, ClientTlsProfile>> {
+    let mut tls_profiles = HashMap::new();
+    let Ok(tls_profiles_config_file) = env::var("WEBHOOK_TLS_PROFILES_CONFIG_FILE") else {
+        return Ok(tls_profiles);
+    };
+    log::info!("Reading webhook TLS profiles config file: {}", tls_profiles_config_file);
+
+    let content = read_to_string(&tls_profiles_config_file)
+        .map_err(|err| anyhow::anyhow!("Failed to read config file {}: {}", tls_profiles_config_file, err))?;
+    let roots = YamlLoader::load_from_str(&content)
+        .map_err(|err| anyhow::anyhow!("Failed to parse YAML: {}", err))?;
+    for root in roots {
+        for object in root {
+            let name = object["name"].as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing or invalid value for key: name"))?
+                .to_string();
+            let tls_profile = ClientTlsProfile::try_from(&object)
+                .map_err(|err| anyhow::anyhow!("Invalid TLS profile {}: {}", name, err))?;
+            if tls_profiles.insert(name.clone(), tls_profile).is_some() {
+                return Err(anyhow::anyhow!("Duplicate TLS profile: {}", name));
+            }
+        }
+    }
+
+    log::info!("Webhook TLS profiles: {:?}", tls_profiles.keys().collect::<Vec<_>>());
+    Ok(tls_profiles)
+}
+
+fn parse_cidrs_env_var(name: &str) -> anyhow::Result<Vec<IpNet>> {
+    parse_list_env_var(name).iter()
+        .map(|cidr| cidr.parse::<IpNet>()
+            // A single address is a network of its own.
+            .or_else(|_| cidr.parse::<IpAddr>().map(IpNet::from))
+            .map_err(|err| anyhow::anyhow!("Invalid value for {}: {} ({})", name, cidr, err)))
+        .collect()
+}
+
+fn parse_list_env_var(name: &str) -> Vec<String> {
+    env::var(name).unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_env_var(name: &str, default_value: u64) -> anyhow::Result<u64> {
+    match env::var(name) {
+        Ok(value) => value.parse()
+            .map_err(|err| anyhow::anyhow!("Invalid value for {}: {} ({})", name, value, err)),
+        Err(_) => Ok(default_value),
+    }
+}
+
+fn parse_optional_env_var(name: &str) -> anyhow::Result<Option<u64>> {
+    match env::var(name) {
+        Ok(value) => value.parse()
+            .map(Some)
+            .map_err(|err| anyhow::anyhow!("Invalid value for {}: {} ({})", name, value, err)),
+        Err(_) => Ok(None),
+    }
+}
+
 pub async fn init_http_server() -> anyhow::Result<()> {
     log::info!("Init http server...");
 
-    let app_state_idempotency_map = web::Data::new(IdempotencyMap::new());
-    HttpServer::new(move || {
+    let tls_settings = parse_tls_settings()?;
+    let http_server = HttpServer::new(|| {
         App::new()
+            .wrap(middleware::from_fn(controller::auth::authenticate))
             .wrap(Logger::new("%r - %a - %{User-Agent}i - Response Status Code: %s"))
-            .app_data(app_state_idempotency_map.clone())
+            .app_data(web::JsonConfig::default().error_handler(controller::json_error_handler))
+            .app_data(web::QueryConfig::default().error_handler(controller::query_error_handler))
             .service(controller::webhooks::post_webhooks)
             .service(controller::webhooks::get_webhooks)
             .service(controller::webhooks::get_webhook_by_id)
+            .service(controller::webhooks::post_webhook_test)
             .service(controller::job_done_watchers::post_job_done_watchers)
             .service(controller::job_done_watchers::get_job_done_watchers)
             .service(controller::job_done_watchers::get_job_done_watcher)
-    }).bind(("0.0.0.0", 8080))?
-        .run()
-        .await?;
+            .service(controller::job_done_watchers::post_job_done_watcher_redeliver)
+            .service(controller::job_done_watchers::post_job_done_trigger_webhook_redeliver)
+            .service(controller::job_done_watchers::get_job_done_watcher_attempts)
+            .service(controller::job_done_watchers::get_job_done_watcher_wait)
+            .service(controller::job_done_watchers::get_job_wait)
+            .service(controller::events::get_job_done_watcher_events)
+            .service(controller::events::get_events)
+            .service(controller::dead_letters::get_dead_letters)
+            .service(controller::dead_letters::post_dead_letters_redeliver)
+            .service(controller::dead_letters::get_dead_letter)
+            .service(controller::dead_letters::delete_dead_letter)
+            .service(controller::job_family_watchers::get_job_family_deliveries)
+            .service(controller::admin::post_purge)
+            .service(controller::admin::get_destinations)
+            .service(controller::admin::post_api_keys)
+            .service(controller::admin::get_api_keys)
+            .service(controller::admin::delete_api_key)
+            .service(controller::metrics::get_metrics)
+    });
+
+    match tls_settings {
+        Some(tls_settings) => {
+            let interval_seconds = parse_env_var("TLS_RELOAD_INTERVAL_SECONDS", DEFAULT_TLS_RELOAD_INTERVAL_SECONDS)?;
+            if interval_seconds == 0 {
+                return Err(anyhow::anyhow!("TLS_RELOAD_INTERVAL_SECONDS must be greater than 0"));
+            }
+
+            let ssl_acceptor_builder = service::tls::build_ssl_acceptor(&tls_settings)?;
+            service::tls::spawn_tls_reload_task(tls_settings, Duration::from_secs(interval_seconds));
+            http_server.bind_openssl(("0.0.0.0", 8080), ssl_acceptor_builder)?
+                .run()
+                .await?;
+        },
+        None => {
+            http_server.bind(("0.0.0.0", 8080))?
+                .run()
+                .await?;
+        },
+    }
 
     Ok(())
+}
+
+fn parse_tls_settings() -> anyhow::Result<Option<TlsSettings>> {
+    let client_ca_file = env::var("TLS_CLIENT_CA_FILE").ok().map(PathBuf::from);
+    match (env::var("TLS_CERT_FILE"), env::var("TLS_KEY_FILE")) {
+        (Ok(cert_file), Ok(key_file)) => {
+            log::info!("Serving HTTPS with certificate {} (client certificates required: {})", cert_file, client_ca_file.is_some());
+            Ok(Some(TlsSettings::new(PathBuf::from(cert_file), PathBuf::from(key_file), client_ca_file)))
+        },
+        (Err(_), Err(_)) if client_ca_file.is_none() => Ok(None),
+        _ => Err(anyhow::anyhow!("TLS_CERT_FILE and TLS_KEY_FILE must be set together, TLS_CLIENT_CA_FILE requires both")),
+    }
 }
\ No newline at end of file
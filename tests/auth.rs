@@ -0,0 +1,80 @@
+use std::sync::Once;
+
+use actix_web::{middleware, test, App};
+use actix_web::http::StatusCode;
+
+use k8s_job_webhooks::models::service::{ApiScope, AuthProvider, AuthSettings, CreateApiKeyRequest};
+use k8s_openapi::serde_json;
+
+use k8s_job_webhooks::{controller, repository, service};
+
+static INIT: Once = Once::new();
+
+fn init() {
+    INIT.call_once(|| {
+        let repository = repository::InMemoryDatabase::new();
+        repository::set_api_key_repository(repository.clone());
+        repository::set_job_done_watcher_repository(repository);
+        service::auth::set_auth_settings(AuthSettings::new(vec![AuthProvider::ApiKey], vec![], vec![], vec![]));
+    });
+}
+
+async fn api_key(name: &str, scopes: Vec<ApiScope>) -> String {
+    let create_api_key_request = CreateApiKeyRequest::new(name, scopes, None).unwrap();
+    service::auth::create_api_key(&create_api_key_request).await.unwrap().key().to_string()
+}
+
+#[actix_web::test]
+async fn admin_scope_is_required_for_encoded_admin_paths() {
+    init();
+    let write_api_key = api_key("writer", vec![ApiScope::Write]).await;
+    let admin_api_key = api_key("admin", vec![ApiScope::Admin]).await;
+
+    let app = test::init_service(App::new()
+        .wrap(middleware::from_fn(controller::auth::authenticate))
+        .service(controller::admin::post_api_keys)).await;
+
+    for path in ["/admin/api-keys", "/%61dmin/api-keys", "/%61%64%6d%69%6e/api-keys"] {
+        let request = test::TestRequest::post()
+            .uri(path)
+            .insert_header(("Authorization", format!("Bearer {}", write_api_key)))
+            .set_json(serde_json::json!({"name": "escalated", "scopes": ["admin"]}))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::FORBIDDEN, "{}", path);
+    }
+
+    let request = test::TestRequest::post()
+        .uri("/%61dmin/api-keys")
+        .insert_header(("Authorization", format!("Bearer {}", admin_api_key)))
+        .set_json(serde_json::json!({"name": "created", "scopes": ["read"]}))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::CREATED);
+}
+
+#[actix_web::test]
+async fn metrics_scope_only_grants_metrics() {
+    init();
+    let metrics_api_key = api_key("prometheus", vec![ApiScope::Metrics]).await;
+    let write_api_key = api_key("writer", vec![ApiScope::Write]).await;
+    let admin_api_key = api_key("admin", vec![ApiScope::Admin]).await;
+
+    let app = test::init_service(App::new()
+        .wrap(middleware::from_fn(controller::auth::authenticate))
+        .service(controller::metrics::get_metrics)
+        .service(controller::admin::get_api_keys)).await;
+
+    for (path, api_key, status) in [
+        ("/metrics", &metrics_api_key, StatusCode::OK),
+        ("/%6Detrics", &metrics_api_key, StatusCode::OK),
+        ("/metrics", &admin_api_key, StatusCode::OK),
+        ("/metrics", &write_api_key, StatusCode::FORBIDDEN),
+        ("/%6Detrics", &write_api_key, StatusCode::FORBIDDEN),
+        ("/admin/api-keys", &metrics_api_key, StatusCode::FORBIDDEN),
+    ] {
+        let request = test::TestRequest::get()
+            .uri(path)
+            .insert_header(("Authorization", format!("Bearer {}", api_key)))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), status, "{}", path);
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::{Duration, Instant};
+
+use url::Url;
+
+use k8s_job_webhooks::models::service::DestinationSettings;
+use k8s_job_webhooks::service::destinations::Destinations;
+
+fn destination_names(destinations: &Destinations) -> Vec<String> {
+    destinations.state().iter()
+        .map(|destination| destination.name().to_string())
+        .collect()
+}
+
+#[actix_web::test]
+async fn idle_destinations_are_evicted_once_their_deliveries_are_done() {
+    let destinations = Destinations::new(DestinationSettings::new(Some(10.0), 10, Some(3), Duration::from_secs(30), Duration::from_secs(60)));
+
+    let busy = destinations.acquire(&Url::parse("https://busy.example.com/hook").unwrap(), None).await;
+    drop(destinations.acquire(&Url::parse("http://idle.example.com:8080/hook").unwrap(), None).await);
+    assert_eq!(destination_names(&destinations), ["busy.example.com:443", "idle.example.com:8080"]);
+
+    destinations.evict_idle(Instant::now());
+    assert_eq!(destination_names(&destinations), ["busy.example.com:443", "idle.example.com:8080"]);
+
+    // A host is kept while a delivery holds its permit.
+    destinations.evict_idle(Instant::now() + Duration::from_secs(120));
+    assert_eq!(destination_names(&destinations), ["busy.example.com:443"]);
+
+    drop(busy);
+    destinations.evict_idle(Instant::now() + Duration::from_secs(120));
+    assert!(destination_names(&destinations).is_empty());
+}
+
+#[actix_web::test]
+async fn idle_destinations_are_kept_until_their_rate_limit_is_refilled() {
+    // A token every 1000 seconds.
+    let destinations = Destinations::new(DestinationSettings::new(Some(0.001), 1, None, Duration::from_secs(30), Duration::from_secs(60)));
+
+    drop(destinations.acquire(&Url::parse("https://slow.example.com/hook").unwrap(), None).await);
+
+    destinations.evict_idle(Instant::now() + Duration::from_secs(120));
+    assert_eq!(destination_names(&destinations), ["slow.example.com:443"]);
+
+    destinations.evict_idle(Instant::now() + Duration::from_secs(1200));
+    assert!(destination_names(&destinations).is_empty());
+}
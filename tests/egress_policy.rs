@@ -0,0 +1,108 @@
+use std::net::IpAddr;
+
+use ipnet::IpNet;
+use url::Url;
+
+use k8s_job_webhooks::models::service::{EgressError, EgressPolicy};
+use k8s_job_webhooks::setup::DEFAULT_EGRESS_DENIED_CIDRS;
+
+fn cidrs(cidrs: &[&str]) -> Vec<IpNet> {
+    cidrs.iter().map(|cidr| cidr.parse().unwrap()).collect()
+}
+
+fn hosts(hosts: &[&str]) -> Vec<String> {
+    hosts.iter().map(|host| host.to_string()).collect()
+}
+
+fn default_policy() -> EgressPolicy {
+    EgressPolicy::new(vec![], cidrs(&DEFAULT_EGRESS_DENIED_CIDRS), vec![], vec![])
+}
+
+fn address(address: &str) -> IpAddr {
+    address.parse().unwrap()
+}
+
+fn url(url: &str) -> Url {
+    Url::parse(url).unwrap()
+}
+
+#[test]
+fn default_deny_list_blocks_loopback_link_local_and_unspecified_addresses() {
+    let egress_policy = default_policy();
+
+    for denied in ["127.0.0.1", "127.1.2.3", "169.254.169.254", "0.0.0.0", "::1", "fe80::1", "::"] {
+        assert!(matches!(egress_policy.check_address(address(denied)), Err(EgressError::AddressNotAllowed(_))), "{} should be denied", denied);
+    }
+    for allowed in ["93.184.216.34", "10.0.0.1", "2606:4700::1"] {
+        assert!(egress_policy.check_address(address(allowed)).is_ok(), "{} should be allowed", allowed);
+    }
+    assert!(egress_policy.check_url(&url("http://169.254.169.254/latest/meta-data")).is_err());
+    assert!(egress_policy.check_url(&url("http://[::1]:8080/")).is_err());
+    assert!(egress_policy.check_url(&url("https://hooks.example.com/")).is_ok());
+}
+
+#[test]
+fn ipv4_mapped_ipv6_addresses_are_checked_as_ipv4() {
+    let egress_policy = default_policy();
+
+    assert!(matches!(
+        egress_policy.check_address(address("::ffff:169.254.169.254")),
+        Err(EgressError::AddressNotAllowed(denied)) if denied == address("169.254.169.254")
+    ));
+    assert!(egress_policy.check_address(address("::ffff:127.0.0.1")).is_err());
+    assert!(egress_policy.check_url(&url("http://[::ffff:169.254.169.254]/latest/meta-data")).is_err());
+    assert!(egress_policy.check_address(address("::ffff:93.184.216.34")).is_ok());
+
+    let egress_policy = EgressPolicy::new(cidrs(&["10.0.0.0/8"]), vec![], vec![], vec![]);
+    assert!(egress_policy.check_address(address("::ffff:10.1.2.3")).is_ok());
+    assert!(egress_policy.check_address(address("::ffff:192.168.1.1")).is_err());
+}
+
+#[test]
+fn wildcard_hosts_only_match_subdomains() {
+    let egress_policy = EgressPolicy::new(vec![], vec![], hosts(&["*.example.com"]), vec![]);
+
+    assert!(egress_policy.check_host("hooks.example.com").is_ok());
+    assert!(egress_policy.check_host("a.b.example.com").is_ok());
+    assert!(egress_policy.check_host("HOOKS.Example.COM").is_ok());
+    for denied in ["example.com", "evil-example.com", "evilexample.com", "example.com.evil.io"] {
+        assert!(matches!(egress_policy.check_host(denied), Err(EgressError::HostNotAllowed(_))), "{} should be denied", denied);
+    }
+
+    let egress_policy = EgressPolicy::new(vec![], vec![], vec![], hosts(&["*.internal", "metadata.google.internal"]));
+    assert!(egress_policy.check_host("db.internal").is_err());
+    assert!(egress_policy.check_host("evil-internal").is_ok());
+    assert!(egress_policy.check_host("hooks.example.com").is_ok());
+}
+
+#[test]
+fn trailing_dot_of_a_host_is_ignored() {
+    let egress_policy = EgressPolicy::new(vec![], vec![], hosts(&["hooks.example.com"]), vec![]);
+    assert!(egress_policy.check_host("hooks.example.com.").is_ok());
+    assert!(egress_policy.check_url(&url("https://hooks.example.com./notify")).is_ok());
+
+    let egress_policy = EgressPolicy::new(vec![], vec![], vec![], hosts(&["metadata.google.internal", "*.corp"]));
+    assert!(egress_policy.check_host("metadata.google.internal.").is_err());
+    assert!(egress_policy.check_host("hooks.corp.").is_err());
+    assert!(egress_policy.check_url(&url("http://metadata.google.internal./computeMetadata/v1")).is_err());
+}
+
+#[test]
+fn address_literals_need_an_allowed_cidr_when_hosts_are_allowed() {
+    let egress_policy = EgressPolicy::new(vec![], cidrs(&DEFAULT_EGRESS_DENIED_CIDRS), hosts(&["hooks.example.com"]), vec![]);
+    assert!(egress_policy.check_url(&url("https://hooks.example.com/")).is_ok());
+    assert!(matches!(egress_policy.check_url(&url("http://93.184.216.34/")), Err(EgressError::AddressNotAllowed(_))));
+    assert!(egress_policy.check_url(&url("http://[2606:4700::1]/")).is_err());
+
+    let egress_policy = EgressPolicy::new(
+        cidrs(&["93.184.216.0/24", "127.0.0.0/8"]),
+        cidrs(&DEFAULT_EGRESS_DENIED_CIDRS),
+        hosts(&["hooks.example.com"]),
+        vec![],
+    );
+    assert!(egress_policy.check_url(&url("http://93.184.216.34/")).is_ok());
+    assert!(egress_policy.check_url(&url("http://[::ffff:93.184.216.34]/")).is_ok());
+    assert!(egress_policy.check_url(&url("http://93.184.217.1/")).is_err());
+    // Denied addresses stay denied, even within an allowed CIDR.
+    assert!(egress_policy.check_url(&url("http://127.0.0.1/")).is_err());
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Once};
+use std::thread;
+use std::time::Duration;
+
+use actix_web::http::StatusCode;
+use actix_web::{web, App, HttpResponse, HttpServer};
+use chrono::Utc;
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::serde_json::{self, json};
+use uuid::Uuid;
+
+use k8s_job_webhooks::models::service::{ClientTlsProfile, CreateJobDoneTriggerWebhookRequest, CreateJobDoneWatcherRequest, DeadLetterFilter, DeliveryClientSettings, DestinationSettings, EgressPolicy, HttpUrl, JobDoneTriggerWebhook, JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherStatus, JobName, Namespace, PageRequest, SortOrder, WaitTimeout, Webhook};
+use k8s_job_webhooks::{repository, service};
+
+static INIT: Once = Once::new();
+
+fn init() {
+    INIT.call_once(|| {
+        let repository = repository::InMemoryDatabase::new();
+        repository::set_webhook_repository(repository.clone());
+        repository::set_dead_letter_repository(repository.clone());
+        repository::set_job_done_watcher_repository(repository);
+        // The receivers of these tests listen on the loopback interface.
+        service::egress::set_egress_policy(EgressPolicy::new(vec![], vec![], vec![], vec![]));
+        let default_tls_profile = ClientTlsProfile::new(None, None, None, None).unwrap();
+        service::delivery_client::set_delivery_client(&DeliveryClientSettings::new(Duration::from_secs(1), None, 1), &default_tls_profile, &HashMap::new()).unwrap();
+        // Deliveries outlive the runtime of the test submitting them, so the pool runs on a runtime of its own.
+        let (started, wait_started) = mpsc::channel();
+        thread::spawn(move || actix_web::rt::System::new().block_on(async move {
+            service::delivery_pool::spawn_delivery_pool(4, Duration::from_secs(5), 2, Duration::ZERO);
+            started.send(()).unwrap();
+            future::pending::<()>().await
+        }));
+        wait_started.recv().unwrap();
+        service::destinations::set_destination_settings(DestinationSettings::new(None, 10, None, Duration::from_secs(30), Duration::from_secs(600)));
+    });
+}
+
+/// Starts a receiver answering every request with `status` and `body`, counting the requests in `received`.
+fn spawn_receiver(status: StatusCode, body: &'static str, received: Arc<AtomicUsize>) -> String {
+    let server = HttpServer::new(move || {
+        let received = received.clone();
+        App::new().default_service(web::to(move || {
+            received.fetch_add(1, Ordering::SeqCst);
+            async move { HttpResponse::build(status).body(body) }
+        }))
+    })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+    let address = server.addrs()[0];
+    actix_web::rt::spawn(server.run());
+    format!("http://{}/hook", address)
+}
+
+fn succeeded_job(job_name: &str, namespace: &str) -> Job {
+    serde_json::from_value(json!({
+        "metadata": { "name": job_name, "namespace": namespace },
+        "status": { "conditions": [{ "type": "Complete", "status": "True" }] }
+    })).unwrap()
+}
+
+async fn create_watcher(job_name: &str, status: JobDoneWatcherStatus) -> Uuid {
+    let job_done_watcher = JobDoneWatcher::new(Uuid::new_v4(), JobName::new(job_name).unwrap(), None, 1, vec![], status, Utc::now());
+    repository::get_job_done_watcher_repository().create_watcher(&job_done_watcher).await.unwrap();
+    job_done_watcher.id()
+}
+
+async fn watcher_status(job_done_watcher_id: &Uuid) -> JobDoneWatcherStatus {
+    repository::get_job_done_watcher_repository()
+        .find_watcher_by_id(job_done_watcher_id)
+        .await
+        .unwrap()
+        .expect("JobDoneWatcher should exist")
+        .status()
+}
+
+#[actix_web::test]
+async fn timeout_does_not_overwrite_a_watcher_that_already_fired() {
+    init();
+    let pending = create_watcher("pending-job", JobDoneWatcherStatus::Pending).await;
+    let fired = create_watcher("fired-job", JobDoneWatcherStatus::Processing).await;
+
+    service::job_done_watchers::time_out_job_done_watcher(&pending).await;
+    service::job_done_watchers::time_out_job_done_watcher(&fired).await;
+
+    assert_eq!(watcher_status(&pending).await, JobDoneWatcherStatus::Timeout);
+    assert_eq!(watcher_status(&fired).await, JobDoneWatcherStatus::Processing);
+}
+
+#[actix_web::test]
+async fn server_errors_are_retried_and_dead_lettered() {
+    init();
+    let received = Arc::new(AtomicUsize::new(0));
+    let url = spawn_receiver(StatusCode::INTERNAL_SERVER_ERROR, "receiver unavailable", received.clone());
+    let webhook = Webhook::new(Uuid::new_v4(), HttpUrl::new(&url).unwrap(), "{}", "Failing receiver", Utc::now());
+    repository::get_webhook_repository().create_webhook(&webhook).await.unwrap();
+
+    let job_name = JobName::new("server-error-job").unwrap();
+    let job_done_trigger_webhook = JobDoneTriggerWebhook::new(Uuid::new_v4(), webhook.id(), 5, JobDoneTriggerWebhookStatus::NotCalled, None);
+    let job_done_watcher = JobDoneWatcher::new(Uuid::new_v4(), job_name.clone(), None, 0, vec![job_done_trigger_webhook], JobDoneWatcherStatus::Pending, Utc::now());
+    let job_done_watcher_repository = repository::get_job_done_watcher_repository();
+    job_done_watcher_repository.create_watcher(&job_done_watcher).await.unwrap();
+
+    service::job_done_watchers::notify_job_done_watchers(&job_name, &Namespace::new("default").unwrap()).await;
+    let job_done_watcher = service::job_done_watchers::wait_for_job_done_watcher(&job_done_watcher.id(), WaitTimeout::new(Some(10)).unwrap(), None)
+        .await
+        .unwrap()
+        .expect("JobDoneWatcher should exist");
+
+    assert_eq!(job_done_watcher.status(), JobDoneWatcherStatus::Failed);
+    assert_eq!(received.load(Ordering::SeqCst), 2);
+    let attempts = job_done_watcher_repository.find_job_done_trigger_webhook_attempts(&job_done_watcher.id()).await.unwrap();
+    assert_eq!(attempts.len(), 2);
+    for attempt in &attempts {
+        assert_eq!(attempt.status(), JobDoneTriggerWebhookStatus::Failed);
+        assert_eq!(attempt.response_status_code(), Some(500));
+        assert_eq!(attempt.response_body(), Some("receiver unavailable"));
+    }
+
+    let dead_letter_filter = DeadLetterFilter::new(Some(webhook.id()), None, None, None);
+    let page_request = PageRequest::new(SortOrder::Descending, None, Some(10)).unwrap();
+    let dead_letters = repository::get_dead_letter_repository().find_all_dead_letters(&dead_letter_filter, &page_request).await.unwrap();
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].response_status_code(), Some(500));
+    assert_eq!(dead_letters[0].response_body(), Some("receiver unavailable"));
+    assert_eq!(dead_letters[0].attempts(), 2);
+}
+
+#[actix_web::test]
+async fn tenant_watcher_fires_for_a_job_of_its_namespace() {
+    init();
+    let received = Arc::new(AtomicUsize::new(0));
+    let url = spawn_receiver(StatusCode::OK, "ok", received.clone());
+    let webhook = Webhook::new(Uuid::new_v4(), HttpUrl::new(&url).unwrap(), "{}", "Team A receiver", Utc::now())
+        .with_tenant(Some("team-a"));
+    repository::get_webhook_repository().create_webhook(&webhook).await.unwrap();
+
+    let create_job_done_watcher_request = CreateJobDoneWatcherRequest::new(
+        "team-a-report",
+        Some("team-a"),
+        0,
+        vec![CreateJobDoneTriggerWebhookRequest::new(&webhook.id().to_string(), 5).unwrap()],
+    ).unwrap();
+    let job_done_watcher = service::job_done_watchers::create_job_done_watcher(create_job_done_watcher_request, Some("team-a")).await.unwrap();
+
+    let processed_job = service::k8s_job_watcher::process_job(&succeeded_job("team-a-report", "team-a")).await
+        .map(|(job_name, namespace)| (job_name.to_string(), namespace.to_string()));
+    assert_eq!(processed_job, Some(("team-a-report".to_string(), "team-a".to_string())));
+
+    let job_done_watcher = service::job_done_watchers::wait_for_job_done_watcher(&job_done_watcher.id(), WaitTimeout::new(Some(10)).unwrap(), Some("team-a"))
+        .await
+        .unwrap()
+        .expect("JobDoneWatcher should exist");
+    assert_eq!(job_done_watcher.status(), JobDoneWatcherStatus::Completed);
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+}
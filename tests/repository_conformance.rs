@@ -0,0 +1,1203 @@
+use std::collections::HashSet;
+use std::env;
+
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use futures_util::future::join_all;
+use uuid::Uuid;
+
+use k8s_job_webhooks::models::service::{ApiKey, ApiScope, DeadLetter, DeadLetterFilter, IdempotencyKey, JobDoneTriggerWebhook, JobDoneTriggerWebhookAttempt, JobDoneTriggerWebhookStatus, JobDoneWatcher, JobDoneWatcherFilter, JobDoneWatcherStatus, JobFamilyDelivery, JobFamilyDeliveryFilter, JobFamilyDeliveryStatus, JobFamilyState, JobFamilyWatcher, JobFamilyWatcherConditions, JobName, JobOutcome, MAX_PAGE_LIMIT, MAX_TENANT_LENGTH, Namespace, PageCursor, PageRequest, RenderedWebhookRequest, SortOrder, Webhook, WebhookFilter};
+use k8s_job_webhooks::repository::{ApiKeyRepository, DeadLetterRepository, IdempotencyKeyRepository, InMemoryDatabase, JobDoneWatcherRepository, JobFamilyWatcherRepository, MySqlDatabase, PostgresDatabase, SqliteDatabase, SqlxAcquire, WebhookRepository};
+
+trait Repositories: ApiKeyRepository + DeadLetterRepository + IdempotencyKeyRepository + WebhookRepository + JobDoneWatcherRepository + JobFamilyWatcherRepository {}
+
+impl<T: ApiKeyRepository + DeadLetterRepository + IdempotencyKeyRepository + WebhookRepository + JobDoneWatcherRepository + JobFamilyWatcherRepository> Repositories for T {}
+
+macro_rules! conformance_suite {
+    ($backend:ident, $repository:expr $(, ignore = $reason:literal)?) => {
+        mod $backend {
+            use super::*;
+
+            conformance_suite!(@tests $repository, [$($reason)?],
+                webhooks_are_created_and_found,
+                watchers_are_created_with_their_triggers,
+                watchers_without_triggers_have_no_triggers,
+                watchers_referencing_unknown_webhooks_are_rejected,
+                watchers_are_found_by_job_name_and_status,
+                watcher_status_is_updated,
+                timeout_cascades_to_triggers,
+                conditional_status_update_only_applies_to_expected_status,
+                watchers_are_claimed_by_job_name_and_status,
+                concurrent_claims_return_each_watcher_once,
+                trigger_status_and_called_at_are_updated,
+                trigger_attempts_are_recorded_and_purged_with_their_watcher,
+                dead_letters_are_saved_filtered_and_deleted,
+                api_keys_are_created_found_by_hash_and_deleted,
+                job_family_watchers_are_created_and_found,
+                job_family_watchers_are_upserted_by_name,
+                job_family_state_is_replaced_only_if_unchanged,
+                job_family_deliveries_are_recorded_and_filtered,
+                expired_watchers_are_counted_and_deleted_in_batches,
+                expired_job_family_deliveries_are_counted_and_deleted_in_batches,
+                watchers_are_claimed_in_their_namespace,
+                watchers_are_filtered,
+                watchers_are_paginated_in_both_orders,
+                webhooks_are_filtered_and_paginated,
+                resources_are_scoped_by_tenant,
+                idempotency_keys_are_inserted_once_until_expired,
+                idempotency_keys_are_completed_and_released,
+                stale_idempotency_key_claims_are_taken_over,
+                idempotency_keys_are_scoped_by_tenant,
+                expired_idempotency_keys_are_counted_and_deleted_in_batches
+            );
+        }
+    };
+    (@tests $repository:expr, [], $($test:ident),+) => {
+        $(
+            #[actix_web::test]
+            async fn $test() {
+                super::$test(&$repository.await).await;
+            }
+        )+
+    };
+    // Backends needing an external database only run with `--ignored`, so they are reported as ignored, not passed.
+    (@tests $repository:expr, [$reason:literal], $($test:ident),+) => {
+        $(
+            #[actix_web::test]
+            #[ignore = $reason]
+            async fn $test() {
+                super::$test(&$repository.await).await;
+            }
+        )+
+    };
+}
+
+conformance_suite!(in_memory, in_memory_database());
+conformance_suite!(sqlite_memory, sqlite_memory_database());
+conformance_suite!(sqlite_file, sqlite_file_database());
+conformance_suite!(postgres, postgres_database(), ignore = "needs a PostgreSQL database in TEST_POSTGRES_URL");
+conformance_suite!(mysql, mysql_database(), ignore = "needs a MySQL/MariaDB database in TEST_MYSQL_URL");
+
+async fn in_memory_database() -> InMemoryDatabase {
+    InMemoryDatabase::new()
+}
+
+async fn sqlite_memory_database() -> SqliteDatabase {
+    let repository = SqliteDatabase::connect_in_memory("sqlite::memory:").await.unwrap();
+    let mut conn = repository.acquire().await.unwrap();
+    sqlx::migrate!("migrations/sqlite").run(&mut *conn).await.unwrap();
+    drop(conn);
+    repository
+}
+
+async fn sqlite_file_database() -> SqliteDatabase {
+    let path = env::temp_dir().join(format!("k8s-job-webhooks-{}.db", Uuid::new_v4()));
+    let repository = SqliteDatabase::connect(&format!("sqlite://{}?mode=rwc", path.display())).await.unwrap();
+    let mut conn = repository.acquire().await.unwrap();
+    sqlx::migrate!("migrations/sqlite").run(&mut *conn).await.unwrap();
+    drop(conn);
+    repository
+}
+
+async fn postgres_database() -> PostgresDatabase {
+    let url = env::var("TEST_POSTGRES_URL").expect("TEST_POSTGRES_URL should point to a PostgreSQL database");
+    let repository = PostgresDatabase::connect(&url).await.unwrap();
+    let mut conn = repository.acquire().await.unwrap();
+    sqlx::migrate!("migrations/postgres").run(&mut *conn).await.unwrap();
+    drop(conn);
+    repository
+}
+
+async fn mysql_database() -> MySqlDatabase {
+    let url = env::var("TEST_MYSQL_URL").expect("TEST_MYSQL_URL should point to a MySQL/MariaDB database");
+    let repository = MySqlDatabase::connect(&url).await.unwrap();
+    let mut conn = repository.acquire().await.unwrap();
+    sqlx::migrate!("migrations/mysql").run(&mut *conn).await.unwrap();
+    drop(conn);
+    repository
+}
+
+fn now() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(0)
+}
+
+fn unique_job_name() -> JobName {
+    JobName::new(&format!("job-{}", Uuid::new_v4())).unwrap()
+}
+
+fn namespace(name: &str) -> Namespace {
+    Namespace::new(name).unwrap()
+}
+
+fn page_request(sort_order: SortOrder, after: Option<PageCursor>, limit: u32) -> PageRequest {
+    PageRequest::new(sort_order, after.map(|after| after.to_string()).as_deref(), Some(limit)).unwrap()
+}
+
+fn watcher_ids(job_done_watchers: &[JobDoneWatcher]) -> Vec<Uuid> {
+    job_done_watchers.iter().map(JobDoneWatcher::id).collect()
+}
+
+async fn create_webhook(repository: &impl Repositories) -> Webhook {
+    let webhook = Webhook::new(
+        Uuid::new_v4(),
+        "http://receiver.example.com/hook".parse().unwrap(),
+        "{\"hello\":\"world\"}",
+        "conformance webhook",
+        now(),
+    );
+    repository.create_webhook(&webhook).await.unwrap();
+    webhook
+}
+
+async fn create_watcher(repository: &impl Repositories, job_name: &JobName, webhooks: &[&Webhook]) -> JobDoneWatcher {
+    let job_done_watcher = JobDoneWatcher::new(
+        Uuid::new_v4(),
+        job_name.clone(),
+        None,
+        60,
+        webhooks.iter()
+            .map(|webhook| JobDoneTriggerWebhook::new(Uuid::new_v4(), webhook.id(), 5, JobDoneTriggerWebhookStatus::NotCalled, None))
+            .collect(),
+        JobDoneWatcherStatus::Pending,
+        now(),
+    );
+    repository.create_watcher(&job_done_watcher).await.unwrap();
+    job_done_watcher
+}
+
+async fn find_watcher(repository: &impl Repositories, id: Uuid) -> JobDoneWatcher {
+    repository.find_watcher_by_id(&id).await.unwrap().expect("watcher should exist")
+}
+
+fn trigger_statuses(job_done_watcher: &JobDoneWatcher) -> Vec<JobDoneTriggerWebhookStatus> {
+    job_done_watcher.job_done_trigger_webhooks().iter().map(|trigger| *trigger.status()).collect()
+}
+
+async fn webhooks_are_created_and_found(repository: &impl Repositories) {
+    let webhook = create_webhook(repository).await;
+
+    let found = repository.find_webhook_by_id(&webhook.id()).await.unwrap().expect("webhook should exist");
+    assert_eq!(found.id(), webhook.id());
+    assert_eq!(found.url().to_string(), webhook.url().to_string());
+    assert_eq!(found.request_body(), webhook.request_body());
+    assert_eq!(found.description(), webhook.description());
+    assert_eq!(found.created_at(), webhook.created_at());
+    assert_eq!(found.tls_profile(), None);
+    assert_eq!(found.rate_limit_per_second(), None);
+
+    let webhook_filter = WebhookFilter::new(Some(webhook.created_at()), Some(webhook.created_at()));
+    let all = repository.find_all_webhooks(&webhook_filter, &page_request(SortOrder::Ascending, None, MAX_PAGE_LIMIT)).await.unwrap();
+    assert!(all.iter().any(|found| found.id() == webhook.id()));
+
+    let mtls_webhook = Webhook::new(Uuid::new_v4(), "https://receiver.example.com/hook".parse().unwrap(), "{}", "mTLS webhook", now())
+        .with_tls_profile(Some("internal"))
+        .with_rate_limit_per_second(Some(0.5));
+    repository.create_webhook(&mtls_webhook).await.unwrap();
+    let found = repository.find_webhook_by_id(&mtls_webhook.id()).await.unwrap().expect("webhook should exist");
+    assert_eq!(found.tls_profile(), Some("internal"));
+    assert_eq!(found.rate_limit_per_second(), Some(0.5));
+
+    assert!(repository.find_webhook_by_id(&Uuid::new_v4()).await.unwrap().is_none());
+}
+
+async fn watchers_are_created_with_their_triggers(repository: &impl Repositories) {
+    let first_webhook = create_webhook(repository).await;
+    let second_webhook = create_webhook(repository).await;
+    let job_name = unique_job_name();
+    let job_done_watcher = create_watcher(repository, &job_name, &[&first_webhook, &second_webhook]).await;
+
+    let found = find_watcher(repository, job_done_watcher.id()).await;
+    assert_eq!(found.job_name(), job_done_watcher.job_name());
+    assert_eq!(found.timeout_seconds(), 60);
+    assert_eq!(found.status(), JobDoneWatcherStatus::Pending);
+    assert_eq!(found.created_at(), job_done_watcher.created_at());
+
+    let expected: HashSet<(Uuid, Uuid)> = job_done_watcher.job_done_trigger_webhooks().iter()
+        .map(|trigger| (trigger.id(), trigger.webhook_id()))
+        .collect();
+    let actual: HashSet<(Uuid, Uuid)> = found.job_done_trigger_webhooks().iter()
+        .map(|trigger| (trigger.id(), trigger.webhook_id()))
+        .collect();
+    assert_eq!(actual, expected);
+
+    for trigger in found.job_done_trigger_webhooks() {
+        assert_eq!(trigger.timeout_seconds(), 5);
+        assert_eq!(*trigger.status(), JobDoneTriggerWebhookStatus::NotCalled);
+        assert!(trigger.called_at().is_none());
+    }
+
+    let job_done_watcher_filter = JobDoneWatcherFilter::new(Some(job_name.clone()), None, None, None, None, None);
+    let all = repository.find_all_watchers(&job_done_watcher_filter, &PageRequest::default()).await.unwrap();
+    let listed = all.iter().find(|found| found.id() == job_done_watcher.id()).expect("watcher should be listed");
+    assert_eq!(listed.job_done_trigger_webhooks().len(), 2);
+}
+
+async fn watchers_without_triggers_have_no_triggers(repository: &impl Repositories) {
+    let job_done_watcher = create_watcher(repository, &unique_job_name(), &[]).await;
+
+    let found = find_watcher(repository, job_done_watcher.id()).await;
+    assert!(found.job_done_trigger_webhooks().is_empty());
+}
+
+async fn watchers_referencing_unknown_webhooks_are_rejected(repository: &impl Repositories) {
+    let job_done_watcher = JobDoneWatcher::new(
+        Uuid::new_v4(),
+        unique_job_name(),
+        None,
+        0,
+        vec![JobDoneTriggerWebhook::new(Uuid::new_v4(), Uuid::new_v4(), 0, JobDoneTriggerWebhookStatus::NotCalled, None)],
+        JobDoneWatcherStatus::Pending,
+        now(),
+    );
+
+    assert!(repository.create_watcher(&job_done_watcher).await.is_err());
+    assert!(repository.find_watcher_by_id(&job_done_watcher.id()).await.unwrap().is_none());
+}
+
+async fn watchers_are_found_by_job_name_and_status(repository: &impl Repositories) {
+    let webhook = create_webhook(repository).await;
+    let job_name = unique_job_name();
+    let pending = create_watcher(repository, &job_name, &[&webhook]).await;
+    let processing = create_watcher(repository, &job_name, &[&webhook]).await;
+    create_watcher(repository, &unique_job_name(), &[&webhook]).await;
+    repository.update_watcher_status(&processing.id(), JobDoneWatcherStatus::Processing).await.unwrap();
+
+    let found = repository.find_all_watchers_by_job_name_and_status(&job_name, JobDoneWatcherStatus::Pending).await.unwrap();
+    assert_eq!(found.iter().map(JobDoneWatcher::id).collect::<Vec<_>>(), vec![pending.id()]);
+    assert_eq!(found[0].job_done_trigger_webhooks().len(), 1);
+
+    let found = repository.find_all_watchers_by_job_name_and_status(&job_name, JobDoneWatcherStatus::Processing).await.unwrap();
+    assert_eq!(found.iter().map(JobDoneWatcher::id).collect::<Vec<_>>(), vec![processing.id()]);
+}
+
+async fn watcher_status_is_updated(repository: &impl Repositories) {
+    let job_done_watcher = create_watcher(repository, &unique_job_name(), &[]).await;
+
+    for status in [JobDoneWatcherStatus::Processing, JobDoneWatcherStatus::PartiallyCompleted, JobDoneWatcherStatus::Completed] {
+        repository.update_watcher_status(&job_done_watcher.id(), status).await.unwrap();
+        assert_eq!(find_watcher(repository, job_done_watcher.id()).await.status(), status);
+    }
+
+    repository.update_watcher_status(&Uuid::new_v4(), JobDoneWatcherStatus::Completed).await.unwrap();
+}
+
+async fn timeout_cascades_to_triggers(repository: &impl Repositories) {
+    let first_webhook = create_webhook(repository).await;
+    let second_webhook = create_webhook(repository).await;
+    let timed_out = create_watcher(repository, &unique_job_name(), &[&first_webhook, &second_webhook]).await;
+    let untouched = create_watcher(repository, &unique_job_name(), &[&first_webhook]).await;
+
+    repository.update_watcher_status(&timed_out.id(), JobDoneWatcherStatus::Timeout).await.unwrap();
+
+    let found = find_watcher(repository, timed_out.id()).await;
+    assert_eq!(found.status(), JobDoneWatcherStatus::Timeout);
+    assert_eq!(trigger_statuses(&found), vec![JobDoneTriggerWebhookStatus::Timeout; 2]);
+
+    let found = find_watcher(repository, untouched.id()).await;
+    assert_eq!(trigger_statuses(&found), vec![JobDoneTriggerWebhookStatus::NotCalled]);
+
+    let timed_out_by_status = create_watcher(repository, &unique_job_name(), &[&first_webhook]).await;
+    repository.update_watcher_status_by_status(&timed_out_by_status.id(), JobDoneWatcherStatus::Pending, JobDoneWatcherStatus::Timeout).await.unwrap();
+
+    let found = find_watcher(repository, timed_out_by_status.id()).await;
+    assert_eq!(trigger_statuses(&found), vec![JobDoneTriggerWebhookStatus::Timeout]);
+}
+
+async fn count_watchers_with_status(repository: &impl Repositories, status: JobDoneWatcherStatus) -> u64 {
+    let watchers_by_status = repository.count_watchers_by_status().await.unwrap();
+    assert!(watchers_by_status.iter().filter(|(watcher_status, _)| *watcher_status == status).count() <= 1);
+    watchers_by_status.into_iter()
+        .find(|(watcher_status, _)| *watcher_status == status)
+        .map_or(0, |(_, count)| count)
+}
+
+// The only test cancelling watchers, so that their count is not changed concurrently.
+async fn conditional_status_update_only_applies_to_expected_status(repository: &impl Repositories) {
+    let cancelled_count = count_watchers_with_status(repository, JobDoneWatcherStatus::Cancelled).await;
+    let job_done_watcher = create_watcher(repository, &unique_job_name(), &[]).await;
+
+    assert!(!repository.update_watcher_status_by_status(&job_done_watcher.id(), JobDoneWatcherStatus::Processing, JobDoneWatcherStatus::Completed).await.unwrap());
+    assert_eq!(find_watcher(repository, job_done_watcher.id()).await.status(), JobDoneWatcherStatus::Pending);
+
+    assert!(repository.update_watcher_status_by_status(&job_done_watcher.id(), JobDoneWatcherStatus::Pending, JobDoneWatcherStatus::Cancelled).await.unwrap());
+    assert_eq!(find_watcher(repository, job_done_watcher.id()).await.status(), JobDoneWatcherStatus::Cancelled);
+    assert_eq!(count_watchers_with_status(repository, JobDoneWatcherStatus::Cancelled).await, cancelled_count + 1);
+
+    assert!(!repository.update_watcher_status_by_status(&job_done_watcher.id(), JobDoneWatcherStatus::Pending, JobDoneWatcherStatus::Timeout).await.unwrap());
+    assert_eq!(find_watcher(repository, job_done_watcher.id()).await.status(), JobDoneWatcherStatus::Cancelled);
+
+    assert!(!repository.update_watcher_status_by_status(&Uuid::new_v4(), JobDoneWatcherStatus::Pending, JobDoneWatcherStatus::Timeout).await.unwrap());
+}
+
+async fn watchers_are_claimed_by_job_name_and_status(repository: &impl Repositories) {
+    let webhook = create_webhook(repository).await;
+    let job_name = unique_job_name();
+    let first = create_watcher(repository, &job_name, &[&webhook]).await;
+    let second = create_watcher(repository, &job_name, &[]).await;
+    let other = create_watcher(repository, &unique_job_name(), &[&webhook]).await;
+
+    let claimed = repository
+        .update_watchers_status_by_job_name_and_status(&job_name, &namespace("default"), JobDoneWatcherStatus::Pending, JobDoneWatcherStatus::Processing)
+        .await
+        .unwrap();
+
+    let claimed_ids: HashSet<Uuid> = claimed.iter().map(JobDoneWatcher::id).collect();
+    assert_eq!(claimed_ids, HashSet::from([first.id(), second.id()]));
+    for job_done_watcher in &claimed {
+        assert_eq!(job_done_watcher.status(), JobDoneWatcherStatus::Processing);
+        let expected_triggers = if job_done_watcher.id() == first.id() { 1 } else { 0 };
+        assert_eq!(job_done_watcher.job_done_trigger_webhooks().len(), expected_triggers);
+    }
+
+    assert_eq!(find_watcher(repository, other.id()).await.status(), JobDoneWatcherStatus::Pending);
+
+    let claimed_again = repository
+        .update_watchers_status_by_job_name_and_status(&job_name, &namespace("default"), JobDoneWatcherStatus::Pending, JobDoneWatcherStatus::Processing)
+        .await
+        .unwrap();
+    assert!(claimed_again.is_empty());
+}
+
+async fn concurrent_claims_return_each_watcher_once(repository: &impl Repositories) {
+    let job_name = unique_job_name();
+    let mut expected = HashSet::new();
+    for _ in 0..8 {
+        expected.insert(create_watcher(repository, &job_name, &[]).await.id());
+    }
+
+    let default_namespace = namespace("default");
+    let claims = join_all((0..8).map(|_| {
+        repository.update_watchers_status_by_job_name_and_status(&job_name, &default_namespace, JobDoneWatcherStatus::Pending, JobDoneWatcherStatus::Processing)
+    })).await;
+
+    let claimed: Vec<Uuid> = claims.into_iter()
+        .flat_map(|claim| claim.unwrap())
+        .map(|job_done_watcher| job_done_watcher.id())
+        .collect();
+    assert_eq!(claimed.len(), expected.len());
+    assert_eq!(claimed.into_iter().collect::<HashSet<_>>(), expected);
+}
+
+async fn trigger_status_and_called_at_are_updated(repository: &impl Repositories) {
+    let first_webhook = create_webhook(repository).await;
+    let second_webhook = create_webhook(repository).await;
+    let job_done_watcher = create_watcher(repository, &unique_job_name(), &[&first_webhook, &second_webhook]).await;
+    let called = &job_done_watcher.job_done_trigger_webhooks()[0];
+    let called_at = now();
+
+    repository.update_job_done_trigger_webhook_status_and_called_at(
+        &job_done_watcher.id(),
+        &called.id(),
+        JobDoneTriggerWebhookStatus::Called,
+        called_at,
+    ).await.unwrap();
+
+    let found = find_watcher(repository, job_done_watcher.id()).await;
+    for trigger in found.job_done_trigger_webhooks() {
+        if trigger.id() == called.id() {
+            assert_eq!(*trigger.status(), JobDoneTriggerWebhookStatus::Called);
+            assert_eq!(trigger.called_at(), Some(called_at));
+        } else {
+            assert_eq!(*trigger.status(), JobDoneTriggerWebhookStatus::NotCalled);
+            assert!(trigger.called_at().is_none());
+        }
+    }
+
+    let other_watcher = create_watcher(repository, &unique_job_name(), &[&first_webhook]).await;
+    repository.update_job_done_trigger_webhook_status_and_called_at(
+        &other_watcher.id(),
+        &called.id(),
+        JobDoneTriggerWebhookStatus::Failed,
+        called_at,
+    ).await.unwrap();
+
+    let found = find_watcher(repository, job_done_watcher.id()).await;
+    let trigger = found.job_done_trigger_webhooks().iter().find(|trigger| trigger.id() == called.id()).unwrap();
+    assert_eq!(*trigger.status(), JobDoneTriggerWebhookStatus::Called);
+}
+
+async fn trigger_attempts_are_recorded_and_purged_with_their_watcher(repository: &impl Repositories) {
+    let cutoff = expiry_cutoff();
+    let webhook = create_webhook(repository).await;
+    let job_done_watcher = JobDoneWatcher::new(
+        Uuid::new_v4(),
+        unique_job_name(),
+        None,
+        60,
+        vec![JobDoneTriggerWebhook::new(Uuid::new_v4(), webhook.id(), 5, JobDoneTriggerWebhookStatus::Called, Some(cutoff - Duration::hours(2)))],
+        JobDoneWatcherStatus::PartiallyCompleted,
+        cutoff - Duration::hours(2),
+    );
+    repository.create_watcher(&job_done_watcher).await.unwrap();
+    let job_done_trigger_webhook_id = job_done_watcher.job_done_trigger_webhooks()[0].id();
+    assert!(repository.find_job_done_trigger_webhook_attempts(&job_done_watcher.id()).await.unwrap().is_empty());
+
+    let request = RenderedWebhookRequest::new(
+        "POST",
+        webhook.url().as_str(),
+        vec![("content-type".to_string(), "application/json".to_string())],
+        webhook.request_body(),
+    );
+    let attempts = [
+        JobDoneTriggerWebhookAttempt::new(
+            Uuid::new_v4(),
+            job_done_watcher.id(),
+            job_done_trigger_webhook_id,
+            JobDoneTriggerWebhookStatus::Failed,
+            None,
+            Some("connection refused".to_string()),
+            cutoff - Duration::hours(2),
+        ).with_request(Some(request.clone())),
+        JobDoneTriggerWebhookAttempt::new(
+            Uuid::new_v4(),
+            job_done_watcher.id(),
+            job_done_trigger_webhook_id,
+            JobDoneTriggerWebhookStatus::Called,
+            Some(200),
+            None,
+            cutoff - Duration::hours(1),
+        ).with_request(Some(request))
+         .with_response(Some(vec![("x-request-id".to_string(), "42".to_string())]), Some("accepted".to_string())),
+    ];
+    for attempt in attempts.iter().rev() {
+        repository.insert_job_done_trigger_webhook_attempt(attempt).await.unwrap();
+    }
+
+    let found = repository.find_job_done_trigger_webhook_attempts(&job_done_watcher.id()).await.unwrap();
+    assert_eq!(found.iter().map(JobDoneTriggerWebhookAttempt::id).collect::<Vec<_>>(), attempts.iter().map(JobDoneTriggerWebhookAttempt::id).collect::<Vec<_>>());
+    assert_eq!(found[0].job_done_trigger_webhook_id(), job_done_trigger_webhook_id);
+    assert_eq!(found[0].status(), JobDoneTriggerWebhookStatus::Failed);
+    assert_eq!(found[0].error(), Some("connection refused"));
+    assert_eq!(found[0].response_status_code(), None);
+    assert_eq!(found[0].response_headers(), None);
+    assert_eq!(found[0].response_body(), None);
+    let found_request = found[0].request().unwrap();
+    assert_eq!(found_request.method(), "POST");
+    assert_eq!(found_request.url(), webhook.url().as_str());
+    assert_eq!(found_request.headers(), [("content-type".to_string(), "application/json".to_string())]);
+    assert_eq!(found_request.body(), webhook.request_body());
+    assert_eq!(found[1].status(), JobDoneTriggerWebhookStatus::Called);
+    assert_eq!(found[1].response_status_code(), Some(200));
+    assert_eq!(found[1].response_headers(), Some([("x-request-id".to_string(), "42".to_string())].as_slice()));
+    assert_eq!(found[1].response_body(), Some("accepted"));
+    assert_eq!(found[1].attempted_at(), cutoff - Duration::hours(1));
+    assert!(repository.find_job_done_trigger_webhook_attempts(&Uuid::new_v4()).await.unwrap().is_empty());
+
+    let dead_letter = dead_letter(&job_done_watcher, job_done_trigger_webhook_id, webhook.id(), None, cutoff - Duration::hours(1));
+    repository.save_dead_letter(&dead_letter).await.unwrap();
+
+    repository.delete_watchers_by_status_created_before(JobDoneWatcherStatus::PartiallyCompleted, cutoff, u32::MAX).await.unwrap();
+    assert!(repository.find_watcher_by_id(&job_done_watcher.id()).await.unwrap().is_none());
+    assert!(repository.find_job_done_trigger_webhook_attempts(&job_done_watcher.id()).await.unwrap().is_empty());
+    assert!(repository.find_dead_letter_by_id(&dead_letter.id()).await.unwrap().is_none());
+}
+
+fn dead_letter(
+    job_done_watcher: &JobDoneWatcher,
+    job_done_trigger_webhook_id: Uuid,
+    webhook_id: Uuid,
+    request: Option<RenderedWebhookRequest>,
+    created_at: DateTime<Utc>
+) -> DeadLetter {
+    DeadLetter::new(
+        Uuid::new_v4(),
+        job_done_watcher.id(),
+        job_done_trigger_webhook_id,
+        webhook_id,
+        JobName::new(job_done_watcher.job_name()).unwrap(),
+        job_done_watcher.namespace().cloned(),
+        request,
+        None,
+        Some("connection refused".to_string()),
+        1,
+        created_at,
+    )
+}
+
+async fn dead_letters_are_saved_filtered_and_deleted(repository: &impl Repositories) {
+    let first_webhook = create_webhook(repository).await;
+    let second_webhook = create_webhook(repository).await;
+    let job_name = unique_job_name();
+    let job_done_watcher = JobDoneWatcher::new(
+        Uuid::new_v4(),
+        job_name.clone(),
+        Some(namespace("default")),
+        60,
+        vec![
+            JobDoneTriggerWebhook::new(Uuid::new_v4(), first_webhook.id(), 5, JobDoneTriggerWebhookStatus::Failed, Some(now())),
+            JobDoneTriggerWebhook::new(Uuid::new_v4(), second_webhook.id(), 5, JobDoneTriggerWebhookStatus::Failed, Some(now())),
+        ],
+        JobDoneWatcherStatus::Failed,
+        now(),
+    );
+    repository.create_watcher(&job_done_watcher).await.unwrap();
+    let first_trigger_id = job_done_watcher.job_done_trigger_webhooks()[0].id();
+    let second_trigger_id = job_done_watcher.job_done_trigger_webhooks()[1].id();
+
+    let request = RenderedWebhookRequest::new(
+        "POST",
+        first_webhook.url().as_str(),
+        vec![("content-type".to_string(), "application/json".to_string())],
+        first_webhook.request_body(),
+    );
+    let first = dead_letter(&job_done_watcher, first_trigger_id, first_webhook.id(), Some(request), now() - Duration::hours(2));
+    let second = dead_letter(&job_done_watcher, second_trigger_id, second_webhook.id(), None, now() - Duration::hours(1));
+    assert_eq!(repository.save_dead_letter(&first).await.unwrap().id(), first.id());
+    assert_eq!(repository.save_dead_letter(&second).await.unwrap().id(), second.id());
+
+    let found = repository.find_dead_letter_by_id(&first.id()).await.unwrap().unwrap();
+    assert_eq!(found.job_done_watcher_id(), job_done_watcher.id());
+    assert_eq!(found.job_done_trigger_webhook_id(), first_trigger_id);
+    assert_eq!(found.webhook_id(), first_webhook.id());
+    assert_eq!(found.job_name().as_str(), job_name.as_str());
+    assert_eq!(found.namespace(), Some(&namespace("default")));
+    assert_eq!(found.error(), Some("connection refused"));
+    assert_eq!(found.response_status_code(), None);
+    assert_eq!(found.attempts(), 1);
+    assert_eq!(found.created_at(), first.created_at());
+    let found_request = found.request().unwrap();
+    assert_eq!(found_request.method(), "POST");
+    assert_eq!(found_request.url(), first_webhook.url().as_str());
+    assert_eq!(found_request.headers(), [("content-type".to_string(), "application/json".to_string())]);
+    assert_eq!(found_request.body(), first_webhook.request_body());
+    assert!(repository.find_dead_letter_by_id(&second.id()).await.unwrap().unwrap().request().is_none());
+
+    let by_job_name = DeadLetterFilter::new(None, Some(job_name.clone()), None, None);
+    let dead_letter_ids = |dead_letters: Vec<DeadLetter>| dead_letters.iter().map(DeadLetter::id).collect::<Vec<_>>();
+    assert_eq!(dead_letter_ids(repository.find_all_dead_letters(&by_job_name, &page_request(SortOrder::Ascending, None, 10)).await.unwrap()), vec![first.id(), second.id()]);
+    assert_eq!(dead_letter_ids(repository.find_all_dead_letters(&by_job_name, &page_request(SortOrder::Descending, None, 1)).await.unwrap()), vec![second.id()]);
+    let after_first = PageCursor::new(first.created_at(), first.id());
+    assert_eq!(dead_letter_ids(repository.find_all_dead_letters(&by_job_name, &page_request(SortOrder::Ascending, Some(after_first), 10)).await.unwrap()), vec![second.id()]);
+    let by_webhook = DeadLetterFilter::new(Some(second_webhook.id()), None, None, None);
+    assert_eq!(dead_letter_ids(repository.find_all_dead_letters(&by_webhook, &page_request(SortOrder::Ascending, None, 10)).await.unwrap()), vec![second.id()]);
+
+    // A new failure of the same trigger updates its dead letter in place.
+    let replacement = dead_letter(&job_done_watcher, first_trigger_id, first_webhook.id(), None, now())
+        .with_response(Some(vec![("retry-after".to_string(), "60".to_string())]), Some("busy".to_string()));
+    let saved = repository.save_dead_letter(&replacement).await.unwrap();
+    assert_eq!(saved.id(), first.id());
+    assert_eq!(saved.created_at(), first.created_at());
+    assert!(repository.find_dead_letter_by_id(&replacement.id()).await.unwrap().is_none());
+    let found = repository.find_dead_letter_by_id(&first.id()).await.unwrap().unwrap();
+    assert!(found.request().is_none());
+    assert_eq!(found.response_headers(), Some([("retry-after".to_string(), "60".to_string())].as_slice()));
+    assert_eq!(found.response_body(), Some("busy"));
+    assert_eq!(dead_letter_ids(repository.find_all_dead_letters(&by_job_name, &page_request(SortOrder::Ascending, None, 10)).await.unwrap()), vec![first.id(), second.id()]);
+
+    assert!(repository.delete_dead_letter(&second.id()).await.unwrap());
+    assert!(!repository.delete_dead_letter(&second.id()).await.unwrap());
+    repository.delete_dead_letter_by_job_done_trigger_webhook_id(&first_trigger_id).await.unwrap();
+    assert!(repository.find_all_dead_letters(&by_job_name, &page_request(SortOrder::Ascending, None, 10)).await.unwrap().is_empty());
+}
+
+async fn api_keys_are_created_found_by_hash_and_deleted(repository: &impl Repositories) {
+    let reader = ApiKey::new(Uuid::new_v4(), "reader", &Uuid::new_v4().simple().to_string(), vec![ApiScope::Read], None, now() - Duration::minutes(1));
+    let writer = ApiKey::new(Uuid::new_v4(), "writer", &Uuid::new_v4().simple().to_string(), vec![ApiScope::Read, ApiScope::Write], Some("team-a"), now());
+    repository.create_api_key(&reader).await.unwrap();
+    repository.create_api_key(&writer).await.unwrap();
+
+    let found = repository.find_api_key_by_hash(writer.key_hash()).await.unwrap().unwrap();
+    assert_eq!(found.id(), writer.id());
+    assert_eq!(found.name(), "writer");
+    assert_eq!(found.scopes(), [ApiScope::Read, ApiScope::Write]);
+    assert_eq!(found.tenant(), Some("team-a"));
+    assert_eq!(found.created_at(), writer.created_at());
+    assert!(repository.find_api_key_by_hash("unknown").await.unwrap().is_none());
+
+    let duplicate = ApiKey::new(Uuid::new_v4(), "duplicate", reader.key_hash(), vec![ApiScope::Admin], None, now());
+    assert!(repository.create_api_key(&duplicate).await.is_err());
+
+    let api_key_ids = |api_keys: Vec<ApiKey>| api_keys.iter()
+        .map(ApiKey::id)
+        .filter(|id| *id == reader.id() || *id == writer.id())
+        .collect::<Vec<_>>();
+    assert_eq!(api_key_ids(repository.find_all_api_keys().await.unwrap()), vec![reader.id(), writer.id()]);
+
+    assert!(repository.delete_api_key(&reader.id()).await.unwrap());
+    assert!(!repository.delete_api_key(&reader.id()).await.unwrap());
+    assert!(repository.find_api_key_by_hash(reader.key_hash()).await.unwrap().is_none());
+    assert_eq!(api_key_ids(repository.find_all_api_keys().await.unwrap()), vec![writer.id()]);
+}
+
+async fn job_family_watchers_are_created_and_found(repository: &impl Repositories) {
+    let job_family = format!("family-{}", Uuid::new_v4());
+    let job_family_watcher = JobFamilyWatcher::new(
+        Uuid::new_v4(),
+        &job_family,
+        "http://receiver.example.com/family",
+        "{}",
+        "conformance family watcher",
+        vec![JobOutcome::Succeeded, JobOutcome::Failed],
+        JobFamilyWatcherConditions::new(Some(30), Some(3), true),
+    ).unwrap();
+    repository.save_job_family_watcher(job_family_watcher.clone()).await.unwrap();
+
+    let found = repository.find_all_job_family_watchers_by_job_family(&job_family).await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id(), job_family_watcher.id());
+    assert_eq!(found[0].url().to_string(), job_family_watcher.url().to_string());
+    assert_eq!(found[0].on(), &vec![JobOutcome::Succeeded, JobOutcome::Failed]);
+    assert_eq!(found[0].conditions().min_duration_seconds(), Some(30));
+    assert_eq!(found[0].conditions().consecutive_failures(), Some(3));
+    assert!(found[0].conditions().on_state_change());
+
+    assert!(repository.find_all_job_family_watchers_by_job_family(&format!("family-{}", Uuid::new_v4())).await.unwrap().is_empty());
+}
+
+async fn job_family_watchers_are_upserted_by_name(repository: &impl Repositories) {
+    let job_family = format!("family-{}", Uuid::new_v4());
+    let job_family_watcher = |url: &str| JobFamilyWatcher::new(
+        Uuid::new_v4(),
+        &job_family,
+        url,
+        "{}",
+        "conformance family watcher",
+        vec![JobOutcome::Failed],
+        JobFamilyWatcherConditions::default(),
+    ).unwrap();
+    let first = job_family_watcher("http://receiver.example.com/first");
+    let second = job_family_watcher("http://receiver.example.com/second");
+    let other = job_family_watcher("http://receiver.example.com/other").with_name(&format!("{}-other", job_family));
+    repository.save_job_family_watcher(first.clone()).await.unwrap();
+    repository.save_job_family_watcher(second).await.unwrap();
+    repository.save_job_family_watcher(other.clone()).await.unwrap();
+
+    let mut found = repository.find_all_job_family_watchers_by_job_family(&job_family).await.unwrap();
+    found.sort_by_key(|job_family_watcher| job_family_watcher.name().to_string());
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].id(), first.id());
+    assert_eq!(found[0].name(), job_family);
+    assert_eq!(found[0].url().to_string(), "http://receiver.example.com/second");
+    assert_eq!(found[1].id(), other.id());
+}
+
+async fn job_family_state_is_replaced_only_if_unchanged(repository: &impl Repositories) {
+    let job_family = format!("family-{}", Uuid::new_v4());
+    assert!(repository.find_job_family_state(&job_family).await.unwrap().is_none());
+
+    let updated_at = now();
+    let failed_once = JobFamilyState::new(&job_family, JobOutcome::Failed, 1, updated_at);
+    let failed_twice = JobFamilyState::new(&job_family, JobOutcome::Failed, 2, updated_at);
+    let succeeded = JobFamilyState::new(&job_family, JobOutcome::Succeeded, 0, updated_at);
+    assert!(repository.replace_job_family_state(None, &failed_once).await.unwrap());
+    assert!(!repository.replace_job_family_state(None, &failed_once).await.unwrap());
+    assert!(repository.replace_job_family_state(Some(&failed_once), &failed_twice).await.unwrap());
+    assert!(!repository.replace_job_family_state(Some(&failed_once), &succeeded).await.unwrap());
+
+    let found = repository.find_job_family_state(&job_family).await.unwrap().expect("state should exist");
+    assert_eq!(found.last_outcome(), JobOutcome::Failed);
+    assert_eq!(found.consecutive_failures(), 2);
+    assert_eq!(found.updated_at(), updated_at);
+}
+
+async fn job_family_deliveries_are_recorded_and_filtered(repository: &impl Repositories) {
+    let job_family = format!("family-{}", Uuid::new_v4());
+    let job_family_watcher = JobFamilyWatcher::new(
+        Uuid::new_v4(),
+        &job_family,
+        "http://receiver.example.com/family",
+        "{}",
+        "conformance family watcher",
+        vec![JobOutcome::Succeeded],
+        JobFamilyWatcherConditions::default(),
+    ).unwrap();
+    repository.save_job_family_watcher(job_family_watcher.clone()).await.unwrap();
+
+    let created_at = now();
+    let mut deliveries = Vec::new();
+    for hours_ago in [3, 2, 1] {
+        let job_family_delivery = JobFamilyDelivery::new(
+            Uuid::new_v4(),
+            job_family_watcher.id(),
+            &job_family,
+            unique_job_name(),
+            JobOutcome::Succeeded,
+            created_at - Duration::hours(hours_ago),
+        );
+        repository.create_job_family_delivery(&job_family_delivery).await.unwrap();
+        deliveries.push(job_family_delivery);
+    }
+
+    let mut delivered = deliveries[0].clone();
+    delivered.set_status(JobFamilyDeliveryStatus::Delivered);
+    delivered.set_attempts(1);
+    delivered.set_response_status_code(Some(204));
+    delivered.set_last_attempt_at(created_at);
+    repository.update_job_family_delivery(&delivered).await.unwrap();
+
+    let filter = JobFamilyDeliveryFilter::new(Some(job_family.clone()), None, None);
+    let found = repository.find_all_job_family_deliveries(&filter).await.unwrap();
+    assert_eq!(
+        found.iter().map(JobFamilyDelivery::id).collect::<Vec<_>>(),
+        deliveries.iter().rev().map(JobFamilyDelivery::id).collect::<Vec<_>>()
+    );
+
+    let found_delivered = found.iter().find(|found| found.id() == delivered.id()).unwrap();
+    assert_eq!(found_delivered.status(), JobFamilyDeliveryStatus::Delivered);
+    assert_eq!(found_delivered.attempts(), 1);
+    assert_eq!(found_delivered.response_status_code(), Some(204));
+    assert_eq!(found_delivered.last_attempt_at(), Some(created_at));
+    assert_eq!(found_delivered.job_name().as_str(), deliveries[0].job_name().as_str());
+
+    let filter = JobFamilyDeliveryFilter::new(
+        Some(job_family.clone()),
+        Some(created_at - Duration::minutes(150)),
+        Some(created_at - Duration::minutes(90)),
+    );
+    let found = repository.find_all_job_family_deliveries(&filter).await.unwrap();
+    assert_eq!(found.iter().map(JobFamilyDelivery::id).collect::<Vec<_>>(), vec![deliveries[1].id()]);
+
+    let unknown_watcher_delivery = JobFamilyDelivery::new(
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        &job_family,
+        unique_job_name(),
+        JobOutcome::Failed,
+        created_at,
+    );
+    assert!(repository.create_job_family_delivery(&unknown_watcher_delivery).await.is_err());
+}
+
+// Rows are created a century in the past so that cutoffs never match rows of concurrently running tests.
+fn expiry_cutoff() -> DateTime<Utc> {
+    now() - Duration::days(36500)
+}
+
+async fn expired_watchers_are_counted_and_deleted_in_batches(repository: &impl Repositories) {
+    let cutoff = expiry_cutoff();
+    for status in [JobDoneWatcherStatus::Completed, JobDoneWatcherStatus::Failed] {
+        repository.delete_watchers_by_status_created_before(status, cutoff, u32::MAX).await.unwrap();
+    }
+
+    let webhook = create_webhook(repository).await;
+    let mut watchers = Vec::new();
+    for (status, created_at) in [
+        (JobDoneWatcherStatus::Completed, cutoff - Duration::hours(3)),
+        (JobDoneWatcherStatus::Completed, cutoff - Duration::hours(2)),
+        (JobDoneWatcherStatus::Completed, cutoff - Duration::hours(1)),
+        (JobDoneWatcherStatus::Failed, cutoff - Duration::hours(1)),
+        (JobDoneWatcherStatus::Completed, cutoff + Duration::hours(1)),
+    ] {
+        let job_done_watcher = JobDoneWatcher::new(
+            Uuid::new_v4(),
+            unique_job_name(),
+            None,
+            60,
+            vec![JobDoneTriggerWebhook::new(Uuid::new_v4(), webhook.id(), 5, JobDoneTriggerWebhookStatus::Called, Some(created_at))],
+            status,
+            created_at,
+        );
+        repository.create_watcher(&job_done_watcher).await.unwrap();
+        watchers.push(job_done_watcher);
+    }
+
+    assert_eq!(repository.count_watchers_by_status_created_before(JobDoneWatcherStatus::Completed, cutoff).await.unwrap(), 3);
+    assert_eq!(repository.count_watchers_by_status_created_before(JobDoneWatcherStatus::Failed, cutoff).await.unwrap(), 1);
+    assert_eq!(repository.count_watchers_by_status_created_before(JobDoneWatcherStatus::Timeout, cutoff).await.unwrap(), 0);
+
+    assert_eq!(repository.delete_watchers_by_status_created_before(JobDoneWatcherStatus::Completed, cutoff, 2).await.unwrap(), 2);
+    assert!(repository.find_watcher_by_id(&watchers[0].id()).await.unwrap().is_none());
+    assert!(repository.find_watcher_by_id(&watchers[1].id()).await.unwrap().is_none());
+    assert_eq!(repository.count_watchers_by_status_created_before(JobDoneWatcherStatus::Completed, cutoff).await.unwrap(), 1);
+
+    assert_eq!(repository.delete_watchers_by_status_created_before(JobDoneWatcherStatus::Completed, cutoff, 2).await.unwrap(), 1);
+    assert_eq!(repository.delete_watchers_by_status_created_before(JobDoneWatcherStatus::Completed, cutoff, 2).await.unwrap(), 0);
+    assert!(repository.find_watcher_by_id(&watchers[2].id()).await.unwrap().is_none());
+
+    assert_eq!(find_watcher(repository, watchers[3].id()).await.status(), JobDoneWatcherStatus::Failed);
+    assert_eq!(find_watcher(repository, watchers[4].id()).await.job_done_trigger_webhooks().len(), 1);
+
+    repository.delete_watchers_by_status_created_before(JobDoneWatcherStatus::Failed, cutoff, u32::MAX).await.unwrap();
+}
+
+async fn expired_job_family_deliveries_are_counted_and_deleted_in_batches(repository: &impl Repositories) {
+    let cutoff = expiry_cutoff();
+    repository.delete_job_family_deliveries_created_before(cutoff, u32::MAX).await.unwrap();
+
+    let job_family = format!("family-{}", Uuid::new_v4());
+    let job_family_watcher = JobFamilyWatcher::new(
+        Uuid::new_v4(),
+        &job_family,
+        "http://receiver.example.com/family",
+        "{}",
+        "conformance family watcher",
+        vec![JobOutcome::Succeeded],
+        JobFamilyWatcherConditions::default(),
+    ).unwrap();
+    repository.save_job_family_watcher(job_family_watcher.clone()).await.unwrap();
+
+    let mut deliveries = Vec::new();
+    for created_at in [cutoff - Duration::hours(3), cutoff - Duration::hours(2), cutoff - Duration::hours(1), cutoff + Duration::hours(1)] {
+        let job_family_delivery = JobFamilyDelivery::new(
+            Uuid::new_v4(),
+            job_family_watcher.id(),
+            &job_family,
+            unique_job_name(),
+            JobOutcome::Succeeded,
+            created_at,
+        );
+        repository.create_job_family_delivery(&job_family_delivery).await.unwrap();
+        deliveries.push(job_family_delivery);
+    }
+
+    assert_eq!(repository.count_job_family_deliveries_created_before(cutoff).await.unwrap(), 3);
+    assert_eq!(repository.delete_job_family_deliveries_created_before(cutoff, 2).await.unwrap(), 2);
+    assert_eq!(repository.count_job_family_deliveries_created_before(cutoff).await.unwrap(), 1);
+    assert_eq!(repository.delete_job_family_deliveries_created_before(cutoff, 2).await.unwrap(), 1);
+
+    let filter = JobFamilyDeliveryFilter::new(Some(job_family), None, None);
+    let found = repository.find_all_job_family_deliveries(&filter).await.unwrap();
+    assert_eq!(found.iter().map(JobFamilyDelivery::id).collect::<Vec<_>>(), vec![deliveries[3].id()]);
+}
+
+async fn watchers_are_claimed_in_their_namespace(repository: &impl Repositories) {
+    let job_name = unique_job_name();
+    let mut watchers = Vec::new();
+    for watcher_namespace in [Some(namespace("team-a")), None, Some(namespace("team-b"))] {
+        let job_done_watcher = JobDoneWatcher::new(Uuid::new_v4(), job_name.clone(), watcher_namespace, 0, vec![], JobDoneWatcherStatus::Pending, now());
+        repository.create_watcher(&job_done_watcher).await.unwrap();
+        watchers.push(job_done_watcher);
+    }
+
+    assert_eq!(find_watcher(repository, watchers[0].id()).await.namespace(), Some(&namespace("team-a")));
+    assert!(find_watcher(repository, watchers[1].id()).await.namespace().is_none());
+
+    let claimed = repository
+        .update_watchers_status_by_job_name_and_status(&job_name, &namespace("team-a"), JobDoneWatcherStatus::Pending, JobDoneWatcherStatus::Processing)
+        .await
+        .unwrap();
+    assert_eq!(watcher_ids(&claimed).into_iter().collect::<HashSet<_>>(), HashSet::from([watchers[0].id(), watchers[1].id()]));
+    assert_eq!(find_watcher(repository, watchers[2].id()).await.status(), JobDoneWatcherStatus::Pending);
+}
+
+async fn watchers_are_filtered(repository: &impl Repositories) {
+    let first_webhook = create_webhook(repository).await;
+    let second_webhook = create_webhook(repository).await;
+    let job_name = unique_job_name();
+    let created_at = now();
+
+    let mut watchers = Vec::new();
+    for (seconds, watcher_namespace, status, webhook) in [
+        (0, Some(namespace("team-a")), JobDoneWatcherStatus::Pending, &first_webhook),
+        (1, Some(namespace("team-a")), JobDoneWatcherStatus::Completed, &first_webhook),
+        (2, None, JobDoneWatcherStatus::Pending, &second_webhook),
+        (3, None, JobDoneWatcherStatus::Pending, &first_webhook),
+    ] {
+        let job_done_watcher = JobDoneWatcher::new(
+            Uuid::new_v4(),
+            job_name.clone(),
+            watcher_namespace,
+            0,
+            vec![JobDoneTriggerWebhook::new(Uuid::new_v4(), webhook.id(), 0, JobDoneTriggerWebhookStatus::NotCalled, None)],
+            status,
+            created_at + Duration::seconds(seconds),
+        );
+        repository.create_watcher(&job_done_watcher).await.unwrap();
+        watchers.push(job_done_watcher);
+    }
+
+    let find = |job_done_watcher_filter: JobDoneWatcherFilter| async move {
+        watcher_ids(&repository.find_all_watchers(&job_done_watcher_filter, &PageRequest::default()).await.unwrap())
+    };
+    let job_name = Some(job_name);
+
+    assert_eq!(find(JobDoneWatcherFilter::new(job_name.clone(), None, None, None, None, None)).await, watcher_ids(&watchers));
+    assert_eq!(
+        find(JobDoneWatcherFilter::new(job_name.clone(), Some(JobDoneWatcherStatus::Completed), None, None, None, None)).await,
+        vec![watchers[1].id()]
+    );
+    assert_eq!(
+        find(JobDoneWatcherFilter::new(job_name.clone(), None, Some(namespace("team-a")), None, None, None)).await,
+        vec![watchers[0].id(), watchers[1].id()]
+    );
+    assert_eq!(
+        find(JobDoneWatcherFilter::new(job_name.clone(), None, None, Some(second_webhook.id()), None, None)).await,
+        vec![watchers[2].id()]
+    );
+    assert_eq!(
+        find(JobDoneWatcherFilter::new(job_name.clone(), None, None, None, Some(created_at + Duration::seconds(1)), Some(created_at + Duration::seconds(2)))).await,
+        vec![watchers[1].id(), watchers[2].id()]
+    );
+    assert!(find(JobDoneWatcherFilter::new(job_name, None, Some(namespace("team-c")), None, None, None)).await.is_empty());
+}
+
+async fn watchers_are_paginated_in_both_orders(repository: &impl Repositories) {
+    let job_name = unique_job_name();
+    let created_at = now();
+
+    // Two watchers share their created_at, the id breaks the tie.
+    let mut watchers = Vec::new();
+    for seconds in [0, 1, 1, 2, 3] {
+        let job_done_watcher = JobDoneWatcher::new(
+            Uuid::new_v4(),
+            job_name.clone(),
+            None,
+            0,
+            vec![],
+            JobDoneWatcherStatus::Pending,
+            created_at + Duration::seconds(seconds),
+        );
+        repository.create_watcher(&job_done_watcher).await.unwrap();
+        watchers.push(job_done_watcher);
+    }
+    watchers.sort_by_key(|job_done_watcher| (job_done_watcher.created_at(), job_done_watcher.id()));
+
+    let job_done_watcher_filter = JobDoneWatcherFilter::new(Some(job_name), None, None, None, None, None);
+    for sort_order in [SortOrder::Ascending, SortOrder::Descending] {
+        let mut expected = watcher_ids(&watchers);
+        if sort_order == SortOrder::Descending {
+            expected.reverse();
+        }
+
+        let mut listed = Vec::new();
+        let mut after = None;
+        loop {
+            let page = repository.find_all_watchers(&job_done_watcher_filter, &page_request(sort_order, after, 2)).await.unwrap();
+            assert!(page.len() <= 2);
+            listed.extend(watcher_ids(&page));
+            match page.last() {
+                Some(last) if page.len() == 2 => after = Some(PageCursor::new(last.created_at(), last.id())),
+                _ => break,
+            }
+        }
+        assert_eq!(listed, expected);
+    }
+}
+
+async fn webhooks_are_filtered_and_paginated(repository: &impl Repositories) {
+    // A random instant in the past keeps the range free of webhooks created by other tests.
+    let created_at = now() - Duration::days(1000) - Duration::seconds((Uuid::new_v4().as_u128() % 1_000_000_000) as i64);
+    let mut webhooks = Vec::new();
+    for seconds in [0, 1, 2] {
+        let webhook = Webhook::new(
+            Uuid::new_v4(),
+            "http://receiver.example.com/hook".parse().unwrap(),
+            "{}",
+            "paginated webhook",
+            created_at + Duration::seconds(seconds),
+        );
+        repository.create_webhook(&webhook).await.unwrap();
+        webhooks.push(webhook);
+    }
+    let webhook_ids: Vec<Uuid> = webhooks.iter().map(Webhook::id).collect();
+
+    let webhook_filter = WebhookFilter::new(Some(created_at), Some(created_at + Duration::seconds(2)));
+    let first_page = repository.find_all_webhooks(&webhook_filter, &page_request(SortOrder::Ascending, None, 2)).await.unwrap();
+    assert_eq!(first_page.iter().map(Webhook::id).collect::<Vec<_>>(), webhook_ids[..2]);
+
+    let after = PageCursor::new(first_page[1].created_at(), first_page[1].id());
+    let second_page = repository.find_all_webhooks(&webhook_filter, &page_request(SortOrder::Ascending, Some(after), 2)).await.unwrap();
+    assert_eq!(second_page.iter().map(Webhook::id).collect::<Vec<_>>(), webhook_ids[2..]);
+
+    let descending = repository.find_all_webhooks(&webhook_filter, &page_request(SortOrder::Descending, None, 2)).await.unwrap();
+    assert_eq!(descending.iter().map(Webhook::id).collect::<Vec<_>>(), vec![webhook_ids[2], webhook_ids[1]]);
+
+    let webhook_filter = WebhookFilter::new(Some(created_at + Duration::seconds(1)), Some(created_at + Duration::seconds(1)));
+    let found = repository.find_all_webhooks(&webhook_filter, &PageRequest::default()).await.unwrap();
+    assert_eq!(found.iter().map(Webhook::id).collect::<Vec<_>>(), vec![webhook_ids[1]]);
+}
+
+async fn resources_are_scoped_by_tenant(repository: &impl Repositories) {
+    let tenant = format!("team-{}", Uuid::new_v4().simple());
+    let job_name = unique_job_name();
+    let created_at = now();
+
+    let owned_webhook = Webhook::new(Uuid::new_v4(), "http://receiver.example.com/hook".parse().unwrap(), "{}", "owned webhook", created_at)
+        .with_tenant(Some(&tenant));
+    repository.create_webhook(&owned_webhook).await.unwrap();
+    let shared_webhook = create_webhook(repository).await;
+    assert_eq!(repository.find_webhook_by_id(&owned_webhook.id()).await.unwrap().unwrap().tenant(), Some(tenant.as_str()));
+    assert_eq!(repository.find_webhook_by_id(&shared_webhook.id()).await.unwrap().unwrap().tenant(), None);
+
+    let webhook_filter = WebhookFilter::new(None, None).with_tenant(Some(&tenant));
+    let found = repository.find_all_webhooks(&webhook_filter, &PageRequest::default()).await.unwrap();
+    assert_eq!(found.iter().map(Webhook::id).collect::<Vec<_>>(), vec![owned_webhook.id()]);
+
+    let owned_watcher = JobDoneWatcher::new(
+        Uuid::new_v4(),
+        job_name.clone(),
+        Some(namespace(&tenant)),
+        60,
+        vec![JobDoneTriggerWebhook::new(Uuid::new_v4(), owned_webhook.id(), 5, JobDoneTriggerWebhookStatus::Failed, Some(created_at))],
+        JobDoneWatcherStatus::Failed,
+        created_at,
+    ).with_tenant(Some(&tenant));
+    repository.create_watcher(&owned_watcher).await.unwrap();
+    let shared_watcher = create_watcher(repository, &job_name, &[&shared_webhook]).await;
+    assert_eq!(find_watcher(repository, owned_watcher.id()).await.tenant(), Some(tenant.as_str()));
+    assert_eq!(find_watcher(repository, shared_watcher.id()).await.tenant(), None);
+
+    let by_job_name = JobDoneWatcherFilter::new(Some(job_name.clone()), None, None, None, None, None);
+    let found = repository.find_all_watchers(&by_job_name, &PageRequest::default()).await.unwrap();
+    assert_eq!(watcher_ids(&found).into_iter().collect::<HashSet<_>>(), HashSet::from([owned_watcher.id(), shared_watcher.id()]));
+    let found = repository.find_all_watchers(&by_job_name.clone().with_tenant(Some(&tenant)), &PageRequest::default()).await.unwrap();
+    assert_eq!(watcher_ids(&found), vec![owned_watcher.id()]);
+    assert_eq!(found[0].tenant(), Some(tenant.as_str()));
+    assert!(repository.find_all_watchers(&by_job_name.with_tenant(Some("other-team")), &PageRequest::default()).await.unwrap().is_empty());
+
+    let owned_trigger_id = owned_watcher.job_done_trigger_webhooks()[0].id();
+    let owned_dead_letter = dead_letter(&owned_watcher, owned_trigger_id, owned_webhook.id(), None, created_at);
+    repository.save_dead_letter(&owned_dead_letter).await.unwrap();
+    let by_job_name = DeadLetterFilter::new(None, Some(job_name.clone()), None, None);
+    let found = repository.find_all_dead_letters(&by_job_name.clone().with_tenant(Some(&tenant)), &PageRequest::default()).await.unwrap();
+    assert_eq!(found.iter().map(DeadLetter::id).collect::<Vec<_>>(), vec![owned_dead_letter.id()]);
+    assert!(repository.find_all_dead_letters(&by_job_name.with_tenant(Some("other-team")), &PageRequest::default()).await.unwrap().is_empty());
+
+    let job_family = format!("family-{}", Uuid::new_v4());
+    let job_family_watcher = JobFamilyWatcher::new(
+        Uuid::new_v4(),
+        &job_family,
+        "http://receiver.example.com/family",
+        "{}",
+        "owned family watcher",
+        vec![JobOutcome::Succeeded],
+        JobFamilyWatcherConditions::default(),
+    ).unwrap().with_tenant(Some(&tenant));
+    repository.save_job_family_watcher(job_family_watcher.clone()).await.unwrap();
+    let found = repository.find_all_job_family_watchers_by_job_family(&job_family).await.unwrap();
+    assert_eq!(found[0].tenant(), Some(tenant.as_str()));
+
+    let job_family_delivery = JobFamilyDelivery::new(Uuid::new_v4(), job_family_watcher.id(), &job_family, job_name.clone(), JobOutcome::Succeeded, created_at)
+        .with_tenant(job_family_watcher.tenant());
+    repository.create_job_family_delivery(&job_family_delivery).await.unwrap();
+    let by_job_family = JobFamilyDeliveryFilter::new(Some(job_family.clone()), None, None);
+    let found = repository.find_all_job_family_deliveries(&by_job_family.clone().with_tenant(Some(&tenant))).await.unwrap();
+    assert_eq!(found.iter().map(JobFamilyDelivery::id).collect::<Vec<_>>(), vec![job_family_delivery.id()]);
+    assert_eq!(found[0].tenant(), Some(tenant.as_str()));
+    assert!(repository.find_all_job_family_deliveries(&by_job_family.with_tenant(Some("other-team"))).await.unwrap().is_empty());
+}
+
+fn unique_idempotency_key() -> String {
+    format!("key-{}", Uuid::new_v4())
+}
+
+/// A threshold no claim under test is older than, unless the test takes the claim over on purpose.
+fn stale_claimed_before(idempotency_key: &IdempotencyKey) -> DateTime<Utc> {
+    idempotency_key.created_at() - Duration::minutes(1)
+}
+
+async fn idempotency_keys_are_inserted_once_until_expired(repository: &impl Repositories) {
+    let key = unique_idempotency_key();
+    let created_at = now();
+    let idempotency_key = IdempotencyKey::new("POST /webhooks", &key, "fingerprint-1", None, created_at, created_at + Duration::hours(1));
+
+    assert!(repository.insert_idempotency_key(&idempotency_key, stale_claimed_before(&idempotency_key)).await.unwrap());
+    let conflicting = IdempotencyKey::new("POST /webhooks", &key, "fingerprint-2", None, created_at, created_at + Duration::hours(1));
+    assert!(!repository.insert_idempotency_key(&conflicting, stale_claimed_before(&conflicting)).await.unwrap());
+    let other_scope = IdempotencyKey::new("POST /job-done-watchers", &key, "fingerprint-2", None, created_at, created_at + Duration::hours(1));
+    assert!(repository.insert_idempotency_key(&other_scope, stale_claimed_before(&other_scope)).await.unwrap());
+
+    let found = repository.find_idempotency_key("POST /webhooks", &key).await.unwrap().unwrap();
+    assert_eq!(found.fingerprint(), "fingerprint-1");
+    assert_eq!(found.resource_id(), None);
+    assert_eq!(found.created_at(), created_at);
+    assert_eq!(found.expires_at(), created_at + Duration::hours(1));
+
+    let after_expiry = created_at + Duration::hours(2);
+    let reused = IdempotencyKey::new("POST /webhooks", &key, "fingerprint-3", None, after_expiry, after_expiry + Duration::hours(1));
+    assert!(repository.insert_idempotency_key(&reused, stale_claimed_before(&reused)).await.unwrap());
+    let found = repository.find_idempotency_key("POST /webhooks", &key).await.unwrap().unwrap();
+    assert_eq!(found.fingerprint(), "fingerprint-3");
+    assert_eq!(found.created_at(), after_expiry);
+
+    assert!(repository.find_idempotency_key("POST /webhooks", &unique_idempotency_key()).await.unwrap().is_none());
+}
+
+async fn idempotency_keys_are_scoped_by_tenant(repository: &impl Repositories) {
+    let key = unique_idempotency_key();
+    let created_at = now();
+    let longest_tenant = "t".repeat(MAX_TENANT_LENGTH);
+    let tenants = [None, Some("team-a"), Some("team-b"), Some(longest_tenant.as_str())];
+
+    for (index, tenant) in tenants.iter().enumerate() {
+        let scope = IdempotencyKey::tenant_scope("POST /job-done-watchers", *tenant);
+        let idempotency_key = IdempotencyKey::new(&scope, &key, &format!("fingerprint-{}", index), None, created_at, created_at + Duration::hours(1));
+        assert!(repository.insert_idempotency_key(&idempotency_key, stale_claimed_before(&idempotency_key)).await.unwrap());
+    }
+
+    let team_a_scope = IdempotencyKey::tenant_scope("POST /job-done-watchers", Some("team-a"));
+    let resource_id = Uuid::new_v4();
+    repository.update_idempotency_key_resource_id(&team_a_scope, &key, created_at, &resource_id).await.unwrap();
+    for (index, tenant) in tenants.iter().enumerate() {
+        let scope = IdempotencyKey::tenant_scope("POST /job-done-watchers", *tenant);
+        let found = repository.find_idempotency_key(&scope, &key).await.unwrap().unwrap();
+        assert_eq!(found.fingerprint(), format!("fingerprint-{}", index));
+        assert_eq!(found.resource_id(), (*tenant == Some("team-a")).then_some(resource_id));
+    }
+
+    let team_b_scope = IdempotencyKey::tenant_scope("POST /job-done-watchers", Some("team-b"));
+    repository.delete_idempotency_key(&team_b_scope, &key, created_at).await.unwrap();
+    assert!(repository.find_idempotency_key(&team_b_scope, &key).await.unwrap().is_none());
+    let untenanted_scope = IdempotencyKey::tenant_scope("POST /job-done-watchers", None);
+    assert!(repository.find_idempotency_key(&untenanted_scope, &key).await.unwrap().is_some());
+}
+
+async fn idempotency_keys_are_completed_and_released(repository: &impl Repositories) {
+    let key = unique_idempotency_key();
+    let created_at = now();
+    let idempotency_key = IdempotencyKey::new("POST /webhooks", &key, "fingerprint", None, created_at, created_at + Duration::hours(1));
+    assert!(repository.insert_idempotency_key(&idempotency_key, stale_claimed_before(&idempotency_key)).await.unwrap());
+
+    repository.delete_idempotency_key("POST /webhooks", &key, created_at).await.unwrap();
+    assert!(repository.find_idempotency_key("POST /webhooks", &key).await.unwrap().is_none());
+    assert!(repository.insert_idempotency_key(&idempotency_key, stale_claimed_before(&idempotency_key)).await.unwrap());
+
+    let resource_id = Uuid::new_v4();
+    repository.update_idempotency_key_resource_id("POST /webhooks", &key, created_at - Duration::seconds(1), &Uuid::new_v4()).await.unwrap();
+    assert_eq!(repository.find_idempotency_key("POST /webhooks", &key).await.unwrap().unwrap().resource_id(), None);
+    repository.update_idempotency_key_resource_id("POST /webhooks", &key, created_at, &resource_id).await.unwrap();
+    assert_eq!(repository.find_idempotency_key("POST /webhooks", &key).await.unwrap().unwrap().resource_id(), Some(resource_id));
+
+    // Only a key still in progress is released.
+    repository.delete_idempotency_key("POST /webhooks", &key, created_at).await.unwrap();
+    assert_eq!(repository.find_idempotency_key("POST /webhooks", &key).await.unwrap().unwrap().resource_id(), Some(resource_id));
+}
+
+async fn stale_idempotency_key_claims_are_taken_over(repository: &impl Repositories) {
+    let key = unique_idempotency_key();
+    // Claims are kept to the microsecond.
+    let claimed_at = Utc::now().trunc_subsecs(6);
+    let crashed = IdempotencyKey::new("POST /webhooks", &key, "fingerprint", None, claimed_at, claimed_at + Duration::hours(1));
+    assert!(repository.insert_idempotency_key(&crashed, stale_claimed_before(&crashed)).await.unwrap());
+
+    let retried_at = claimed_at + Duration::minutes(5);
+    let retry = IdempotencyKey::new("POST /webhooks", &key, "fingerprint", None, retried_at, retried_at + Duration::hours(1));
+    assert!(!repository.insert_idempotency_key(&retry, claimed_at).await.unwrap());
+    assert!(repository.insert_idempotency_key(&retry, stale_claimed_before(&retry)).await.unwrap());
+
+    // The crashed request coming back neither completes nor releases the claim taken over.
+    repository.update_idempotency_key_resource_id("POST /webhooks", &key, claimed_at, &Uuid::new_v4()).await.unwrap();
+    repository.delete_idempotency_key("POST /webhooks", &key, claimed_at).await.unwrap();
+    let found = repository.find_idempotency_key("POST /webhooks", &key).await.unwrap().unwrap();
+    assert_eq!(found.created_at(), retried_at);
+    assert_eq!(found.resource_id(), None);
+
+    let resource_id = Uuid::new_v4();
+    repository.update_idempotency_key_resource_id("POST /webhooks", &key, retried_at, &resource_id).await.unwrap();
+    let late_retried_at = retried_at + Duration::minutes(5);
+    let late_retry = IdempotencyKey::new("POST /webhooks", &key, "fingerprint", None, late_retried_at, late_retried_at + Duration::hours(1));
+    assert!(!repository.insert_idempotency_key(&late_retry, late_retried_at).await.unwrap());
+    assert_eq!(repository.find_idempotency_key("POST /webhooks", &key).await.unwrap().unwrap().resource_id(), Some(resource_id));
+}
+
+async fn expired_idempotency_keys_are_counted_and_deleted_in_batches(repository: &impl Repositories) {
+    let cutoff = expiry_cutoff();
+    repository.delete_expired_idempotency_keys(cutoff, u32::MAX).await.unwrap();
+
+    let mut keys = Vec::new();
+    for expires_at in [cutoff - Duration::hours(3), cutoff - Duration::hours(2), cutoff - Duration::hours(1), cutoff + Duration::hours(1)] {
+        let key = unique_idempotency_key();
+        let idempotency_key = IdempotencyKey::new("POST /webhooks", &key, "fingerprint", None, expires_at - Duration::hours(1), expires_at);
+        assert!(repository.insert_idempotency_key(&idempotency_key, stale_claimed_before(&idempotency_key)).await.unwrap());
+        keys.push(key);
+    }
+
+    assert_eq!(repository.count_expired_idempotency_keys(cutoff).await.unwrap(), 3);
+    assert_eq!(repository.delete_expired_idempotency_keys(cutoff, 2).await.unwrap(), 2);
+    assert_eq!(repository.count_expired_idempotency_keys(cutoff).await.unwrap(), 1);
+    assert_eq!(repository.delete_expired_idempotency_keys(cutoff, 2).await.unwrap(), 1);
+
+    for key in &keys[..3] {
+        assert!(repository.find_idempotency_key("POST /webhooks", key).await.unwrap().is_none());
+    }
+    assert!(repository.find_idempotency_key("POST /webhooks", &keys[3]).await.unwrap().is_some());
+}